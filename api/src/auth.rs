@@ -0,0 +1,104 @@
+use data_access::api_keys::{ApiKey, ApiKeyClient};
+
+use crate::rate_limit::RouteClass;
+
+#[derive(Debug, thiserror::Error)]
+pub enum AuthError {
+    #[error("Invalid or revoked API key")]
+    InvalidKey,
+    #[error("Daily quota of {quota} requests exceeded for API key {key_id}")]
+    QuotaExceeded { key_id: String, quota: u64 },
+    #[error("Internal error: {0}")]
+    Internal(String),
+}
+
+/// The token of an `Authorization: Bearer <token>` header
+pub fn bearer_token(authorization: Option<&str>) -> Option<&str> {
+    let (scheme, token) = authorization?.trim().split_once(' ')?;
+    if !scheme.eq_ignore_ascii_case("bearer") {
+        return None;
+    }
+    let token = token.trim();
+    (!token.is_empty()).then_some(token)
+}
+
+/// Daily quotas reset at midnight UTC
+pub fn seconds_until_quota_reset() -> u64 {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    86_400 - now % 86_400
+}
+
+/// Resolve a bearer token to its key and count the request against the key's daily quota.
+/// Requests without a route class (MCP handshakes, tool listings) are authenticated but not
+/// counted. Rejected requests still count, so hammering an exhausted key does not reset it.
+pub async fn authenticate(
+    api_keys: &(dyn ApiKeyClient + Send + Sync),
+    token: &str,
+    class: Option<RouteClass>,
+) -> Result<ApiKey, AuthError> {
+    let key = api_keys
+        .authenticate(token)
+        .await
+        .map_err(|e| AuthError::Internal(e.to_string()))?
+        .ok_or(AuthError::InvalidKey)?;
+
+    let Some(class) = class else {
+        return Ok(key);
+    };
+    let used = api_keys
+        .record_usage(&key.id, class.as_str())
+        .await
+        .map_err(|e| AuthError::Internal(e.to_string()))?;
+
+    match key.daily_quota {
+        Some(quota) if used > quota => Err(AuthError::QuotaExceeded {
+            key_id: key.id,
+            quota,
+        }),
+        _ => Ok(key),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data_access::api_keys::{SqliteApiKeyClient, SqliteApiKeyConfig};
+
+    #[test]
+    fn test_bearer_token() {
+        assert_eq!(bearer_token(Some("Bearer tdp_a_b")), Some("tdp_a_b"));
+        assert_eq!(bearer_token(Some("bearer  tdp_a_b ")), Some("tdp_a_b"));
+        assert_eq!(bearer_token(Some("Basic dXNlcjpwdw==")), None);
+        assert_eq!(bearer_token(Some("Bearer ")), None);
+        assert_eq!(bearer_token(None), None);
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_enforces_quota() {
+        let api_keys = SqliteApiKeyClient::new(SqliteApiKeyConfig {
+            filename: ":memory:".to_string(),
+            pool_size: None,
+        })
+        .unwrap();
+        let issued = api_keys.issue_key("bot", None, Some(2)).await.unwrap();
+
+        // Handshakes are not counted
+        authenticate(&api_keys, &issued.token, None).await.unwrap();
+        for _ in 0..2 {
+            authenticate(&api_keys, &issued.token, Some(RouteClass::Search))
+                .await
+                .unwrap();
+        }
+        assert!(matches!(
+            authenticate(&api_keys, &issued.token, Some(RouteClass::Content)).await,
+            Err(AuthError::QuotaExceeded { quota: 2, .. })
+        ));
+        assert!(matches!(
+            authenticate(&api_keys, "tdp_nope_nope", None).await,
+            Err(AuthError::InvalidKey)
+        ));
+    }
+}
@@ -0,0 +1,376 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use configuration::{CrawlerConfig, CrawlerPolicy};
+
+const DEFAULT_SCRAPER_REQUESTS_PER_MINUTE: u32 = 120;
+const DEFAULT_SLOW_DOWN_MS: u64 = 2000;
+/// Number of tracked IPs after which expired rate windows are swept
+const SWEEP_THRESHOLD: usize = 10_000;
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// User agent fragments of LLM training crawlers and AI assistants fetching on a user's behalf
+const LLM_AGENTS: &[&str] = &[
+    "gptbot",
+    "chatgpt-user",
+    "oai-searchbot",
+    "claudebot",
+    "claude-user",
+    "claude-web",
+    "anthropic-ai",
+    "perplexitybot",
+    "perplexity-user",
+    "google-extended",
+    "ccbot",
+    "bytespider",
+    "applebot-extended",
+    "cohere-ai",
+    "meta-externalagent",
+    "mistralai-user",
+];
+
+/// User agent fragments of search engine and SEO crawlers. The generic tokens catch the many
+/// smaller bots that identify themselves, such as `SomeBot/1.0` or `compatible; FooBot;`,
+/// without matching words like "robot" or phone models like "CUBOT".
+const CRAWLERS: &[&str] = &[
+    "googlebot",
+    "bingbot",
+    "duckduckbot",
+    "baiduspider",
+    "yandexbot",
+    "applebot",
+    "slurp",
+    "facebookexternalhit",
+    "twitterbot",
+    "linkedinbot",
+    "ahrefsbot",
+    "semrushbot",
+    "mj12bot",
+    "dotbot",
+    "petalbot",
+    "bot/",
+    "-bot",
+    "bot;",
+    "crawler",
+    "spider",
+];
+
+/// User agent fragments of HTTP libraries and command line tools
+const HTTP_LIBRARIES: &[&str] = &[
+    "curl",
+    "wget",
+    "python-requests",
+    "python-urllib",
+    "aiohttp",
+    "httpx",
+    "go-http-client",
+    "okhttp",
+    "java/",
+    "libwww-perl",
+    "scrapy",
+    "node-fetch",
+    "axios",
+    "headlesschrome",
+];
+
+/// Paths only vulnerability scanners ask for
+const PROBE_PATHS: &[&str] = &[
+    "/wp-",
+    "/.env",
+    "/.git",
+    "/phpmyadmin",
+    "/cgi-bin",
+    "/xmlrpc",
+    ".php",
+    "/actuator",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ClientClass {
+    /// Browsers and clients identified by an API key
+    Human,
+    Crawler,
+    LlmAgent,
+    Scraper,
+}
+
+impl ClientClass {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ClientClass::Human => "human",
+            ClientClass::Crawler => "crawler",
+            ClientClass::LlmAgent => "llm_agent",
+            ClientClass::Scraper => "scraper",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Window {
+    started: Instant,
+    requests: u32,
+}
+
+/// Tags requests with a `ClientClass` from the user agent, the path and the request rate of
+/// the client IP, and looks up the configured policy for each class
+pub struct ClientClassifier {
+    scraper_requests_per_minute: u32,
+    slow_down: Duration,
+    crawler: CrawlerPolicy,
+    llm_agent: CrawlerPolicy,
+    scraper: CrawlerPolicy,
+    windows: Mutex<HashMap<String, Window>>,
+}
+
+impl ClientClassifier {
+    pub fn new(config: &CrawlerConfig) -> Self {
+        Self {
+            scraper_requests_per_minute: config
+                .scraper_requests_per_minute
+                .unwrap_or(DEFAULT_SCRAPER_REQUESTS_PER_MINUTE),
+            slow_down: Duration::from_millis(config.slow_down_ms.unwrap_or(DEFAULT_SLOW_DOWN_MS)),
+            crawler: config.crawler.unwrap_or_default(),
+            llm_agent: config.llm_agent.unwrap_or_default(),
+            scraper: config.scraper.unwrap_or_default(),
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn policy(&self, class: ClientClass) -> CrawlerPolicy {
+        match class {
+            ClientClass::Human => CrawlerPolicy::Allow,
+            ClientClass::Crawler => self.crawler,
+            ClientClass::LlmAgent => self.llm_agent,
+            ClientClass::Scraper => self.scraper,
+        }
+    }
+
+    pub fn slow_down(&self) -> Duration {
+        self.slow_down
+    }
+
+    /// Classify a request. Self-declared crawlers and LLM agents keep their class whatever
+    /// their rate; clients with an API key are not held to the HTTP library rule. Only
+    /// `counted` requests add to the IP's rate, so assets loaded along with a page, such as
+    /// figures, do not turn a reader into a scraper.
+    pub fn classify(
+        &self,
+        user_agent: &str,
+        path: &str,
+        ip: Option<&str>,
+        has_api_key: bool,
+        counted: bool,
+    ) -> ClientClass {
+        self.classify_at(user_agent, path, ip, has_api_key, counted, Instant::now())
+    }
+
+    fn classify_at(
+        &self,
+        user_agent: &str,
+        path: &str,
+        ip: Option<&str>,
+        has_api_key: bool,
+        counted: bool,
+        now: Instant,
+    ) -> ClientClass {
+        let over_rate = ip.is_some_and(|ip| {
+            if counted {
+                self.count_request(ip, now)
+            } else {
+                self.is_over_rate(ip, now)
+            }
+        });
+        let user_agent = user_agent.to_lowercase();
+        let path = path.to_lowercase();
+
+        if LLM_AGENTS.iter().any(|a| user_agent.contains(a)) {
+            ClientClass::LlmAgent
+        } else if CRAWLERS.iter().any(|c| user_agent.contains(c)) {
+            ClientClass::Crawler
+        } else if PROBE_PATHS.iter().any(|p| path.contains(p))
+            || over_rate
+            || (!has_api_key
+                && (user_agent.trim().is_empty()
+                    || HTTP_LIBRARIES.iter().any(|l| user_agent.contains(l))))
+        {
+            ClientClass::Scraper
+        } else {
+            ClientClass::Human
+        }
+    }
+
+    /// Count a request from `ip` and report whether the IP is over the scraper rate
+    fn count_request(&self, ip: &str, now: Instant) -> bool {
+        let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        if windows.len() >= SWEEP_THRESHOLD {
+            windows.retain(|_, w| now.duration_since(w.started) < WINDOW);
+        }
+
+        let window = windows.entry(ip.to_string()).or_insert(Window {
+            started: now,
+            requests: 0,
+        });
+        if now.duration_since(window.started) >= WINDOW {
+            *window = Window {
+                started: now,
+                requests: 0,
+            };
+        }
+        window.requests += 1;
+        window.requests > self.scraper_requests_per_minute
+    }
+
+    /// Whether `ip` is over the scraper rate, without counting a request
+    fn is_over_rate(&self, ip: &str, now: Instant) -> bool {
+        let windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        windows.get(ip).is_some_and(|window| {
+            now.duration_since(window.started) < WINDOW
+                && window.requests > self.scraper_requests_per_minute
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BROWSER: &str = "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/126.0 Safari/537.36";
+
+    fn classifier() -> ClientClassifier {
+        ClientClassifier::new(&CrawlerConfig {
+            scraper_requests_per_minute: Some(3),
+            scraper: Some(CrawlerPolicy::Block),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_classify_by_user_agent_and_path() {
+        let c = classifier();
+        let classify =
+            |ua: &str, path: &str, has_api_key: bool| c.classify(ua, path, None, has_api_key, true);
+
+        assert_eq!(classify(BROWSER, "/api/search", false), ClientClass::Human);
+        assert_eq!(
+            classify(
+                "Mozilla/5.0 (compatible; GPTBot/1.2; +https://openai.com/gptbot)",
+                "/",
+                false
+            ),
+            ClientClass::LlmAgent
+        );
+        assert_eq!(
+            classify(
+                "Mozilla/5.0 (compatible; Googlebot/2.1)",
+                "/pdfs/a.pdf",
+                false
+            ),
+            ClientClass::Crawler
+        );
+        assert_eq!(classify("SomeNewBot/0.1", "/", false), ClientClass::Crawler);
+        assert_eq!(
+            classify(
+                "Mozilla/5.0 (compatible; FooBot; +https://foo.example)",
+                "/",
+                false
+            ),
+            ClientClass::Crawler
+        );
+        assert_eq!(
+            classify(
+                "Mozilla/5.0 (Linux; Android 11; CUBOT X50) AppleWebKit/537.36 Chrome/126.0 Mobile Safari/537.36",
+                "/",
+                false
+            ),
+            ClientClass::Human
+        );
+        assert_eq!(
+            classify(BROWSER, "/wp-login.php", false),
+            ClientClass::Scraper
+        );
+        assert_eq!(
+            classify("python-requests/2.32", "/api/papers", false),
+            ClientClass::Scraper
+        );
+        assert_eq!(classify("", "/api/papers", false), ClientClass::Scraper);
+
+        // Scripts are fine when they identify themselves with an API key
+        assert_eq!(
+            classify("python-requests/2.32", "/api/papers", true),
+            ClientClass::Human
+        );
+    }
+
+    #[test]
+    fn test_request_rate_marks_scrapers() {
+        let c = classifier();
+        let ip = Some("203.0.113.7");
+        let start = Instant::now();
+
+        for _ in 0..3 {
+            assert_eq!(
+                c.classify_at(BROWSER, "/", ip, false, true, start),
+                ClientClass::Human
+            );
+        }
+        assert_eq!(
+            c.classify_at(BROWSER, "/", ip, false, true, start),
+            ClientClass::Scraper
+        );
+        assert_eq!(
+            c.classify_at(BROWSER, "/", Some("198.51.100.1"), false, true, start),
+            ClientClass::Human
+        );
+
+        // Declared crawlers keep their class, and the window resets after a minute
+        assert_eq!(
+            c.classify_at("Googlebot/2.1", "/", ip, false, true, start),
+            ClientClass::Crawler
+        );
+        let later = start + WINDOW;
+        assert_eq!(
+            c.classify_at(BROWSER, "/", ip, false, true, later),
+            ClientClass::Human
+        );
+    }
+
+    #[test]
+    fn test_uncounted_requests_do_not_add_to_rate() {
+        let c = classifier();
+        let ip = Some("203.0.113.8");
+        let start = Instant::now();
+
+        for _ in 0..10 {
+            assert_eq!(
+                c.classify_at(BROWSER, "/images/a.webp", ip, false, false, start),
+                ClientClass::Human
+            );
+        }
+        for _ in 0..3 {
+            assert_eq!(
+                c.classify_at(BROWSER, "/api/papers", ip, false, true, start),
+                ClientClass::Human
+            );
+        }
+
+        // Once over the rate, assets are refused along with everything else
+        assert_eq!(
+            c.classify_at(BROWSER, "/api/papers", ip, false, true, start),
+            ClientClass::Scraper
+        );
+        assert_eq!(
+            c.classify_at(BROWSER, "/images/a.webp", ip, false, false, start),
+            ClientClass::Scraper
+        );
+    }
+
+    #[test]
+    fn test_policies() {
+        let c = classifier();
+        assert_eq!(c.policy(ClientClass::Scraper), CrawlerPolicy::Block);
+        assert_eq!(c.policy(ClientClass::Crawler), CrawlerPolicy::Allow);
+        assert_eq!(c.policy(ClientClass::Human), CrawlerPolicy::Allow);
+        assert_eq!(c.slow_down(), Duration::from_millis(DEFAULT_SLOW_DOWN_MS));
+    }
+}
@@ -1,9 +1,172 @@
+use data_access::embed::EmbedClientError;
+use data_access::metadata::MetadataClientError;
+use data_access::vector::VectorClientError;
+use serde_json::{Value, json};
+
+/// Machine-readable error codes shared by the web API and the MCP server
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    InvalidArgument,
+    InvalidFilter,
+    PaperNotFound,
+    NotFound,
+    Unauthorized,
+    Forbidden,
+    RateLimited,
+    UpstreamUnavailable,
+    Internal,
+}
+
+impl ErrorCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::InvalidArgument => "invalid_argument",
+            ErrorCode::InvalidFilter => "invalid_filter",
+            ErrorCode::PaperNotFound => "paper_not_found",
+            ErrorCode::NotFound => "not_found",
+            ErrorCode::Unauthorized => "unauthorized",
+            ErrorCode::Forbidden => "forbidden",
+            ErrorCode::RateLimited => "rate_limited",
+            ErrorCode::UpstreamUnavailable => "upstream_unavailable",
+            ErrorCode::Internal => "internal",
+        }
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum ApiError {
     #[error("Argument error: {0} : {1}")]
     Argument(String, String),
+    #[error("Invalid filter: {0} : {1}")]
+    InvalidFilter(String, String),
+    #[error("Paper not found: {0}")]
+    PaperNotFound(String),
+    #[error("Not found: {0}")]
+    NotFound(String),
     #[error("Forbidden: {0}")]
     Forbidden(String),
+    /// The embedding provider or vector database failed; retrying later may succeed
+    #[error("Upstream unavailable: {0}")]
+    Unavailable(String),
     #[error("Internal error: {0}")]
     Internal(String),
 }
+
+impl ApiError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            ApiError::Argument(_, _) => ErrorCode::InvalidArgument,
+            ApiError::InvalidFilter(_, _) => ErrorCode::InvalidFilter,
+            ApiError::PaperNotFound(_) => ErrorCode::PaperNotFound,
+            ApiError::NotFound(_) => ErrorCode::NotFound,
+            ApiError::Forbidden(_) => ErrorCode::Forbidden,
+            ApiError::Unavailable(_) => ErrorCode::UpstreamUnavailable,
+            ApiError::Internal(_) => ErrorCode::Internal,
+        }
+    }
+
+    /// The argument an invalid argument or filter error is about
+    pub fn field(&self) -> Option<&str> {
+        match self {
+            ApiError::Argument(field, _) | ApiError::InvalidFilter(field, _) => Some(field),
+            _ => None,
+        }
+    }
+
+    /// `{"error": ..., "code": ..., "field": ...}`, the body of error responses
+    pub fn to_json(&self) -> Value {
+        let mut body = json!({
+            "error": self.to_string(),
+            "code": self.code().as_str(),
+        });
+        if let Some(field) = self.field() {
+            body["field"] = json!(field);
+        }
+        body
+    }
+
+    /// Classify a search failure by the client error it started from
+    pub fn from_search(err: anyhow::Error) -> Self {
+        if let Some(e) = err.downcast_ref::<EmbedClientError>() {
+            return match e {
+                EmbedClientError::OpenAI(_) | EmbedClientError::Unavailable(_) => {
+                    ApiError::Unavailable(e.to_string())
+                }
+                _ => ApiError::Internal(e.to_string()),
+            };
+        }
+        let err = match err.downcast::<VectorClientError>() {
+            Ok(e) => return e.into(),
+            Err(err) => err,
+        };
+        match err.downcast::<MetadataClientError>() {
+            Ok(e) => e.into(),
+            Err(err) => ApiError::Internal(err.to_string()),
+        }
+    }
+}
+
+impl From<VectorClientError> for ApiError {
+    fn from(err: VectorClientError) -> Self {
+        match err {
+            VectorClientError::Unavailable(_) => ApiError::Unavailable(err.to_string()),
+            e => ApiError::Internal(e.to_string()),
+        }
+    }
+}
+
+impl From<MetadataClientError> for ApiError {
+    fn from(err: MetadataClientError) -> Self {
+        match err {
+            MetadataClientError::PaperNotFound(paper) => ApiError::PaperNotFound(paper),
+            MetadataClientError::NotFound(what) => ApiError::NotFound(what),
+            e => ApiError::Internal(e.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codes_and_details() {
+        let err =
+            ApiError::InvalidFilter("league_filter".to_string(), "unknown league".to_string());
+        assert_eq!(err.code(), ErrorCode::InvalidFilter);
+        assert_eq!(
+            err.to_json(),
+            json!({
+                "error": "Invalid filter: league_filter : unknown league",
+                "code": "invalid_filter",
+                "field": "league_filter",
+            })
+        );
+
+        let err = ApiError::from(MetadataClientError::PaperNotFound("a__2024__b".to_string()));
+        assert_eq!(err.code().as_str(), "paper_not_found");
+        assert!(err.to_json().get("field").is_none());
+
+        let err = ApiError::Forbidden("Invalid team code".to_string());
+        assert_eq!(err.code().as_str(), "forbidden");
+    }
+
+    #[test]
+    fn test_from_search() {
+        let unavailable =
+            anyhow::Error::from(VectorClientError::Unavailable("timeout".to_string()));
+        assert_eq!(
+            ApiError::from_search(unavailable).code(),
+            ErrorCode::UpstreamUnavailable
+        );
+
+        let missing = anyhow::Error::from(MetadataClientError::PaperNotFound("x".to_string()));
+        assert_eq!(
+            ApiError::from_search(missing).code(),
+            ErrorCode::PaperNotFound
+        );
+
+        let other = anyhow::anyhow!("boom");
+        assert_eq!(ApiError::from_search(other).code(), ErrorCode::Internal);
+    }
+}
@@ -0,0 +1,588 @@
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::Arc;
+
+use arrow_array::builder::{Float32Builder, ListBuilder, StringBuilder, UInt32Builder};
+use arrow_array::{ArrayRef, RecordBatch, StringArray, UInt32Array};
+use arrow_schema::{DataType, Field, Schema};
+use data_access::metadata::MetadataClient;
+use data_access::vector::VectorClient;
+use data_structures::content::TocEntry;
+use data_structures::file::TDPName;
+use data_structures::filter::Filter;
+use data_structures::intermediate::Chunk;
+use event_processing::dispatcher::EventDispatcher;
+use event_processing::{Event, EventSource, ExportDatasetEvent};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::metadata::KeyValue;
+use parquet::file::properties::WriterProperties;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::ApiError;
+use crate::paper_navigation::compute_breadcrumbs;
+use crate::search::parse_filter;
+
+/// Version of the dataset row layout. Bump it whenever a column is added, removed, renamed or
+/// changes meaning, so analyses can check which layout they were written against.
+pub const DATASET_SCHEMA_VERSION: u32 = 1;
+
+/// Rows per Parquet row group.
+const ROW_GROUP_SIZE: usize = 8192;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DatasetFormat {
+    #[default]
+    Jsonl,
+    Parquet,
+}
+
+impl DatasetFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DatasetFormat::Jsonl => "jsonl",
+            DatasetFormat::Parquet => "parquet",
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct ExportDatasetArgs {
+    #[schemars(description = "Output format: 'jsonl' (default) or 'parquet'.")]
+    pub format: Option<DatasetFormat>,
+    #[schemars(description = "Optional comma-separated league filter, e.g. 'Soccer SmallSize'.")]
+    pub league_filter: Option<String>,
+    #[schemars(description = "Optional comma-separated year filter, e.g. '2023, 2024'.")]
+    pub year_filter: Option<String>,
+    #[schemars(description = "Optional comma-separated team filter, e.g. 'RoboTeam Twente'.")]
+    pub team_filter: Option<String>,
+    #[schemars(description = "Optional comma-separated paper_lyt filter.")]
+    pub paper_lyt_filter: Option<String>,
+    #[schemars(
+        description = "Optional comma-separated content type filter: 'text', 'table', 'image'."
+    )]
+    pub content_type_filter: Option<String>,
+    #[schemars(description = "Include the dense embedding of each chunk. Defaults to false.")]
+    pub include_dense: Option<bool>,
+    #[schemars(description = "Include the sparse embedding of each chunk. Defaults to false.")]
+    pub include_sparse: Option<bool>,
+}
+
+/// One exported chunk. Every row carries all columns; vectors that were not requested are null.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DatasetRow {
+    pub schema_version: u32,
+    pub index_version: u32,
+    pub chunk_id: String,
+    pub paper_lyt: String,
+    pub league: String,
+    pub year: u32,
+    pub team: String,
+    pub content_seq: u32,
+    pub chunk_seq: u32,
+    pub content_type: String,
+    pub title: String,
+    /// Titles of the enclosing sections, root first
+    pub breadcrumbs: Vec<String>,
+    pub image_path: Option<String>,
+    pub text: String,
+    pub dense_embedding: Option<Vec<f32>>,
+    /// Sparse embedding as parallel arrays, sorted by index
+    pub sparse_indices: Option<Vec<u32>>,
+    pub sparse_values: Option<Vec<f32>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DatasetSummary {
+    pub rows: usize,
+    pub papers: usize,
+    pub index_version: u32,
+}
+
+pub async fn export_dataset<W: Write + Send>(
+    metadata_client: Arc<dyn MetadataClient>,
+    vector_client: Arc<dyn VectorClient + Send + Sync>,
+    args: ExportDatasetArgs,
+    writer: W,
+    dispatcher: &EventDispatcher,
+    source: EventSource,
+) -> Result<DatasetSummary, ApiError> {
+    let format = args.format.unwrap_or_default();
+    let include_dense = args.include_dense.unwrap_or(false);
+    let include_sparse = args.include_sparse.unwrap_or(false);
+
+    let filter = parse_filter(
+        args.league_filter.as_deref(),
+        args.year_filter.as_deref(),
+        args.team_filter.as_deref(),
+        args.paper_lyt_filter.as_deref(),
+        args.content_type_filter.as_deref(),
+    )?;
+
+    let mut chunks: Vec<Chunk> = vector_client
+        .get_all_chunks()
+        .await?
+        .into_iter()
+        .filter(|chunk| matches_chunk(&filter, chunk))
+        .collect();
+    // Qdrant returns points in no particular order; sort so identical inputs give identical files
+    chunks.sort_by(|a, b| {
+        (&a.paper_lyt, a.content_seq, a.chunk_seq).cmp(&(&b.paper_lyt, b.content_seq, b.chunk_seq))
+    });
+
+    let mut tocs: HashMap<String, Vec<TocEntry>> = HashMap::new();
+    for chunk in &chunks {
+        if !tocs.contains_key(&chunk.paper_lyt) {
+            let toc = metadata_client.load_toc(chunk.paper_lyt.clone()).await?;
+            tocs.insert(chunk.paper_lyt.clone(), toc);
+        }
+    }
+
+    let index_version = vector_client.index_version();
+    let rows: Vec<DatasetRow> = chunks
+        .into_iter()
+        .map(|chunk| {
+            let breadcrumbs = compute_breadcrumbs(&tocs[&chunk.paper_lyt], chunk.content_seq)
+                .into_iter()
+                .map(|b| b.title)
+                .collect();
+            to_row(
+                chunk,
+                breadcrumbs,
+                index_version,
+                include_dense,
+                include_sparse,
+            )
+        })
+        .collect();
+
+    match format {
+        DatasetFormat::Jsonl => write_jsonl(writer, &rows),
+        DatasetFormat::Parquet => write_parquet(writer, &rows, index_version),
+    }
+    .map_err(|e| ApiError::Internal(format!("Failed to write dataset: {}", e)))?;
+
+    dispatcher.dispatch(
+        source,
+        Event::ExportDataset(ExportDatasetEvent {
+            format: format.as_str().to_string(),
+            schema_version: DATASET_SCHEMA_VERSION,
+            row_count: rows.len(),
+            include_dense,
+            include_sparse,
+            league_filter: args.league_filter,
+            year_filter: args.year_filter,
+            team_filter: args.team_filter,
+            content_type_filter: args.content_type_filter,
+        }),
+    );
+
+    Ok(DatasetSummary {
+        rows: rows.len(),
+        papers: tocs.len(),
+        index_version,
+    })
+}
+
+fn matches_chunk(filter: &Filter, chunk: &Chunk) -> bool {
+    let tdp_name = TDPName::new(chunk.league, chunk.year, chunk.team.clone());
+    if !filter.matches_tdp_name(&tdp_name) {
+        return false;
+    }
+    match &filter.content_types {
+        Some(content_types) => content_types.contains(chunk.content_type.as_str()),
+        None => true,
+    }
+}
+
+fn to_row(
+    chunk: Chunk,
+    breadcrumbs: Vec<String>,
+    index_version: u32,
+    include_dense: bool,
+    include_sparse: bool,
+) -> DatasetRow {
+    let (sparse_indices, sparse_values) = if include_sparse {
+        let mut sparse: Vec<(u32, f32)> = chunk
+            .sparse_embedding
+            .iter()
+            .map(|(i, v)| (*i, *v))
+            .collect();
+        sparse.sort_by_key(|(i, _)| *i);
+        let (indices, values) = sparse.into_iter().unzip();
+        (Some(indices), Some(values))
+    } else {
+        (None, None)
+    };
+
+    DatasetRow {
+        schema_version: DATASET_SCHEMA_VERSION,
+        index_version,
+        chunk_id: chunk.to_uuid().to_string(),
+        league: chunk.league.name().to_string(),
+        year: chunk.year,
+        team: chunk.team.name,
+        content_seq: chunk.content_seq,
+        chunk_seq: chunk.chunk_seq,
+        content_type: chunk.content_type.as_str().to_string(),
+        title: chunk.title,
+        breadcrumbs,
+        image_path: chunk.image_path,
+        text: chunk.text,
+        dense_embedding: include_dense.then_some(chunk.dense_embedding),
+        sparse_indices,
+        sparse_values,
+        paper_lyt: chunk.paper_lyt,
+    }
+}
+
+fn write_jsonl<W: Write>(mut writer: W, rows: &[DatasetRow]) -> anyhow::Result<()> {
+    for row in rows {
+        serde_json::to_writer(&mut writer, row)?;
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+pub fn dataset_schema() -> Schema {
+    let list_of =
+        |data_type: DataType| DataType::List(Arc::new(Field::new("item", data_type, true)));
+
+    Schema::new(vec![
+        Field::new("schema_version", DataType::UInt32, false),
+        Field::new("index_version", DataType::UInt32, false),
+        Field::new("chunk_id", DataType::Utf8, false),
+        Field::new("paper_lyt", DataType::Utf8, false),
+        Field::new("league", DataType::Utf8, false),
+        Field::new("year", DataType::UInt32, false),
+        Field::new("team", DataType::Utf8, false),
+        Field::new("content_seq", DataType::UInt32, false),
+        Field::new("chunk_seq", DataType::UInt32, false),
+        Field::new("content_type", DataType::Utf8, false),
+        Field::new("title", DataType::Utf8, false),
+        Field::new("breadcrumbs", list_of(DataType::Utf8), false),
+        Field::new("image_path", DataType::Utf8, true),
+        Field::new("text", DataType::Utf8, false),
+        Field::new("dense_embedding", list_of(DataType::Float32), true),
+        Field::new("sparse_indices", list_of(DataType::UInt32), true),
+        Field::new("sparse_values", list_of(DataType::Float32), true),
+    ])
+}
+
+fn write_parquet<W: Write + Send>(
+    writer: W,
+    rows: &[DatasetRow],
+    index_version: u32,
+) -> anyhow::Result<()> {
+    let schema = Arc::new(dataset_schema());
+    let properties = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .set_max_row_group_size(ROW_GROUP_SIZE)
+        .set_key_value_metadata(Some(vec![
+            KeyValue::new(
+                "schema_version".to_string(),
+                DATASET_SCHEMA_VERSION.to_string(),
+            ),
+            KeyValue::new("index_version".to_string(), index_version.to_string()),
+        ]))
+        .build();
+    let mut parquet_writer = ArrowWriter::try_new(writer, schema.clone(), Some(properties))?;
+
+    for batch in rows.chunks(ROW_GROUP_SIZE) {
+        parquet_writer.write(&to_record_batch(schema.clone(), batch)?)?;
+    }
+    parquet_writer.close()?;
+    Ok(())
+}
+
+fn to_record_batch(schema: Arc<Schema>, rows: &[DatasetRow]) -> anyhow::Result<RecordBatch> {
+    let u32_column = |f: fn(&DatasetRow) -> u32| -> ArrayRef {
+        Arc::new(rows.iter().map(f).collect::<UInt32Array>())
+    };
+    let str_column = |f: fn(&DatasetRow) -> Option<&str>| -> ArrayRef {
+        Arc::new(rows.iter().map(f).collect::<StringArray>())
+    };
+
+    let mut breadcrumbs = ListBuilder::new(StringBuilder::new());
+    let mut dense = ListBuilder::new(Float32Builder::new());
+    let mut sparse_indices = ListBuilder::new(UInt32Builder::new());
+    let mut sparse_values = ListBuilder::new(Float32Builder::new());
+    for row in rows {
+        breadcrumbs.append_value(row.breadcrumbs.iter().map(Some));
+        dense.append_option(
+            row.dense_embedding
+                .as_ref()
+                .map(|v| v.iter().copied().map(Some)),
+        );
+        sparse_indices.append_option(
+            row.sparse_indices
+                .as_ref()
+                .map(|v| v.iter().copied().map(Some)),
+        );
+        sparse_values.append_option(
+            row.sparse_values
+                .as_ref()
+                .map(|v| v.iter().copied().map(Some)),
+        );
+    }
+
+    let columns: Vec<ArrayRef> = vec![
+        u32_column(|r| r.schema_version),
+        u32_column(|r| r.index_version),
+        str_column(|r| Some(&r.chunk_id)),
+        str_column(|r| Some(&r.paper_lyt)),
+        str_column(|r| Some(&r.league)),
+        u32_column(|r| r.year),
+        str_column(|r| Some(&r.team)),
+        u32_column(|r| r.content_seq),
+        u32_column(|r| r.chunk_seq),
+        str_column(|r| Some(&r.content_type)),
+        str_column(|r| Some(&r.title)),
+        Arc::new(breadcrumbs.finish()),
+        str_column(|r| r.image_path.as_deref()),
+        str_column(|r| Some(&r.text)),
+        Arc::new(dense.finish()),
+        Arc::new(sparse_indices.finish()),
+        Arc::new(sparse_values.finish()),
+    ];
+
+    Ok(RecordBatch::try_new(schema, columns)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data_access::metadata::{MetadataClientError, MockMetadataClient};
+    use data_access::vector::{MockVectorClient, VectorClientError};
+    use data_structures::content::ContentType;
+    use data_structures::file::{League, TeamName};
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use uuid::Uuid;
+
+    fn chunk(league: League, team: &str, content_seq: u32, content_type: ContentType) -> Chunk {
+        let team = TeamName::from_pretty(team);
+        Chunk {
+            dense_embedding: vec![0.5, -0.5],
+            sparse_embedding: HashMap::from([(9, 0.9), (2, 0.2)]),
+            paper_lyt: TDPName::new(league, 2024, team.clone()).get_paper_lyt(),
+            league,
+            year: 2024,
+            team,
+            content_seq,
+            chunk_seq: 0,
+            content_type,
+            title: format!("Item {}", content_seq),
+            image_path: None,
+            text: format!("Text of item {}", content_seq),
+        }
+    }
+
+    fn clients() -> (Arc<dyn MetadataClient>, Arc<dyn VectorClient + Send + Sync>) {
+        let mut mock = MockMetadataClient::new();
+        mock.expect_load_toc().returning(|_| {
+            let toc = vec![
+                TocEntry {
+                    content_seq: 0,
+                    content_type: ContentType::Text,
+                    depth: 1,
+                    title: "Vision".to_string(),
+                },
+                TocEntry {
+                    content_seq: 1,
+                    content_type: ContentType::Text,
+                    depth: 2,
+                    title: "Camera".to_string(),
+                },
+                TocEntry {
+                    content_seq: 2,
+                    content_type: ContentType::Table,
+                    depth: 3,
+                    title: "Specs".to_string(),
+                },
+            ];
+            Box::pin(std::future::ready(Ok(toc)))
+        });
+
+        // Deliberately out of order, the export must sort
+        let chunks = vec![
+            chunk(
+                League::SoccerSmallSize,
+                "RoboTeam Twente",
+                2,
+                ContentType::Table,
+            ),
+            chunk(
+                League::SoccerMidSize,
+                "TIGERs Mannheim",
+                0,
+                ContentType::Text,
+            ),
+            chunk(
+                League::SoccerSmallSize,
+                "RoboTeam Twente",
+                1,
+                ContentType::Text,
+            ),
+        ];
+        let mut vector_client = MockVectorClient::new();
+        vector_client.expect_index_version().return_const(3u32);
+        vector_client
+            .expect_get_all_chunks()
+            .returning(move || Ok(chunks.clone()));
+
+        (Arc::new(mock), Arc::new(vector_client))
+    }
+
+    #[tokio::test]
+    async fn test_export_dataset_jsonl() {
+        let (metadata_client, vector_client) = clients();
+        let mut buffer = Vec::new();
+
+        let summary = export_dataset(
+            metadata_client,
+            vector_client,
+            ExportDatasetArgs {
+                league_filter: Some("Soccer SmallSize".to_string()),
+                include_sparse: Some(true),
+                ..Default::default()
+            },
+            &mut buffer,
+            &EventDispatcher::new(),
+            EventSource::Web,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(summary.rows, 2);
+        assert_eq!(summary.papers, 1);
+        assert_eq!(summary.index_version, 3);
+
+        let rows: Vec<serde_json::Value> = String::from_utf8(buffer)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["schema_version"], DATASET_SCHEMA_VERSION);
+        assert_eq!(rows[0]["content_seq"], 1);
+        assert_eq!(rows[0]["league"], "soccer_smallsize");
+        assert_eq!(rows[0]["team"], "RoboTeam_Twente");
+        assert_eq!(rows[0]["breadcrumbs"], serde_json::json!(["Vision"]));
+        assert_eq!(
+            rows[1]["breadcrumbs"],
+            serde_json::json!(["Vision", "Camera"])
+        );
+        assert!(rows[0]["dense_embedding"].is_null());
+        assert_eq!(rows[0]["sparse_indices"], serde_json::json!([2, 9]));
+        assert_eq!(rows[0]["sparse_values"], serde_json::json!([0.2, 0.9]));
+    }
+
+    #[tokio::test]
+    async fn test_export_dataset_parquet() {
+        let (metadata_client, vector_client) = clients();
+        let path = std::env::temp_dir().join(format!("dataset_{}.parquet", Uuid::new_v4()));
+
+        let summary = export_dataset(
+            metadata_client,
+            vector_client,
+            ExportDatasetArgs {
+                format: Some(DatasetFormat::Parquet),
+                content_type_filter: Some("text".to_string()),
+                include_dense: Some(true),
+                ..Default::default()
+            },
+            std::fs::File::create(&path).unwrap(),
+            &EventDispatcher::new(),
+            EventSource::Web,
+        )
+        .await
+        .unwrap();
+        assert_eq!(summary.rows, 2);
+
+        let builder =
+            ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(&path).unwrap()).unwrap();
+        let metadata = builder
+            .metadata()
+            .file_metadata()
+            .key_value_metadata()
+            .unwrap()
+            .clone();
+        assert!(metadata.iter().any(|kv| kv.key == "schema_version"
+            && kv.value.as_deref() == Some(&DATASET_SCHEMA_VERSION.to_string())));
+        assert_eq!(
+            builder.schema().fields().len(),
+            dataset_schema().fields().len()
+        );
+
+        let batches: Vec<RecordBatch> = builder.build().unwrap().map(|b| b.unwrap()).collect();
+        let n_rows: usize = batches.iter().map(|b| b.num_rows()).sum();
+        assert_eq!(n_rows, 2);
+        let batch = &batches[0];
+        let dense = batch.column_by_name("dense_embedding").unwrap();
+        let sparse = batch.column_by_name("sparse_indices").unwrap();
+        assert_eq!(dense.null_count(), 0);
+        assert_eq!(sparse.null_count(), 2);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_export_dataset_invalid_filter() {
+        let (metadata_client, vector_client) = clients();
+        let result = export_dataset(
+            metadata_client,
+            vector_client,
+            ExportDatasetArgs {
+                year_filter: Some("last year".to_string()),
+                ..Default::default()
+            },
+            Vec::new(),
+            &EventDispatcher::new(),
+            EventSource::Web,
+        )
+        .await;
+        assert!(matches!(result, Err(ApiError::InvalidFilter(field, _)) if field == "year_filter"));
+    }
+
+    #[tokio::test]
+    async fn test_export_dataset_upstream_errors() {
+        let (metadata_client, _) = clients();
+        let mut vector_client = MockVectorClient::new();
+        vector_client.expect_get_all_chunks().returning(|| {
+            Err(VectorClientError::Unavailable(
+                "connection refused".to_string(),
+            ))
+        });
+        let result = export_dataset(
+            metadata_client,
+            Arc::new(vector_client),
+            ExportDatasetArgs::default(),
+            Vec::new(),
+            &EventDispatcher::new(),
+            EventSource::Web,
+        )
+        .await;
+        assert!(matches!(result, Err(ApiError::Unavailable(_))));
+
+        let (_, vector_client) = clients();
+        let mut metadata_client = MockMetadataClient::new();
+        metadata_client.expect_load_toc().returning(|paper| {
+            Box::pin(std::future::ready(Err(MetadataClientError::PaperNotFound(
+                paper,
+            ))))
+        });
+        let result = export_dataset(
+            Arc::new(metadata_client),
+            vector_client,
+            ExportDatasetArgs::default(),
+            Vec::new(),
+            &EventDispatcher::new(),
+            EventSource::Web,
+        )
+        .await;
+        assert!(matches!(result, Err(ApiError::PaperNotFound(_))));
+    }
+}
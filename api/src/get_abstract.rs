@@ -24,8 +24,7 @@ pub async fn get_abstract(
 ) -> Result<String, ApiError> {
     let abstract_text = metadata_client
         .load_paper_abstract(args.paper.clone())
-        .await
-        .map_err(|e| ApiError::Internal(e.to_string()))?;
+        .await?;
 
     dispatcher.dispatch(
         source,
@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use data_access::metadata::MetadataClient;
-use data_structures::content::ContentType;
+use data_structures::content::{ContentType, absolute_url, figure_url, thumbnail_url};
 use event_processing::dispatcher::EventDispatcher;
 use event_processing::{Event, EventSource, GetImageEvent};
 use schemars::JsonSchema;
@@ -19,16 +19,18 @@ pub struct GetImageArgs {
     pub content_seq: u32,
 }
 
+/// Figure and thumbnail URLs are relative to the website, or absolute under `website_url` for
+/// clients such as MCP that cannot resolve relative ones
 pub async fn get_image(
     metadata_client: Arc<dyn MetadataClient>,
     args: GetImageArgs,
+    website_url: Option<&str>,
     dispatcher: &EventDispatcher,
     source: EventSource,
 ) -> Result<String, ApiError> {
     let item = metadata_client
         .load_content_item(args.paper.clone(), args.content_seq)
-        .await
-        .map_err(|e| ApiError::Internal(e.to_string()))?;
+        .await?;
 
     if item.content_type != ContentType::Image {
         return Err(ApiError::Argument(
@@ -41,6 +43,18 @@ pub async fn get_image(
     }
 
     let path = item.image_path.unwrap_or_default();
+    let mut result = format!("{}\nImage: {}", item.title, path);
+    if !path.is_empty() {
+        let resolve = |url: String| match website_url {
+            Some(website_url) => absolute_url(website_url, &url),
+            None => url,
+        };
+        result.push_str(&format!(
+            "\nFigure: {}\nThumbnail: {}",
+            resolve(figure_url(&args.paper, &path)),
+            resolve(thumbnail_url(&args.paper, &path))
+        ));
+    }
 
     dispatcher.dispatch(
         source,
@@ -50,7 +64,7 @@ pub async fn get_image(
         }),
     );
 
-    Ok(format!("{}\nImage: {}", item.title, path))
+    Ok(result)
 }
 
 #[cfg(test)]
@@ -86,12 +100,41 @@ mod tests {
             content_seq: 1,
         };
 
-        let result = get_image(client, args, &EventDispatcher::new(), EventSource::Web)
-            .await
-            .unwrap();
+        let result = get_image(
+            client.clone(),
+            GetImageArgs {
+                paper: args.paper.clone(),
+                content_seq: args.content_seq,
+            },
+            None,
+            &EventDispatcher::new(),
+            EventSource::Web,
+        )
+        .await
+        .unwrap();
 
         assert!(result.contains("Figure 1: Robot"));
         assert!(result.contains("Image: images/robot.png"));
+        assert!(
+            result
+                .contains("Figure: /tdps/soccer_smallsize__2024__RoboTeam_Twente/images/robot.png")
+        );
+        assert!(result.contains(
+            "Thumbnail: /images/soccer_smallsize__2024__RoboTeam_Twente/images/robot.png"
+        ));
+
+        let result = get_image(
+            client,
+            args,
+            Some("https://tdps.example.org"),
+            &EventDispatcher::new(),
+            EventSource::Mcp,
+        )
+        .await
+        .unwrap();
+        assert!(result.contains(
+            "Thumbnail: https://tdps.example.org/images/soccer_smallsize__2024__RoboTeam_Twente/images/robot.png"
+        ));
     }
 
     #[tokio::test]
@@ -121,7 +164,7 @@ mod tests {
             content_seq: 0,
         };
 
-        let result = get_image(client, args, &EventDispatcher::new(), EventSource::Web).await;
+        let result = get_image(client, args, None, &EventDispatcher::new(), EventSource::Web).await;
         assert!(result.is_err());
     }
 }
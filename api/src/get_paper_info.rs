@@ -23,10 +23,7 @@ pub async fn get_paper_info(
     dispatcher: &EventDispatcher,
     source: EventSource,
 ) -> Result<PaperInfo, ApiError> {
-    let info = metadata_client
-        .load_paper_info(args.paper.clone())
-        .await
-        .map_err(|e| ApiError::Internal(e.to_string()))?;
+    let info = metadata_client.load_paper_info(args.paper.clone()).await?;
 
     dispatcher.dispatch(
         source,
@@ -26,8 +26,7 @@ pub async fn get_paragraph(
 ) -> Result<String, ApiError> {
     let item = metadata_client
         .load_content_item(args.paper.clone(), args.content_seq)
-        .await
-        .map_err(|e| ApiError::Internal(e.to_string()))?;
+        .await?;
 
     dispatcher.dispatch(
         source,
@@ -43,7 +42,7 @@ pub async fn get_paragraph(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use data_access::metadata::MockMetadataClient;
+    use data_access::metadata::{MetadataClientError, MockMetadataClient};
     use data_structures::content::{ContentItem, ContentType};
 
     #[tokio::test]
@@ -79,4 +78,30 @@ mod tests {
 
         assert_eq!(result, "This is the introduction text.");
     }
+
+    #[tokio::test]
+    async fn test_get_paragraph_of_unknown_paper() {
+        let mut mock = MockMetadataClient::new();
+        mock.expect_load_content_item().returning(|paper_lyt, _| {
+            Box::pin(std::future::ready(Err(MetadataClientError::PaperNotFound(
+                paper_lyt,
+            ))))
+        });
+
+        let args = GetParagraphArgs {
+            paper: "soccer_smallsize__2024__Nobody".to_string(),
+            content_seq: 0,
+        };
+        let result = get_paragraph(
+            Arc::new(mock),
+            args,
+            &EventDispatcher::new(),
+            EventSource::Web,
+        )
+        .await;
+
+        assert!(
+            matches!(result, Err(ApiError::PaperNotFound(ref paper)) if paper == "soccer_smallsize__2024__Nobody")
+        );
+    }
 }
@@ -22,10 +22,7 @@ pub async fn get_references(
     dispatcher: &EventDispatcher,
     source: EventSource,
 ) -> Result<Vec<String>, ApiError> {
-    let references = metadata_client
-        .load_references(args.paper.clone())
-        .await
-        .map_err(|e| ApiError::Internal(e.to_string()))?;
+    let references = metadata_client.load_references(args.paper.clone()).await?;
 
     dispatcher.dispatch(
         source,
@@ -22,6 +22,10 @@ pub struct GetSectionArgs {
         description = "If true (default), returns the section and all its subsections. If false, returns only the single content item."
     )]
     pub include_children: Option<bool>,
+    #[schemars(
+        description = "The result_set_id of the search this section was found through, if any"
+    )]
+    pub result_set_id: Option<String>,
 }
 
 pub async fn get_section(
@@ -33,30 +37,25 @@ pub async fn get_section(
     let include_children = args.include_children.unwrap_or(true);
 
     // Load ToC for breadcrumbs
-    let toc = metadata_client
-        .load_toc(args.paper.clone())
-        .await
-        .map_err(|e| ApiError::Internal(e.to_string()))?;
+    let toc = metadata_client.load_toc(args.paper.clone()).await?;
 
     let breadcrumbs = compute_breadcrumbs(&toc, args.content_seq);
 
     // Load content items
     let items = if include_children {
         let (start, end) = compute_section_range(&toc, args.content_seq).ok_or_else(|| {
-            ApiError::Internal(format!(
+            ApiError::NotFound(format!(
                 "content_seq {} not found in ToC for {}",
                 args.content_seq, args.paper
             ))
         })?;
         metadata_client
             .load_content_items_range(args.paper.clone(), start, end)
-            .await
-            .map_err(|e| ApiError::Internal(e.to_string()))?
+            .await?
     } else {
         let item = metadata_client
             .load_content_item(args.paper.clone(), args.content_seq)
-            .await
-            .map_err(|e| ApiError::Internal(e.to_string()))?;
+            .await?;
         vec![item]
     };
 
@@ -67,6 +66,7 @@ pub async fn get_section(
             content_seq: args.content_seq,
             include_children,
             items_returned: items.len(),
+            result_set_id: args.result_set_id,
         }),
     );
 
@@ -177,6 +177,7 @@ mod tests {
                 paper: paper_lyt.clone(),
                 content_seq: 1,
                 include_children: Some(true),
+                result_set_id: None,
             },
             &EventDispatcher::new(),
             EventSource::Web,
@@ -226,6 +227,7 @@ mod tests {
                 paper: paper_lyt.clone(),
                 content_seq: 2,
                 include_children: Some(false),
+                result_set_id: None,
             },
             &EventDispatcher::new(),
             EventSource::Web,
@@ -27,8 +27,7 @@ pub async fn get_table(
 ) -> Result<String, ApiError> {
     let item = metadata_client
         .load_content_item(args.paper.clone(), args.content_seq)
-        .await
-        .map_err(|e| ApiError::Internal(e.to_string()))?;
+        .await?;
 
     if item.content_type != ContentType::Table {
         return Err(ApiError::Argument(
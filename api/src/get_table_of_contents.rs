@@ -22,10 +22,7 @@ pub async fn get_table_of_contents(
     dispatcher: &EventDispatcher,
     source: EventSource,
 ) -> Result<String, ApiError> {
-    let toc = metadata_client
-        .load_toc(args.paper.clone())
-        .await
-        .map_err(|e| ApiError::Internal(e.to_string()))?;
+    let toc = metadata_client.load_toc(args.paper.clone()).await?;
 
     let mut result = String::from("seq | depth | type  | title\n----|-------|-------|------\n");
     for entry in &toc {
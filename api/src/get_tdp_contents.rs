@@ -32,10 +32,7 @@ pub async fn get_tdp_contents(
     let team_name = TeamName::new(&args.team);
     let tdp_name = TDPName::new(league, args.year, team_name);
 
-    let markdown = metadata_client
-        .get_tdp_markdown(tdp_name)
-        .await
-        .map_err(|e| ApiError::Internal(e.to_string()))?;
+    let markdown = metadata_client.get_tdp_markdown(tdp_name).await?;
 
     dispatcher.dispatch(
         source,
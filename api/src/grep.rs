@@ -0,0 +1,223 @@
+use std::sync::Arc;
+
+use data_access::metadata::MetadataClient;
+use data_structures::intermediate::{GrepMatch, GrepQuery, GrepScope};
+use event_processing::dispatcher::EventDispatcher;
+use event_processing::{Event, EventSource, GrepEvent};
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::error::ApiError;
+use crate::search::parse_filter;
+
+const DEFAULT_LIMIT: usize = 50;
+const MAX_LIMIT: usize = 500;
+const MAX_PATTERN_LENGTH: usize = 256;
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct GrepArgs {
+    #[schemars(
+        description = "The text or regular expression to find, e.g. 'STM32F7', 'nRF24L01+' or 'ER-Force'. Matched verbatim unless regex is true."
+    )]
+    pub pattern: String,
+
+    #[schemars(
+        description = "Interpret the pattern as a regular expression (Rust regex syntax), e.g. 'STM32F[47]\\d*'. Defaults to false (literal match)."
+    )]
+    pub regex: Option<bool>,
+
+    #[schemars(description = "Match case exactly. Defaults to false.")]
+    pub case_sensitive: Option<bool>,
+
+    #[schemars(
+        description = "Where to look: 'content' (default) matches individual paragraphs/tables and returns their content_seq, 'markdown' matches the raw markdown of whole papers."
+    )]
+    pub scope: Option<GrepScope>,
+
+    #[schemars(description = "Maximum number of matches to return. Defaults to 50, at most 500.")]
+    pub limit: Option<usize>,
+
+    #[schemars(
+        description = "Optional comma-separated league filter. Use exact league names from list_leagues, e.g. 'Soccer SmallSize'."
+    )]
+    pub league_filter: Option<String>,
+
+    #[schemars(description = "Optional comma-separated year filter, e.g. '2023, 2024'.")]
+    pub year_filter: Option<String>,
+
+    #[schemars(
+        description = "Optional comma-separated team filter. Use exact team names from list_teams, e.g. 'RoboTeam Twente'."
+    )]
+    pub team_filter: Option<String>,
+
+    #[schemars(
+        description = "Optional comma-separated filter for specific papers by their paper_lyt identifier, e.g. 'soccer_smallsize__2024__RoboTeam_Twente'."
+    )]
+    pub paper_lyt_filter: Option<String>,
+
+    #[schemars(
+        description = "Optional comma-separated content type filter: 'text', 'table', 'image'. Only applies to the 'content' scope."
+    )]
+    pub content_type_filter: Option<String>,
+}
+
+impl GrepArgs {
+    pub fn to_query(&self) -> Result<GrepQuery, ApiError> {
+        if self.pattern.is_empty() {
+            return Err(ApiError::Argument(
+                "pattern".to_string(),
+                "Pattern must not be empty".to_string(),
+            ));
+        }
+        if self.pattern.chars().count() > MAX_PATTERN_LENGTH {
+            return Err(ApiError::Argument(
+                "pattern".to_string(),
+                format!("Pattern must be at most {MAX_PATTERN_LENGTH} characters"),
+            ));
+        }
+
+        let filter = parse_filter(
+            self.league_filter.as_deref(),
+            self.year_filter.as_deref(),
+            self.team_filter.as_deref(),
+            self.paper_lyt_filter.as_deref(),
+            self.content_type_filter.as_deref(),
+        )?;
+
+        let query = GrepQuery {
+            pattern: self.pattern.clone(),
+            regex: self.regex.unwrap_or(false),
+            case_sensitive: self.case_sensitive.unwrap_or(false),
+            scope: self.scope.unwrap_or_default(),
+            filter: Some(filter),
+            limit: self.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT),
+        };
+
+        query
+            .build_regex()
+            .map_err(|e| ApiError::Argument("pattern".to_string(), e.to_string()))?;
+
+        Ok(query)
+    }
+}
+
+pub async fn grep(
+    metadata_client: Arc<dyn MetadataClient>,
+    args: GrepArgs,
+    dispatcher: &EventDispatcher,
+    source: EventSource,
+) -> Result<Vec<GrepMatch>, ApiError> {
+    let query = args.to_query()?;
+    let regex = query.regex;
+    let case_sensitive = query.case_sensitive;
+    let scope = query.scope;
+
+    let matches = metadata_client.grep(query).await?;
+
+    dispatcher.dispatch(
+        source,
+        Event::Grep(GrepEvent {
+            pattern: args.pattern.clone(),
+            regex,
+            case_sensitive,
+            scope: scope.as_str().to_string(),
+            result_count: matches.len(),
+            league_filter: args.league_filter.clone(),
+            year_filter: args.year_filter.clone(),
+            team_filter: args.team_filter.clone(),
+        }),
+    );
+
+    Ok(matches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data_access::metadata::MockMetadataClient;
+
+    #[tokio::test]
+    async fn test_grep() {
+        let mut mock = MockMetadataClient::new();
+
+        mock.expect_grep()
+            .withf(|query| {
+                query.pattern == "nRF24L01+"
+                    && !query.regex
+                    && !query.case_sensitive
+                    && query.scope == GrepScope::Content
+                    && query.limit == DEFAULT_LIMIT
+                    && query.filter.as_ref().unwrap().years.as_ref().unwrap().contains(&2024)
+            })
+            .returning(|_| {
+                Box::pin(std::future::ready(Ok(vec![GrepMatch {
+                    paper_lyt: "soccer_smallsize__2024__RoboTeam_Twente".to_string(),
+                    content_seq: Some(3),
+                    content_type: Some("text".to_string()),
+                    start: 10,
+                    end: 19,
+                    matched: "nRF24L01+".to_string(),
+                    context: "We use an nRF24L01+ radio".to_string(),
+                }])))
+            });
+
+        let args = GrepArgs {
+            pattern: "nRF24L01+".to_string(),
+            year_filter: Some("2024".to_string()),
+            ..Default::default()
+        };
+
+        let result = grep(Arc::new(mock), args, &EventDispatcher::new(), EventSource::Web)
+            .await
+            .unwrap();
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].content_seq, Some(3));
+        assert_eq!((result[0].start, result[0].end), (10, 19));
+    }
+
+    #[tokio::test]
+    async fn test_grep_argument_errors() {
+        let invalid = vec![
+            GrepArgs {
+                pattern: "".to_string(),
+                ..Default::default()
+            },
+            GrepArgs {
+                pattern: "STM32F[".to_string(),
+                regex: Some(true),
+                ..Default::default()
+            },
+            GrepArgs {
+                pattern: "STM32".to_string(),
+                year_filter: Some("not_a_year".to_string()),
+                ..Default::default()
+            },
+        ];
+
+        for args in invalid {
+            let mock = MockMetadataClient::new();
+            let result = grep(Arc::new(mock), args, &EventDispatcher::new(), EventSource::Web).await;
+            assert!(matches!(
+                result,
+                Err(ApiError::Argument(_, _) | ApiError::InvalidFilter(_, _))
+            ));
+        }
+
+        let args = GrepArgs {
+            pattern: "STM32".to_string(),
+            year_filter: Some("not_a_year".to_string()),
+            ..Default::default()
+        };
+        let err = args.to_query().unwrap_err();
+        assert_eq!(err.code(), crate::error::ErrorCode::InvalidFilter);
+        assert_eq!(err.field(), Some("year_filter"));
+
+        // The same pattern is fine in literal mode
+        let args = GrepArgs {
+            pattern: "STM32F[".to_string(),
+            ..Default::default()
+        };
+        assert!(args.to_query().is_ok());
+    }
+}
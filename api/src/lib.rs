@@ -1,4 +1,7 @@
+pub mod auth;
+pub mod crawler;
 pub mod error;
+pub mod export_dataset;
 pub mod get_abstract;
 pub mod get_paper_info;
 pub mod get_image;
@@ -8,12 +11,14 @@ pub mod get_section;
 pub mod get_table;
 pub mod get_table_of_contents;
 pub mod get_tdp_contents;
+pub mod grep;
 pub mod list_leagues;
 pub mod list_papers;
 pub mod list_teams;
 pub mod list_years;
 pub mod paper_filter;
 pub mod paper_navigation;
+pub mod rate_limit;
 pub mod search;
 pub mod suggestion;
 pub mod get_league_info;
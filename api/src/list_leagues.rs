@@ -12,10 +12,7 @@ pub async fn list_leagues(
     dispatcher: &EventDispatcher,
     source: EventSource,
 ) -> Result<Vec<League>, ApiError> {
-    let leagues = metadata_client
-        .load_leagues()
-        .await
-        .map_err(|err| ApiError::Internal(err.to_string()))?;
+    let leagues = metadata_client.load_leagues().await?;
 
     dispatcher.dispatch(
         source,
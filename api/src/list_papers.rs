@@ -14,10 +14,7 @@ pub async fn list_papers(
     dispatcher: &EventDispatcher,
     source: EventSource,
 ) -> Result<Vec<TDPName>, ApiError> {
-    let papers = metadata_client
-        .load_tdps()
-        .await
-        .map_err(|err| ApiError::Internal(err.to_string()))?;
+    let papers = metadata_client.load_tdps().await?;
 
     let result = filter.filter_papers(papers)?;
 
@@ -22,10 +22,7 @@ pub async fn list_teams(
     dispatcher: &EventDispatcher,
     source: EventSource,
 ) -> Result<Vec<TeamName>, ApiError> {
-    let mut teams = metadata_client
-        .load_teams()
-        .await
-        .map_err(|err| ApiError::Internal(err.to_string()))?;
+    let mut teams = metadata_client.load_teams().await?;
 
     if let Some(hint) = &args.hint {
         let team_names = teams.iter().map(Into::into).collect();
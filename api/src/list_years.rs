@@ -14,10 +14,7 @@ pub async fn list_years(
     dispatcher: &EventDispatcher,
     source: EventSource,
 ) -> Result<Vec<u32>, ApiError> {
-    let papers = metadata_client
-        .load_tdps()
-        .await
-        .map_err(|err| ApiError::Internal(err.to_string()))?;
+    let papers = metadata_client.load_tdps().await?;
 
     let filtered = filter.filter_papers(papers)?;
     let years: BTreeSet<u32> = filtered.iter().map(|tdp| tdp.year).collect();
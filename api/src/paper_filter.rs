@@ -22,7 +22,7 @@ impl PaperFilter {
     pub fn matches(&self, paper: &TDPName) -> Result<bool, ApiError> {
         if let Some(ref league_str) = self.league {
             let league = League::try_from(league_str.as_str())
-                .map_err(|e| ApiError::Argument("league".to_string(), e.to_string()))?;
+                .map_err(|e| ApiError::InvalidFilter("league".to_string(), e.to_string()))?;
             if paper.league != league {
                 return Ok(false);
             }
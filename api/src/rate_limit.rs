@@ -0,0 +1,233 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use configuration::{RateLimitConfig, RateLimitRule};
+
+/// Number of tracked clients after which idle buckets are swept
+const SWEEP_THRESHOLD: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RouteClass {
+    Search,
+    Content,
+    Write,
+}
+
+impl RouteClass {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RouteClass::Search => "search",
+            RouteClass::Content => "content",
+            RouteClass::Write => "write",
+        }
+    }
+
+    /// Classify an MCP tool by name
+    pub fn for_tool(tool: &str) -> Self {
+        match tool {
+            "search" | "grep" => RouteClass::Search,
+            "submit_suggestion" => RouteClass::Write,
+            _ => RouteClass::Content,
+        }
+    }
+}
+
+/// Who a request is counted against. An upstream authentication layer that verified an API
+/// key can insert `ClientId::ApiKey` as a request extension; otherwise clients are told apart
+/// by IP.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ClientId {
+    Ip(String),
+    ApiKey(String),
+    Unknown,
+}
+
+/// The client IP as seen by the reverse proxy: the first `x-forwarded-for` entry, falling back
+/// to `x-real-ip`. Clients connecting directly are told apart by their socket `peer` address.
+pub fn client_ip(
+    forwarded_for: Option<&str>,
+    real_ip: Option<&str>,
+    peer: Option<IpAddr>,
+) -> Option<String> {
+    forwarded_for
+        .and_then(|s| s.split(',').next())
+        .map(|s| s.trim().to_string())
+        .or_else(|| real_ip.map(|s| s.to_string()))
+        .or_else(|| peer.map(|ip| ip.to_string()))
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Limit {
+    capacity: f64,
+    per_second: f64,
+}
+
+impl From<RateLimitRule> for Limit {
+    fn from(rule: RateLimitRule) -> Self {
+        Self {
+            capacity: rule.burst.unwrap_or(rule.requests_per_minute).max(1) as f64,
+            per_second: rule.requests_per_minute as f64 / 60.0,
+        }
+    }
+}
+
+/// Token bucket rate limiter, one bucket per route class and client
+pub struct RateLimiter {
+    search: Option<Limit>,
+    content: Option<Limit>,
+    write: Option<Limit>,
+    buckets: Mutex<HashMap<(RouteClass, ClientId), Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(config: &RateLimitConfig) -> Self {
+        Self {
+            search: config.search.map(Limit::from),
+            content: config.content.map(Limit::from),
+            write: config.write.map(Limit::from),
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn limit(&self, class: RouteClass) -> Option<Limit> {
+        match class {
+            RouteClass::Search => self.search,
+            RouteClass::Content => self.content,
+            RouteClass::Write => self.write,
+        }
+    }
+
+    /// Take a token for `client`. Returns how long to wait when the bucket is empty.
+    pub fn check(&self, class: RouteClass, client: &ClientId) -> Result<(), Duration> {
+        self.check_at(class, client, Instant::now())
+    }
+
+    fn check_at(&self, class: RouteClass, client: &ClientId, now: Instant) -> Result<(), Duration> {
+        let Some(limit) = self.limit(class) else {
+            return Ok(());
+        };
+
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        if buckets.len() >= SWEEP_THRESHOLD {
+            // A bucket that has refilled completely is indistinguishable from a new one
+            buckets.retain(|(class, _), bucket| {
+                self.limit(*class).is_some_and(|l| {
+                    bucket.tokens + now.duration_since(bucket.updated).as_secs_f64() * l.per_second
+                        < l.capacity
+                })
+            });
+        }
+
+        let bucket = buckets.entry((class, client.clone())).or_insert(Bucket {
+            tokens: limit.capacity,
+            updated: now,
+        });
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * limit.per_second).min(limit.capacity);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            return Ok(());
+        }
+        if limit.per_second <= 0.0 {
+            return Err(Duration::from_secs(60));
+        }
+        Err(Duration::from_secs_f64(
+            (1.0 - bucket.tokens) / limit.per_second,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter() -> RateLimiter {
+        RateLimiter::new(&RateLimitConfig {
+            search: Some(RateLimitRule {
+                requests_per_minute: 60,
+                burst: Some(2),
+            }),
+            content: None,
+            write: Some(RateLimitRule {
+                requests_per_minute: 6,
+                burst: None,
+            }),
+        })
+    }
+
+    #[test]
+    fn test_burst_then_refill() {
+        let limiter = limiter();
+        let client = ClientId::Ip("203.0.113.7".to_string());
+        let start = Instant::now();
+
+        assert!(limiter.check_at(RouteClass::Search, &client, start).is_ok());
+        assert!(limiter.check_at(RouteClass::Search, &client, start).is_ok());
+        let retry = limiter
+            .check_at(RouteClass::Search, &client, start)
+            .unwrap_err();
+        assert_eq!(retry, Duration::from_secs(1));
+
+        // One token per second at 60 requests per minute
+        let later = start + Duration::from_secs(1);
+        assert!(limiter.check_at(RouteClass::Search, &client, later).is_ok());
+        assert!(
+            limiter
+                .check_at(RouteClass::Search, &client, later)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_buckets_are_per_client_and_class() {
+        let limiter = limiter();
+        let a = ClientId::Ip("203.0.113.7".to_string());
+        let b = ClientId::ApiKey("key-1".to_string());
+        let now = Instant::now();
+
+        for _ in 0..2 {
+            limiter.check_at(RouteClass::Search, &a, now).unwrap();
+        }
+        assert!(limiter.check_at(RouteClass::Search, &a, now).is_err());
+        assert!(limiter.check_at(RouteClass::Search, &b, now).is_ok());
+
+        // Burst defaults to the per-minute rate, content is unlimited
+        for _ in 0..6 {
+            limiter.check_at(RouteClass::Write, &a, now).unwrap();
+        }
+        assert!(limiter.check_at(RouteClass::Write, &a, now).is_err());
+        for _ in 0..100 {
+            limiter.check_at(RouteClass::Content, &a, now).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_client_ip() {
+        assert_eq!(
+            client_ip(Some("203.0.113.7, 10.0.0.1"), Some("10.0.0.2"), None),
+            Some("203.0.113.7".to_string())
+        );
+        let peer = "198.51.100.4".parse().ok();
+        assert_eq!(
+            client_ip(None, Some("10.0.0.2"), peer),
+            Some("10.0.0.2".to_string())
+        );
+        assert_eq!(
+            client_ip(None, None, peer),
+            Some("198.51.100.4".to_string())
+        );
+        assert_eq!(client_ip(None, None, None), None);
+        assert_eq!(RouteClass::for_tool("grep"), RouteClass::Search);
+        assert_eq!(RouteClass::for_tool("get_section"), RouteClass::Content);
+    }
+}
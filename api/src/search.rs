@@ -5,10 +5,12 @@ use data_structures::{
     filter::Filter,
 };
 use event_processing::dispatcher::EventDispatcher;
-use event_processing::{Event, EventSource, SearchEvent};
+use event_processing::{Event, EventSource, SearchEvent, SearchEventResult};
 use schemars::JsonSchema;
 use serde::Deserialize;
 
+use crate::error::ApiError;
+
 #[derive(thiserror::Error, Debug)]
 pub enum SearchError {
     #[error("Failed to parse league: {0}")]
@@ -19,6 +21,23 @@ pub enum SearchError {
     ContentTypeParseError(String),
 }
 
+impl SearchError {
+    /// The filter argument the error is about
+    pub fn field(&self) -> &'static str {
+        match self {
+            SearchError::LeagueParseError(_) => "league_filter",
+            SearchError::YearParseError(_) => "year_filter",
+            SearchError::ContentTypeParseError(_) => "content_type_filter",
+        }
+    }
+}
+
+impl From<SearchError> for ApiError {
+    fn from(err: SearchError) -> Self {
+        ApiError::InvalidFilter(err.field().to_string(), err.to_string())
+    }
+}
+
 #[derive(Debug, Default, Deserialize, JsonSchema)]
 pub struct SearchArgs {
     #[schemars(
@@ -61,44 +80,62 @@ pub struct SearchArgs {
 
 impl SearchArgs {
     pub fn to_filter(&self) -> Result<Option<Filter>, SearchError> {
-        let mut filter = Filter::default();
+        parse_filter(
+            self.league_filter.as_deref(),
+            self.year_filter.as_deref(),
+            self.team_filter.as_deref(),
+            self.paper_lyt_filter.as_deref(),
+            self.content_type_filter.as_deref(),
+        )
+        .map(Some)
+    }
+}
 
-        if let Some(league_filter) = &self.league_filter {
-            for league in league_filter.split(",") {
-                filter.add_league(League::try_from(league.trim())?);
-            }
+/// Builds a [`Filter`] from the comma-separated filter strings shared by search-like endpoints.
+pub fn parse_filter(
+    league_filter: Option<&str>,
+    year_filter: Option<&str>,
+    team_filter: Option<&str>,
+    paper_lyt_filter: Option<&str>,
+    content_type_filter: Option<&str>,
+) -> Result<Filter, SearchError> {
+    let mut filter = Filter::default();
+
+    if let Some(league_filter) = league_filter {
+        for league in league_filter.split(",") {
+            filter.add_league(League::try_from(league.trim())?);
         }
+    }
 
-        if let Some(year_filter) = &self.year_filter {
-            for year in year_filter.split(",") {
-                filter.add_year(year.trim().parse()?);
-            }
+    if let Some(year_filter) = year_filter {
+        for year in year_filter.split(",") {
+            filter.add_year(year.trim().parse()?);
         }
+    }
 
-        if let Some(team_filter) = &self.team_filter {
-            for team in team_filter.split(",") {
-                filter.add_team(TeamName::new(team.trim()));
-            }
+    if let Some(team_filter) = team_filter {
+        for team in team_filter.split(",") {
+            filter.add_team(TeamName::new(team.trim()));
         }
+    }
 
-        if let Some(paper_lyt_filter) = &self.paper_lyt_filter {
-            for paper_lyt in paper_lyt_filter.split(",") {
-                filter.add_paper_lyt(paper_lyt.trim().to_string());
-            }
+    if let Some(paper_lyt_filter) = paper_lyt_filter {
+        for paper_lyt in paper_lyt_filter.split(",") {
+            filter.add_paper_lyt(paper_lyt.trim().to_string());
         }
+    }
 
-        if let Some(content_type_filter) = &self.content_type_filter {
-            for ct in content_type_filter.split(",") {
-                let ct = ct.trim().to_lowercase();
-                if !["text", "table", "image"].contains(&ct.as_str()) {
-                    return Err(SearchError::ContentTypeParseError(ct));
-                }
-                filter.add_content_type(ct);
+    if let Some(content_type_filter) = content_type_filter {
+        for ct in content_type_filter.split(",") {
+            let ct = ct.trim().to_lowercase();
+            if !["text", "table", "image"].contains(&ct.as_str()) {
+                return Err(SearchError::ContentTypeParseError(ct));
             }
+            filter.add_content_type(ct);
         }
-
-        Ok(Some(filter))
     }
+
+    Ok(filter)
 }
 
 pub async fn search(
@@ -106,7 +143,7 @@ pub async fn search(
     args: SearchArgs,
     dispatcher: &EventDispatcher,
     source: EventSource,
-) -> anyhow::Result<data_structures::intermediate::SearchResult> {
+) -> Result<data_structures::intermediate::SearchResult, ApiError> {
     let search_type = args.search_type.unwrap_or_default();
     let search_type_str = match search_type {
         EmbedType::DENSE => "dense",
@@ -120,7 +157,8 @@ pub async fn search(
             args.to_filter()?,
             search_type.into(),
         )
-        .await?;
+        .await
+        .map_err(ApiError::from_search)?;
 
     dispatcher.dispatch(
         source,
@@ -132,6 +170,16 @@ pub async fn search(
             year_filter: args.year_filter.clone(),
             team_filter: args.team_filter.clone(),
             content_type_filter: args.content_type_filter.clone(),
+            result_set_id: search_result.result_set_id.clone(),
+            results: search_result
+                .chunks
+                .iter()
+                .map(|c| SearchEventResult {
+                    paper_lyt: c.paper_lyt.clone(),
+                    content_seq: c.content_seq,
+                })
+                .collect(),
+            degraded: search_result.degraded,
         }),
     );
 
@@ -4,18 +4,19 @@ use data_access::registry::RegistryClient;
 use data_structures::file::TeamName;
 use event_processing::dispatcher::EventDispatcher;
 use event_processing::{Event, EventSource, UpdateTeamInfoEvent};
+use schemars::JsonSchema;
 use serde::Deserialize;
 
 use crate::error::ApiError;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct UpdateTeamInfoArgs {
     pub team: String,
     pub code: String,
     pub entries: Vec<UpdateEntry>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct UpdateEntry {
     pub key: String,
     pub value: String,
@@ -113,7 +114,9 @@ mod tests {
             filename: ":memory:".to_string(),
             master_password: Some(master_pw.to_string()),
             salt: None,
-        }))
+            pool_size: None,
+        })
+        .unwrap())
     }
 
     fn make_dispatcher() -> EventDispatcher {
@@ -13,6 +13,16 @@ pub struct AppConfig {
     pub data_processing: DataProcessingConfig,
     pub event_processing: Option<EventProcessingConfig>,
     pub website_url: Option<String>,
+    /// Per-client throttling for the web and MCP servers. Unlimited when unset.
+    pub rate_limit: Option<RateLimitConfig>,
+    /// Crawler classification policies for the web server. Every class is allowed when unset.
+    pub crawlers: Option<CrawlerConfig>,
+    /// Caching of the PDFs and TDP files served by the web server
+    pub files: Option<FilesConfig>,
+    /// Bind addresses and frontends of the `serve` binary
+    pub server: Option<ServerConfig>,
+    /// OpenTelemetry export of the servers' spans. Only logged when unset.
+    pub tracing: Option<TracingConfig>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -26,6 +36,182 @@ pub struct ActivityListenerConfig {
     pub sqlite: Option<SqliteListenerConfig>,
 }
 
+/// Token bucket limits per route class. A class without a rule is not limited.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct RateLimitConfig {
+    /// Search and grep
+    pub search: Option<RateLimitRule>,
+    /// Papers, sections, lists and static TDP/PDF files
+    pub content: Option<RateLimitRule>,
+    /// Registry updates and suggestions
+    pub write: Option<RateLimitRule>,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+pub struct RateLimitRule {
+    pub requests_per_minute: u32,
+    /// Requests a client may make in a burst. Defaults to `requests_per_minute`.
+    pub burst: Option<u32>,
+}
+
+/// What the web server does with requests of a client class. Classes without a policy are
+/// allowed.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct CrawlerConfig {
+    /// Requests per minute from one IP above which a client counts as a suspicious scraper.
+    /// Defaults to 120.
+    pub scraper_requests_per_minute: Option<u32>,
+    /// Delay added to requests under the `slow` policy. Defaults to 2000.
+    pub slow_down_ms: Option<u64>,
+    /// Self-declared search engine and SEO crawlers
+    pub crawler: Option<CrawlerPolicy>,
+    /// Self-declared LLM training crawlers and AI assistants fetching pages
+    pub llm_agent: Option<CrawlerPolicy>,
+    /// HTTP libraries, vulnerability probes and clients exceeding the request rate
+    pub scraper: Option<CrawlerPolicy>,
+}
+
+const DEFAULT_PDF_CACHE_CONTROL: &str = "public, max-age=86400";
+const DEFAULT_TDPS_CACHE_CONTROL: &str = "public, max-age=3600";
+const DEFAULT_IMAGES_CACHE_CONTROL: &str = "public, max-age=604800";
+const DEFAULT_IMAGE_CACHE_DIR: &str = "data/image_cache";
+const DEFAULT_THUMBNAIL_WIDTH: u32 = 320;
+const DEFAULT_MAX_IMAGE_WIDTH: u32 = 1600;
+
+/// `Cache-Control` headers of the static file routes and the resized figure cache
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct FilesConfig {
+    /// For `/pdfs/*`. Defaults to "public, max-age=86400".
+    pub pdf_cache_control: Option<String>,
+    /// For `/tdps/*`, the markdown and the images of a paper. Defaults to
+    /// "public, max-age=3600".
+    pub tdps_cache_control: Option<String>,
+    /// For `/images/*`, the resized figures. Defaults to "public, max-age=604800".
+    pub images_cache_control: Option<String>,
+    /// Where resized figures are cached. Defaults to "data/image_cache".
+    pub image_cache_dir: Option<String>,
+    /// Width of figure thumbnails when `/images/*` is requested without `?w=`. Defaults to 320.
+    pub thumbnail_width: Option<u32>,
+    /// Largest width `/images/*` resizes to. Defaults to 1600.
+    pub max_image_width: Option<u32>,
+}
+
+impl FilesConfig {
+    pub fn pdf_cache_control(&self) -> &str {
+        self.pdf_cache_control.as_deref().unwrap_or(DEFAULT_PDF_CACHE_CONTROL)
+    }
+
+    pub fn tdps_cache_control(&self) -> &str {
+        self.tdps_cache_control.as_deref().unwrap_or(DEFAULT_TDPS_CACHE_CONTROL)
+    }
+
+    pub fn images_cache_control(&self) -> &str {
+        self.images_cache_control
+            .as_deref()
+            .unwrap_or(DEFAULT_IMAGES_CACHE_CONTROL)
+    }
+
+    pub fn image_cache_dir(&self) -> &str {
+        self.image_cache_dir.as_deref().unwrap_or(DEFAULT_IMAGE_CACHE_DIR)
+    }
+
+    pub fn thumbnail_width(&self) -> u32 {
+        self.thumbnail_width.unwrap_or(DEFAULT_THUMBNAIL_WIDTH)
+    }
+
+    pub fn max_image_width(&self) -> u32 {
+        self.max_image_width.unwrap_or(DEFAULT_MAX_IMAGE_WIDTH)
+    }
+}
+
+const DEFAULT_WEB_ADDR: &str = "0.0.0.0:50000";
+const DEFAULT_MCP_ADDR: &str = "0.0.0.0:50001";
+
+/// What `serve` starts and where. Command line flags take precedence.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct ServerConfig {
+    /// Frontends to start. Defaults to both.
+    pub frontends: Option<Vec<Frontend>>,
+    /// Defaults to "0.0.0.0:50000".
+    pub web_addr: Option<String>,
+    /// Defaults to "0.0.0.0:50001".
+    pub mcp_addr: Option<String>,
+}
+
+impl ServerConfig {
+    pub fn frontends(&self) -> Vec<Frontend> {
+        self.frontends
+            .clone()
+            .unwrap_or_else(|| vec![Frontend::Web, Frontend::Mcp])
+    }
+
+    pub fn web_addr(&self) -> &str {
+        self.web_addr.as_deref().unwrap_or(DEFAULT_WEB_ADDR)
+    }
+
+    pub fn mcp_addr(&self) -> &str {
+        self.mcp_addr.as_deref().unwrap_or(DEFAULT_MCP_ADDR)
+    }
+}
+
+const DEFAULT_SERVICE_NAME: &str = "tdp-search";
+
+/// Where `serve` sends its spans. Nothing is exported without an `otlp_endpoint`.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct TracingConfig {
+    /// OTLP over HTTP collector, e.g. "http://localhost:4318" for a local Jaeger
+    pub otlp_endpoint: Option<String>,
+    /// Defaults to "tdp-search".
+    pub service_name: Option<String>,
+    /// Fraction of traces to keep, between 0 and 1. Defaults to 1. Traces started by a caller
+    /// that sent a `traceparent` header follow the caller's sampling decision.
+    pub sample_ratio: Option<f64>,
+}
+
+impl TracingConfig {
+    pub fn service_name(&self) -> &str {
+        self.service_name.as_deref().unwrap_or(DEFAULT_SERVICE_NAME)
+    }
+
+    pub fn sample_ratio(&self) -> f64 {
+        self.sample_ratio.unwrap_or(1.0).clamp(0.0, 1.0)
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Frontend {
+    /// REST API, file routes and the static frontend
+    Web,
+    /// MCP over streamable HTTP
+    Mcp,
+}
+
+impl TryFrom<&str> for Frontend {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "web" => Ok(Frontend::Web),
+            "mcp" => Ok(Frontend::Mcp),
+            other => Err(format!("Unknown frontend: '{}'", other)),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CrawlerPolicy {
+    #[default]
+    Allow,
+    /// Reject with 403 Forbidden
+    Block,
+    /// Serve after `slow_down_ms`
+    Slow,
+    /// Serve, but keep the client's events out of the activity log
+    Exclude,
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum ConfigError {
     #[error("Failed to load configuration: {0}")]
@@ -128,11 +314,63 @@ filename = "metadata.db"
 [data_processing]
 tdps_markdown_root = "some_root"
 tdps_pdf_root = "some_pdf_root"
+
+[rate_limit.search]
+requests_per_minute = 30
+burst = 10
+
+[crawlers]
+scraper_requests_per_minute = 60
+scraper = "block"
+llm_agent = "exclude"
+
+[files]
+pdf_cache_control = "public, max-age=604800, immutable"
+thumbnail_width = 256
+
+[server]
+frontends = ["mcp"]
+mcp_addr = "127.0.0.1:60001"
+
+[tracing]
+otlp_endpoint = "http://localhost:4318"
+sample_ratio = 0.25
 "#
         )?;
 
         let config = AppConfig::load_from_file(file.path())?;
 
+        let rate_limit = config.rate_limit.as_ref().unwrap();
+        assert_eq!(
+            rate_limit.search,
+            Some(RateLimitRule {
+                requests_per_minute: 30,
+                burst: Some(10)
+            })
+        );
+        assert!(rate_limit.content.is_none());
+        let crawlers = config.crawlers.as_ref().unwrap();
+        assert_eq!(crawlers.scraper_requests_per_minute, Some(60));
+        assert_eq!(crawlers.scraper, Some(CrawlerPolicy::Block));
+        assert_eq!(crawlers.llm_agent, Some(CrawlerPolicy::Exclude));
+        assert_eq!(crawlers.crawler, None);
+        let files = config.files.as_ref().unwrap();
+        assert_eq!(files.pdf_cache_control(), "public, max-age=604800, immutable");
+        assert_eq!(files.tdps_cache_control(), DEFAULT_TDPS_CACHE_CONTROL);
+        assert_eq!(files.thumbnail_width(), 256);
+        assert_eq!(files.max_image_width(), DEFAULT_MAX_IMAGE_WIDTH);
+
+        let server = config.server.as_ref().unwrap();
+        assert_eq!(server.frontends(), vec![Frontend::Mcp]);
+        assert_eq!(server.mcp_addr(), "127.0.0.1:60001");
+        assert_eq!(server.web_addr(), DEFAULT_WEB_ADDR);
+        let tracing = config.tracing.as_ref().unwrap();
+        assert_eq!(
+            tracing.otlp_endpoint.as_deref(),
+            Some("http://localhost:4318")
+        );
+        assert_eq!(tracing.service_name(), DEFAULT_SERVICE_NAME);
+        assert_eq!(tracing.sample_ratio(), 0.25);
         assert_eq!(
             config.data_access.vector.qdrant.as_ref().unwrap().url,
             "http://localhost:6334"
@@ -0,0 +1,101 @@
+//! Builds the clients and the `Searcher` shared by the web and MCP frontends. Both frontends
+//! started from one process get the same `Services`, so they share connections and caches.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use data_access::api_keys::ApiKeyClient;
+use data_access::embed::EmbedClient;
+use data_access::metadata::MetadataClient;
+use data_access::registry::RegistryClient;
+use data_access::resilience::CircuitBreaker;
+use data_access::vector::VectorClient;
+use data_processing::search::Searcher;
+use event_processing::dispatcher::EventDispatcher;
+use tracing::info;
+
+use super::AppConfig;
+use super::helpers;
+
+#[derive(Clone)]
+pub struct Services {
+    pub embed_client: Arc<dyn EmbedClient + Send + Sync>,
+    pub vector_client: Arc<dyn VectorClient + Send + Sync>,
+    pub metadata_client: Arc<dyn MetadataClient + Send + Sync>,
+    pub dispatcher: Arc<EventDispatcher>,
+    pub registry: Option<Arc<dyn RegistryClient + Send + Sync>>,
+    pub api_keys: Option<Arc<dyn ApiKeyClient + Send + Sync>>,
+    pub searcher: Arc<Searcher>,
+    /// Circuit breakers of the embedding provider and the vector database
+    pub breakers: Vec<Arc<CircuitBreaker>>,
+    /// Circuit breaker of the embedding provider, which `/readyz` reports instead of calling it
+    pub embed_breaker: Arc<CircuitBreaker>,
+}
+
+/// Connect every configured client and load the IDF, teams and leagues the `Searcher` needs
+pub async fn bootstrap(config: &AppConfig) -> anyhow::Result<Services> {
+    crate::metrics::install()?;
+
+    let embed_client = helpers::load_any_embed_client(config)?;
+    let vector_client = helpers::load_any_vector_client(config).await?;
+    let metadata_client = helpers::load_any_metadata_client(config)?;
+    let dispatcher = helpers::build_event_dispatcher(config);
+    let registry = helpers::build_registry_client(config)?;
+    let api_keys = helpers::build_api_key_client(config)?;
+    let embed_breaker = embed_client.breaker();
+    let breakers = vec![embed_breaker.clone(), vector_client.breaker()];
+
+    metadata_client.print_analytics().await?;
+
+    info!("Clients initialized.");
+
+    let idf_map = metadata_client
+        .load_idf(vector_client.index_version())
+        .await?;
+
+    info!("IDF loaded.");
+
+    let tdps = metadata_client.load_tdps().await?;
+    let mut teams: Vec<String> = tdps
+        .iter()
+        .map(|tdp| tdp.team_name.name_pretty.clone())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    teams.sort();
+
+    let mut leagues: Vec<String> = tdps
+        .iter()
+        .map(|tdp| tdp.league.name_pretty().to_string())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    leagues.sort();
+
+    let embed_client: Arc<dyn EmbedClient + Send + Sync> = embed_client;
+    let vector_client: Arc<dyn VectorClient + Send + Sync> = vector_client;
+    crate::health::check_embedding_size(embed_client.as_ref(), vector_client.as_ref()).await?;
+
+    let searcher = Searcher::new(
+        embed_client.clone(),
+        vector_client.clone(),
+        metadata_client.clone(),
+        Arc::new(idf_map),
+        teams,
+        leagues,
+        config.data_processing.highlight_idf_threshold(),
+    )
+    .with_embed_timeout(config.data_processing.embed_timeout());
+
+    Ok(Services {
+        embed_client,
+        vector_client,
+        metadata_client,
+        dispatcher,
+        registry,
+        api_keys,
+        searcher: Arc::new(searcher),
+        breakers,
+        embed_breaker,
+    })
+}
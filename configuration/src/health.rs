@@ -0,0 +1,264 @@
+//! Readiness of the shared services, served at `/readyz` by both frontends. Qdrant, SQLite and
+//! the IDF are checked on each call, so the report reflects the current state rather than the
+//! one at startup. The embedding provider is paid per call and public probes must not spend
+//! its quota, so its check reads the circuit breaker instead; its dimension is checked once at
+//! startup by [`check_embedding_size`].
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use data_access::embed::EmbedClient;
+use data_access::resilience::{BreakerState, CircuitBreaker};
+use data_access::vector::VectorClient;
+use serde::Serialize;
+use tracing::{info, warn};
+
+use super::bootstrap::Services;
+
+/// Upper bound of a single check, including the clients' own retries
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+/// Embedded at startup to check that the provider answers with the collection's dimension
+const EMBED_PROBE: &str = "readiness probe";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Ok,
+    /// Impaired but still serving, such as searches falling back to sparse while the embedding
+    /// provider is down. Does not fail readiness.
+    Degraded,
+    Down,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Check {
+    pub status: CheckStatus,
+    pub detail: String,
+    pub latency_ms: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Readiness {
+    /// Whether no check is down
+    pub ready: bool,
+    /// By dependency: `vector`, `metadata`, `idf` and `embed`
+    pub checks: BTreeMap<&'static str, Check>,
+    /// Circuit breaker states by dependency name
+    pub breakers: BTreeMap<String, BreakerState>,
+}
+
+/// Check the Qdrant collection and its dimension, SQLite, the loaded IDF and the embedding
+/// provider's circuit breaker. The database checks run concurrently.
+pub async fn readiness(services: &Services) -> Readiness {
+    let (vector, metadata) = tokio::join!(
+        timed(async {
+            services
+                .vector_client
+                .embedding_size()
+                .await
+                .map_err(|e| e.to_string())
+        }),
+        timed(async {
+            services
+                .metadata_client
+                .load_leagues()
+                .await
+                .map(|leagues| format!("{} leagues", leagues.len()))
+                .map_err(|e| e.to_string())
+        }),
+    );
+
+    let index_version = services.vector_client.index_version();
+    let mut checks = BTreeMap::new();
+    checks.insert(
+        "vector",
+        vector.into_check(|size| {
+            Ok(format!(
+                "index version {}, embedding size {}",
+                index_version, size
+            ))
+        }),
+    );
+    checks.insert("metadata", metadata.into_check(Ok));
+    checks.insert("embed", embed_check(&services.embed_breaker));
+    checks.insert("idf", idf_check(services, index_version));
+
+    let breakers = services
+        .breakers
+        .iter()
+        .map(|breaker| (breaker.name().to_string(), breaker.state()))
+        .collect();
+
+    Readiness {
+        ready: checks.values().all(|check| check.status != CheckStatus::Down),
+        checks,
+        breakers,
+    }
+}
+
+/// Searches fall back to sparse while the breaker is not closed, so the provider is reported
+/// degraded rather than down
+fn embed_check(breaker: &CircuitBreaker) -> Check {
+    let (status, detail) = match breaker.state() {
+        BreakerState::Closed => (
+            CheckStatus::Ok,
+            format!("{} circuit breaker closed", breaker.name()),
+        ),
+        state => (
+            CheckStatus::Degraded,
+            format!(
+                "{} circuit breaker {}, searches fall back to sparse",
+                breaker.name(),
+                if state == BreakerState::Open {
+                    "open"
+                } else {
+                    "half-open"
+                }
+            ),
+        ),
+    };
+    Check {
+        status,
+        detail,
+        latency_ms: 0,
+    }
+}
+
+/// Embed a probe text once and compare its size with the collection's. A mismatch fails every
+/// dense search, so it fails startup; a provider or collection that cannot be reached only
+/// logs a warning, since searches fall back to sparse and `/readyz` reports the collection.
+pub async fn check_embedding_size(
+    embed_client: &(dyn EmbedClient + Send + Sync),
+    vector_client: &(dyn VectorClient + Send + Sync),
+) -> anyhow::Result<()> {
+    let expected = match vector_client.embedding_size().await {
+        Ok(size) => size,
+        Err(e) => {
+            warn!("Could not read the collection's embedding size: {}", e);
+            return Ok(());
+        }
+    };
+    let size = match embed_client.embed_string(EMBED_PROBE).await {
+        Ok(embedding) => embedding.len() as u64,
+        Err(e) => {
+            warn!("Could not check the embedding size: {}", e);
+            return Ok(());
+        }
+    };
+    if size != expected {
+        anyhow::bail!(
+            "Embedding size {} does not match the collection's {}",
+            size,
+            expected
+        );
+    }
+    info!("Embedding size {} matches the collection.", size);
+    Ok(())
+}
+
+fn idf_check(services: &Services, index_version: u32) -> Check {
+    let terms = services.searcher.idf_map.len();
+    let (status, detail) = if terms == 0 {
+        (
+            CheckStatus::Down,
+            format!("no IDF loaded for index version {}", index_version),
+        )
+    } else {
+        (CheckStatus::Ok, format!("{} terms", terms))
+    };
+    Check {
+        status,
+        detail,
+        latency_ms: 0,
+    }
+}
+
+struct Timed<T> {
+    result: Result<T, String>,
+    latency: Duration,
+}
+
+impl<T> Timed<T> {
+    /// `describe` turns a successful result into the check's detail, or fails the check
+    fn into_check(self, describe: impl FnOnce(T) -> Result<String, String>) -> Check {
+        let (status, detail) = match self.result.and_then(describe) {
+            Ok(detail) => (CheckStatus::Ok, detail),
+            Err(e) => (CheckStatus::Down, e),
+        };
+        Check {
+            status,
+            detail,
+            latency_ms: self.latency.as_millis() as u64,
+        }
+    }
+}
+
+async fn timed<T>(check: impl Future<Output = Result<T, String>>) -> Timed<T> {
+    let start = Instant::now();
+    let result = match tokio::time::timeout(CHECK_TIMEOUT, check).await {
+        Ok(result) => result,
+        Err(_) => Err(format!("timed out after {:?}", CHECK_TIMEOUT)),
+    };
+    Timed {
+        result,
+        latency: start.elapsed(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data_access::embed::EmbedClientError;
+    use data_access::resilience::{Resilience, ResiliencePolicy};
+
+    #[tokio::test]
+    async fn test_timed_check() {
+        let check = timed(async { Ok::<_, String>(3) })
+            .await
+            .into_check(|n| Ok(format!("{} items", n)));
+        assert_eq!(check.status, CheckStatus::Ok);
+        assert_eq!(check.detail, "3 items");
+
+        let check = timed(async { Ok::<_, String>(3) })
+            .await
+            .into_check(|_| Err("wrong size".to_string()));
+        assert_eq!(check.status, CheckStatus::Down);
+        assert_eq!(check.detail, "wrong size");
+
+        tokio::time::pause();
+        let check = timed(async {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            Ok::<_, String>(())
+        })
+        .await
+        .into_check(|_| Ok(String::new()));
+        assert_eq!(check.status, CheckStatus::Down);
+        assert!(check.detail.starts_with("timed out"));
+    }
+
+    #[tokio::test]
+    async fn test_embed_check_reads_breaker() {
+        let resilience = Resilience::new(
+            "openai",
+            ResiliencePolicy {
+                retries: Some(0),
+                failure_threshold: Some(1),
+                ..Default::default()
+            },
+        );
+        let breaker = resilience.breaker();
+        let check = embed_check(&breaker);
+        assert_eq!(check.status, CheckStatus::Ok);
+        assert_eq!(check.detail, "openai circuit breaker closed");
+
+        let _ = resilience
+            .call("embed_string", Duration::from_secs(1), || async {
+                Err::<(), _>(EmbedClientError::Unavailable("down".to_string()))
+            })
+            .await;
+        let check = embed_check(&breaker);
+        assert_eq!(check.status, CheckStatus::Degraded);
+        assert!(check.detail.contains("open"));
+    }
+}
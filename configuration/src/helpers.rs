@@ -1,9 +1,11 @@
 use super::AppConfig;
 use data_access::{
-    embed::{EmbedClient, FastembedClient, OpenAIClient},
+    api_keys::{ApiKeyClient, SqliteApiKeyClient},
+    embed::{EmbedClient, FastembedClient, OpenAIClient, ResilientEmbedClient},
     metadata::{MetadataClient, SqliteClient},
     registry::{RegistryClient, SqliteRegistryClient},
-    vector::{QdrantClient, VectorClient},
+    resilience::{ResilienceConfig, ResiliencePolicy},
+    vector::{QdrantClient, ResilientVectorClient, VectorClient},
 };
 use event_processing::dispatcher::EventDispatcher;
 use event_processing::listeners::sqlite::SqliteListener;
@@ -11,60 +13,111 @@ use event_processing::listeners::telegram::TelegramListener;
 use std::sync::Arc;
 use tracing::info;
 
-pub fn load_any_embed_client(config: &AppConfig) -> Arc<dyn EmbedClient + Send + Sync> {
+/// The configured embedding client, wrapped with the `[data_access.resilience.embed]` policy
+pub fn load_any_embed_client(config: &AppConfig) -> anyhow::Result<Arc<ResilientEmbedClient>> {
     // Initialize embed client based on config
-    let embed_client: Arc<dyn EmbedClient + Send + Sync> =
+    let (dependency, embed_client): (&str, Arc<dyn EmbedClient + Send + Sync>) =
         if let Some(openai_cfg) = &config.data_access.embed.openai {
             info!(
                 "Using OpenAI Embeddings with model: {}",
                 openai_cfg.model_name
             );
-            Arc::new(OpenAIClient::new(openai_cfg))
+            ("openai", Arc::new(OpenAIClient::new(openai_cfg)))
         } else if let Some(fastembed_cfg) = &config.data_access.embed.fastembed {
             info!("Using FastEmbed with model: {}", fastembed_cfg.model_name);
-            Arc::new(FastembedClient::new(fastembed_cfg).unwrap())
+            ("fastembed", Arc::new(FastembedClient::new(fastembed_cfg)?))
         } else {
-            panic!("No embedding configuration found in config.toml");
+            anyhow::bail!("No embedding configuration found in config.toml");
         };
 
-    embed_client
+    let policy = resilience_policy(config, |resilience| resilience.embed.as_ref());
+    Ok(Arc::new(ResilientEmbedClient::new(
+        dependency,
+        embed_client,
+        policy,
+    )))
 }
 
+/// The configured vector client, wrapped with the `[data_access.resilience.vector]` policy
 pub async fn load_any_vector_client(
     config: &AppConfig,
-) -> anyhow::Result<Arc<dyn VectorClient + Send + Sync>> {
+) -> anyhow::Result<Arc<ResilientVectorClient>> {
     // Initialize vector client based on config
-    let vector_client: Arc<dyn VectorClient + Send + Sync> =
+    let (dependency, vector_client): (&str, Arc<dyn VectorClient + Send + Sync>) =
         if let Some(qdrant_cfg) = &config.data_access.vector.qdrant {
             info!("Using Qdrant");
             let client = QdrantClient::new(qdrant_cfg.clone()).await?;
-            Arc::new(client)
+            ("qdrant", Arc::new(client))
         } else {
-            panic!("No vector configuration found in config.toml");
+            anyhow::bail!("No vector configuration found in config.toml");
         };
 
-    Ok(vector_client)
+    let policy = resilience_policy(config, |resilience| resilience.vector.as_ref());
+    Ok(Arc::new(ResilientVectorClient::new(
+        dependency,
+        vector_client,
+        policy,
+    )))
 }
 
-pub fn load_any_metadata_client(config: &AppConfig) -> Arc<dyn MetadataClient + Send + Sync> {
+fn resilience_policy(
+    config: &AppConfig,
+    select: impl FnOnce(&ResilienceConfig) -> Option<&ResiliencePolicy>,
+) -> ResiliencePolicy {
+    config
+        .data_access
+        .resilience
+        .as_ref()
+        .and_then(select)
+        .cloned()
+        .unwrap_or_default()
+}
+
+pub fn load_any_metadata_client(
+    config: &AppConfig,
+) -> anyhow::Result<Arc<dyn MetadataClient + Send + Sync>> {
     // Initialize metadata client based on config
     let metadata_client: Arc<dyn MetadataClient + Send + Sync> =
         if let Some(sqlite_cfg) = &config.data_access.metadata.sqlite {
             info!("Using SQLite Metadata with file: {}", sqlite_cfg.filename);
-            Arc::new(SqliteClient::new(sqlite_cfg.clone()))
+            Arc::new(SqliteClient::new(sqlite_cfg.clone())?)
         } else {
-            panic!("No metadata configuration found in config.toml");
+            anyhow::bail!("No metadata configuration found in config.toml");
         };
 
-    metadata_client
+    Ok(metadata_client)
 }
 
-pub fn build_registry_client(config: &AppConfig) -> Option<Arc<dyn RegistryClient + Send + Sync>> {
-    let registry_config = config.data_access.registry.as_ref()?;
-    let sqlite_cfg = registry_config.sqlite.as_ref()?;
+pub fn build_registry_client(
+    config: &AppConfig,
+) -> anyhow::Result<Option<Arc<dyn RegistryClient + Send + Sync>>> {
+    let Some(sqlite_cfg) = config
+        .data_access
+        .registry
+        .as_ref()
+        .and_then(|registry_config| registry_config.sqlite.as_ref())
+    else {
+        return Ok(None);
+    };
 
     info!("Using SQLite Registry with file: {}", sqlite_cfg.filename);
-    Some(Arc::new(SqliteRegistryClient::new(sqlite_cfg.clone())))
+    Ok(Some(Arc::new(SqliteRegistryClient::new(sqlite_cfg.clone())?)))
+}
+
+pub fn build_api_key_client(
+    config: &AppConfig,
+) -> anyhow::Result<Option<Arc<dyn ApiKeyClient + Send + Sync>>> {
+    let Some(sqlite_cfg) = config
+        .data_access
+        .api_keys
+        .as_ref()
+        .and_then(|api_keys_config| api_keys_config.sqlite.as_ref())
+    else {
+        return Ok(None);
+    };
+
+    info!("Using SQLite API keys with file: {}", sqlite_cfg.filename);
+    Ok(Some(Arc::new(SqliteApiKeyClient::new(sqlite_cfg.clone())?)))
 }
 
 pub fn build_event_dispatcher(config: &AppConfig) -> Arc<EventDispatcher> {
@@ -1,4 +1,11 @@
 mod appconfig;
-pub use appconfig::AppConfig;
+pub use appconfig::{
+    AppConfig, CrawlerConfig, CrawlerPolicy, FilesConfig, Frontend, RateLimitConfig,
+    RateLimitRule, ServerConfig, TracingConfig,
+};
 
+pub mod bootstrap;
+pub mod health;
 pub mod helpers;
+pub mod metrics;
+pub mod telemetry;
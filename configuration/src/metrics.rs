@@ -0,0 +1,86 @@
+//! Prometheus exporter for the `metrics` recorded across the workspace. Crates record with the
+//! `metrics` macros; the frontends serve `render()` at `/metrics`.
+
+use std::sync::OnceLock;
+
+use metrics::{Unit, describe_counter, describe_gauge, describe_histogram};
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+
+/// Buckets of every `*_seconds` histogram, from 5 ms to 10 s
+const LATENCY_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+static HANDLE: OnceLock<PrometheusHandle> = OnceLock::new();
+
+/// Install the process-wide recorder. Later calls are no-ops.
+pub fn install() -> anyhow::Result<()> {
+    if HANDLE.get().is_some() {
+        return Ok(());
+    }
+    let handle = PrometheusBuilder::new()
+        .set_buckets_for_metric(Matcher::Suffix("_seconds".to_string()), LATENCY_BUCKETS)?
+        .install_recorder()?;
+    describe();
+    let _ = HANDLE.set(handle);
+    Ok(())
+}
+
+/// All metrics in the Prometheus text format. Empty until `install` was called.
+pub fn render() -> String {
+    HANDLE.get().map(|h| h.render()).unwrap_or_default()
+}
+
+fn describe() {
+    describe_counter!(
+        "tdp_http_requests_total",
+        "Web requests by matched route, method and status"
+    );
+    describe_histogram!(
+        "tdp_http_request_duration_seconds",
+        Unit::Seconds,
+        "Web request latency by matched route and method"
+    );
+    describe_counter!(
+        "tdp_mcp_tool_calls_total",
+        "MCP tool calls by tool and outcome (ok, error)"
+    );
+    describe_histogram!(
+        "tdp_mcp_tool_duration_seconds",
+        Unit::Seconds,
+        "MCP tool call latency by tool"
+    );
+    describe_histogram!(
+        "tdp_search_stage_duration_seconds",
+        Unit::Seconds,
+        "Time spent in each stage of a search: embed, vector_query, toc_load, suggestions"
+    );
+    describe_counter!(
+        "tdp_search_degraded_total",
+        "Searches that fell back to sparse only, by reason (error, timeout)"
+    );
+    describe_counter!(
+        "tdp_embed_errors_total",
+        "Failed embedding provider requests by provider"
+    );
+    describe_counter!(
+        "tdp_retries_total",
+        "Retried calls to the embedding provider and vector database by dependency and operation"
+    );
+    describe_gauge!(
+        "tdp_circuit_breaker_state",
+        "Circuit breaker state by dependency: 0 closed, 1 half-open, 2 open"
+    );
+    describe_counter!(
+        "tdp_openai_tokens_total",
+        "Tokens sent to the OpenAI embeddings API by model"
+    );
+    describe_gauge!(
+        "tdp_openai_cost_dollars",
+        "Cost of the OpenAI embeddings API since startup by model, in US dollars"
+    );
+    describe_counter!(
+        "tdp_event_listener_failures_total",
+        "Events an event listener failed to handle, by listener"
+    );
+}
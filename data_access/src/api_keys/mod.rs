@@ -0,0 +1,73 @@
+mod sqlite_client;
+pub use sqlite_client::{SqliteApiKeyClient, SqliteApiKeyConfig};
+
+use std::future::Future;
+use std::pin::Pin;
+use serde::{Deserialize, Serialize};
+
+#[derive(thiserror::Error, Debug)]
+pub enum ApiKeyError {
+    #[error("Internal error: {0}")]
+    Internal(String),
+    #[error("Unknown API key '{0}'")]
+    NotFound(String),
+    #[error(transparent)]
+    Migration(#[from] crate::migration::MigrationError),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiKey {
+    /// Public part of the token, safe to log and attach to events
+    pub id: String,
+    pub name: String,
+    pub contact: Option<String>,
+    /// Requests per UTC day, unlimited when unset
+    pub daily_quota: Option<u64>,
+    pub created_at: String,
+    pub revoked_at: Option<String>,
+}
+
+impl ApiKey {
+    pub fn is_active(&self) -> bool {
+        self.revoked_at.is_none()
+    }
+}
+
+/// A freshly issued key. The token is shown once; only its hash is stored.
+#[derive(Debug, Clone)]
+pub struct IssuedApiKey {
+    pub key: ApiKey,
+    pub token: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiKeyUsage {
+    pub key_id: String,
+    /// UTC day, YYYY-MM-DD
+    pub day: String,
+    pub route_class: String,
+    pub count: u64,
+}
+
+pub trait ApiKeyClient: Send + Sync {
+    fn issue_key<'a>(&'a self, name: &'a str, contact: Option<&'a str>, daily_quota: Option<u64>)
+        -> Pin<Box<dyn Future<Output = Result<IssuedApiKey, ApiKeyError>> + Send + 'a>>;
+
+    fn revoke_key<'a>(&'a self, key_id: &'a str)
+        -> Pin<Box<dyn Future<Output = Result<(), ApiKeyError>> + Send + 'a>>;
+
+    fn list_keys<'a>(&'a self)
+        -> Pin<Box<dyn Future<Output = Result<Vec<ApiKey>, ApiKeyError>> + Send + 'a>>;
+
+    /// The active key a bearer token belongs to, if any
+    fn authenticate<'a>(&'a self, token: &'a str)
+        -> Pin<Box<dyn Future<Output = Result<Option<ApiKey>, ApiKeyError>> + Send + 'a>>;
+
+    /// Count one request and return the key's total for today across all route classes
+    fn record_usage<'a>(&'a self, key_id: &'a str, route_class: &'a str)
+        -> Pin<Box<dyn Future<Output = Result<u64, ApiKeyError>> + Send + 'a>>;
+
+    /// Daily request counts, optionally from a day (YYYY-MM-DD) onwards
+    fn usage<'a>(&'a self, since: Option<&'a str>)
+        -> Pin<Box<dyn Future<Output = Result<Vec<ApiKeyUsage>, ApiKeyError>> + Send + 'a>>;
+}
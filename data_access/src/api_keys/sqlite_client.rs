@@ -0,0 +1,400 @@
+use std::future::Future;
+use std::pin::Pin;
+use rusqlite::{Connection, OptionalExtension, params};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+use crate::api_keys::{ApiKey, ApiKeyClient, ApiKeyError, ApiKeyUsage, IssuedApiKey};
+use crate::migration::{Migration, MigrationError, MigrationStatus, Migrator};
+use crate::sqlite_pool::{DEFAULT_POOL_SIZE, SqlitePool, SqlitePoolError};
+
+const TOKEN_PREFIX: &str = "tdp_";
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct SqliteApiKeyConfig {
+    pub filename: String,
+    /// Number of read-only connections. Defaults to 4.
+    pub pool_size: Option<usize>,
+}
+
+pub struct SqliteApiKeyClient {
+    pool: SqlitePool,
+}
+
+const DATABASE_NAME: &str = "API key database";
+
+static MIGRATIONS: &[Migration] = &[Migration {
+    version: 1,
+    description: "api_keys and api_key_usage tables",
+    sql: "
+        CREATE TABLE IF NOT EXISTS api_keys (
+            id          TEXT PRIMARY KEY,
+            secret_hash TEXT NOT NULL,
+            name        TEXT NOT NULL,
+            contact     TEXT,
+            daily_quota INTEGER,
+            created_at  TEXT NOT NULL,
+            revoked_at  TEXT
+        );
+        CREATE TABLE IF NOT EXISTS api_key_usage (
+            key_id      TEXT NOT NULL,
+            day         TEXT NOT NULL,
+            route_class TEXT NOT NULL,
+            count       INTEGER NOT NULL,
+            PRIMARY KEY (key_id, day, route_class)
+        );
+    ",
+}];
+
+const KEY_COLUMNS: &str = "id, name, contact, daily_quota, created_at, revoked_at";
+
+impl SqliteApiKeyClient {
+    pub fn new(config: SqliteApiKeyConfig) -> Result<Self, ApiKeyError> {
+        let pool = SqlitePool::open(
+            &config.filename,
+            config.pool_size.unwrap_or(DEFAULT_POOL_SIZE),
+        )
+        .map_err(|source| MigrationError::Sqlite {
+            database: config.filename.clone(),
+            source,
+        })?;
+        pool.write_blocking(|conn| Self::migrator(&config).check(conn))?;
+
+        Ok(SqliteApiKeyClient { pool })
+    }
+
+    pub fn migration_status(config: &SqliteApiKeyConfig) -> Result<MigrationStatus, MigrationError> {
+        let conn = Self::open(config)?;
+        Self::migrator(config).status(&conn)
+    }
+
+    pub fn migrate(config: &SqliteApiKeyConfig) -> Result<Vec<&'static Migration>, MigrationError> {
+        let mut conn = Self::open(config)?;
+        Self::migrator(config).apply(&mut conn)
+    }
+
+    fn migrator(config: &SqliteApiKeyConfig) -> Migrator {
+        Migrator::new(format!("{} ({})", DATABASE_NAME, config.filename), MIGRATIONS)
+    }
+
+    fn open(config: &SqliteApiKeyConfig) -> Result<Connection, MigrationError> {
+        let map_err = |source| MigrationError::Sqlite {
+            database: config.filename.clone(),
+            source,
+        };
+
+        let conn = Connection::open(&config.filename).map_err(map_err)?;
+        conn.execute_batch("PRAGMA journal_mode=WAL;").map_err(map_err)?;
+
+        Ok(conn)
+    }
+
+    fn hash_secret(secret: &str) -> String {
+        hex::encode(Sha256::digest(secret.as_bytes()))
+    }
+
+    /// Splits a `tdp_<id>_<secret>` token into its id and secret
+    fn parse_token(token: &str) -> Option<(&str, &str)> {
+        let (id, secret) = token.strip_prefix(TOKEN_PREFIX)?.split_once('_')?;
+        if id.is_empty() || secret.is_empty() {
+            return None;
+        }
+        Some((id, secret))
+    }
+
+    fn today() -> String {
+        chrono::Utc::now().format("%Y-%m-%d").to_string()
+    }
+
+    fn row_to_key(row: &rusqlite::Row) -> rusqlite::Result<ApiKey> {
+        Ok(ApiKey {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            contact: row.get(2)?,
+            daily_quota: row.get::<_, Option<i64>>(3)?.map(|q| q.max(0) as u64),
+            created_at: row.get(4)?,
+            revoked_at: row.get(5)?,
+        })
+    }
+}
+
+fn pool_error(e: SqlitePoolError) -> ApiKeyError {
+    ApiKeyError::Internal(e.to_string())
+}
+
+fn internal(e: rusqlite::Error) -> ApiKeyError {
+    ApiKeyError::Internal(e.to_string())
+}
+
+impl ApiKeyClient for SqliteApiKeyClient {
+    fn issue_key<'a>(
+        &'a self,
+        name: &'a str,
+        contact: Option<&'a str>,
+        daily_quota: Option<u64>,
+    ) -> Pin<Box<dyn Future<Output = Result<IssuedApiKey, ApiKeyError>> + Send + 'a>>
+    {
+        Box::pin(async move {
+            let id = hex::encode(rand::random::<[u8; 8]>());
+            let secret = hex::encode(rand::random::<[u8; 32]>());
+            let key = ApiKey {
+                id: id.clone(),
+                name: name.to_string(),
+                contact: contact.map(str::to_string),
+                daily_quota,
+                created_at: chrono::Utc::now().to_rfc3339(),
+                revoked_at: None,
+            };
+
+            let row = key.clone();
+            let secret_hash = Self::hash_secret(&secret);
+            self.pool
+                .write(move |conn| {
+                    conn.execute(
+                        "INSERT INTO api_keys (id, secret_hash, name, contact, daily_quota, created_at)
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                        params![
+                            row.id,
+                            secret_hash,
+                            row.name,
+                            row.contact,
+                            row.daily_quota.map(|q| q as i64),
+                            row.created_at
+                        ],
+                    )
+                })
+                .await
+                .map_err(pool_error)?
+                .map_err(internal)?;
+
+            Ok(IssuedApiKey {
+                key,
+                token: format!("{TOKEN_PREFIX}{id}_{secret}"),
+            })
+        })
+    }
+
+    fn revoke_key<'a>(
+        &'a self,
+        key_id: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<(), ApiKeyError>> + Send + 'a>>
+    {
+        Box::pin(async move {
+            let id = key_id.to_string();
+            let updated = self
+                .pool
+                .write(move |conn| {
+                    conn.execute(
+                        "UPDATE api_keys SET revoked_at = COALESCE(revoked_at, ?2) WHERE id = ?1",
+                        params![id, chrono::Utc::now().to_rfc3339()],
+                    )
+                })
+                .await
+                .map_err(pool_error)?
+                .map_err(internal)?;
+
+            if updated == 0 {
+                return Err(ApiKeyError::NotFound(key_id.to_string()));
+            }
+            Ok(())
+        })
+    }
+
+    fn list_keys<'a>(
+        &'a self,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<ApiKey>, ApiKeyError>> + Send + 'a>>
+    {
+        Box::pin(async move {
+            self.pool
+                .read(|conn| {
+                    let sql = format!("SELECT {KEY_COLUMNS} FROM api_keys ORDER BY created_at, id");
+                    let mut stmt = conn.prepare_cached(&sql)?;
+                    stmt.query_map([], Self::row_to_key)?
+                        .collect::<Result<Vec<_>, _>>()
+                })
+                .await
+                .map_err(pool_error)?
+                .map_err(internal)
+        })
+    }
+
+    fn authenticate<'a>(
+        &'a self,
+        token: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<ApiKey>, ApiKeyError>> + Send + 'a>>
+    {
+        Box::pin(async move {
+            let Some((id, secret)) = Self::parse_token(token) else {
+                return Ok(None);
+            };
+
+            let id = id.to_string();
+            let row: Option<(ApiKey, String)> = self
+                .pool
+                .read(move |conn| {
+                    let sql = format!("SELECT {KEY_COLUMNS}, secret_hash FROM api_keys WHERE id = ?1");
+                    conn.query_row(&sql, params![id], |row| {
+                        Ok((Self::row_to_key(row)?, row.get(6)?))
+                    })
+                    .optional()
+                })
+                .await
+                .map_err(pool_error)?
+                .map_err(internal)?;
+
+            let Some((key, stored_hash)) = row else {
+                return Ok(None);
+            };
+            let submitted_hash = Self::hash_secret(secret);
+            let matches: bool = stored_hash.as_bytes().ct_eq(submitted_hash.as_bytes()).into();
+
+            Ok((matches && key.is_active()).then_some(key))
+        })
+    }
+
+    fn record_usage<'a>(
+        &'a self,
+        key_id: &'a str,
+        route_class: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<u64, ApiKeyError>> + Send + 'a>>
+    {
+        Box::pin(async move {
+            let key_id = key_id.to_string();
+            let route_class = route_class.to_string();
+            let day = Self::today();
+            self.pool
+                .write(move |conn| {
+                    conn.execute(
+                        "INSERT INTO api_key_usage (key_id, day, route_class, count) VALUES (?1, ?2, ?3, 1)
+                         ON CONFLICT (key_id, day, route_class) DO UPDATE SET count = count + 1",
+                        params![key_id, day, route_class],
+                    )?;
+                    conn.query_row(
+                        "SELECT COALESCE(SUM(count), 0) FROM api_key_usage WHERE key_id = ?1 AND day = ?2",
+                        params![key_id, day],
+                        |row| row.get::<_, i64>(0),
+                    )
+                })
+                .await
+                .map_err(pool_error)?
+                .map(|total| total.max(0) as u64)
+                .map_err(internal)
+        })
+    }
+
+    fn usage<'a>(
+        &'a self,
+        since: Option<&'a str>,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<ApiKeyUsage>, ApiKeyError>> + Send + 'a>>
+    {
+        Box::pin(async move {
+            let since = since.unwrap_or("").to_string();
+            self.pool
+                .read(move |conn| {
+                    let mut stmt = conn.prepare_cached(
+                        "SELECT key_id, day, route_class, count FROM api_key_usage
+                         WHERE day >= ?1 ORDER BY day, key_id, route_class",
+                    )?;
+                    stmt.query_map(params![since], |row| {
+                        Ok(ApiKeyUsage {
+                            key_id: row.get(0)?,
+                            day: row.get(1)?,
+                            route_class: row.get(2)?,
+                            count: row.get::<_, i64>(3)?.max(0) as u64,
+                        })
+                    })?
+                    .collect::<Result<Vec<_>, _>>()
+                })
+                .await
+                .map_err(pool_error)?
+                .map_err(internal)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_client() -> SqliteApiKeyClient {
+        SqliteApiKeyClient::new(SqliteApiKeyConfig {
+            filename: ":memory:".to_string(),
+            pool_size: None,
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_issue_and_authenticate() {
+        let client = test_client();
+        let issued = client
+            .issue_key("RoboTeam dashboard", Some("ops@example.com"), Some(100))
+            .await
+            .unwrap();
+
+        assert!(issued.token.starts_with(TOKEN_PREFIX));
+        assert!(issued.token.contains(&issued.key.id));
+
+        let key = client.authenticate(&issued.token).await.unwrap().unwrap();
+        assert_eq!(key, issued.key);
+        assert_eq!(key.daily_quota, Some(100));
+
+        let keys = client.list_keys().await.unwrap();
+        assert_eq!(keys, vec![issued.key]);
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_rejects_bad_tokens() {
+        let client = test_client();
+        let issued = client.issue_key("a", None, None).await.unwrap();
+        let (id, _) = SqliteApiKeyClient::parse_token(&issued.token).unwrap();
+
+        for token in [
+            "",
+            "tdp_",
+            "not-a-token",
+            &format!("tdp_{id}_wrongsecret"),
+            "tdp_0000000000000000_secret",
+        ] {
+            assert!(client.authenticate(token).await.unwrap().is_none(), "{token}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_revoked_key_is_rejected() {
+        let client = test_client();
+        let issued = client.issue_key("a", None, None).await.unwrap();
+
+        client.revoke_key(&issued.key.id).await.unwrap();
+        assert!(client.authenticate(&issued.token).await.unwrap().is_none());
+        assert!(!client.list_keys().await.unwrap()[0].is_active());
+
+        assert!(matches!(
+            client.revoke_key("missing").await,
+            Err(ApiKeyError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_record_usage_counts_per_day() {
+        let client = test_client();
+        let a = client.issue_key("a", None, None).await.unwrap().key;
+        let b = client.issue_key("b", None, None).await.unwrap().key;
+
+        assert_eq!(client.record_usage(&a.id, "search").await.unwrap(), 1);
+        assert_eq!(client.record_usage(&a.id, "search").await.unwrap(), 2);
+        assert_eq!(client.record_usage(&a.id, "content").await.unwrap(), 3);
+        assert_eq!(client.record_usage(&b.id, "search").await.unwrap(), 1);
+
+        let usage = client.usage(None).await.unwrap();
+        assert_eq!(usage.len(), 3);
+        let a_search = usage
+            .iter()
+            .find(|u| u.key_id == a.id && u.route_class == "search")
+            .unwrap();
+        assert_eq!(a_search.count, 2);
+        assert_eq!(a_search.day, SqliteApiKeyClient::today());
+
+        assert!(client.usage(Some("9999-01-01")).await.unwrap().is_empty());
+    }
+}
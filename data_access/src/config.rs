@@ -1,7 +1,9 @@
+use crate::api_keys::SqliteApiKeyConfig;
 use crate::embed::FastEmbedConfig;
 use crate::embed::OpenAiConfig;
 use crate::metadata::SqliteConfig;
 use crate::registry::SqliteRegistryConfig;
+use crate::resilience::ResilienceConfig;
 use crate::vector::QdrantConfig;
 use serde::Deserialize;
 
@@ -11,6 +13,8 @@ pub struct DataAccessConfig {
     pub vector: VectorConfig,
     pub metadata: MetadataConfig,
     pub registry: Option<RegistryConfig>,
+    pub api_keys: Option<ApiKeysConfig>,
+    pub resilience: Option<ResilienceConfig>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -33,3 +37,8 @@ pub struct MetadataConfig {
 pub struct RegistryConfig {
     pub sqlite: Option<SqliteRegistryConfig>,
 }
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ApiKeysConfig {
+    pub sqlite: Option<SqliteApiKeyConfig>,
+}
@@ -6,7 +6,7 @@ use fastembed::{
     UserDefinedEmbeddingModel,
 };
 use serde::Deserialize;
-use tracing::debug;
+use tracing::{Instrument, debug, info_span};
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct FastEmbedConfig {
@@ -73,7 +73,8 @@ impl EmbedClient for FastembedClient {
         &'a self,
         string: &'a str,
     ) -> Pin<Box<dyn Future<Output = Result<Vec<f32>, EmbedClientError>> + Send + 'a>> {
-        Box::pin(async move {
+        let span = info_span!("embed", provider = "fastembed", inputs = 1);
+        let embed = async move {
             debug!("Embedding string: {}", string);
             let start = std::time::Instant::now();
 
@@ -91,21 +92,24 @@ impl EmbedClient for FastembedClient {
 
             debug!("String embedded in {}ms", start.elapsed().as_millis());
             Ok(vec)
-        })
+        };
+        Box::pin(embed.instrument(span))
     }
 
     fn embed_strings<'a>(
         &'a self,
         strings: Vec<String>,
     ) -> Pin<Box<dyn Future<Output = Result<Vec<Vec<f32>>, EmbedClientError>> + Send + 'a>> {
-        Box::pin(async move {
+        let span = info_span!("embed", provider = "fastembed", inputs = strings.len());
+        let embed = async move {
             let vecs = self
                 .model
                 .lock()
                 .map_err(|e| EmbedClientError::Internal(e.to_string()))?
                 .embed(strings, None)?;
             Ok(vecs)
-        })
+        };
+        Box::pin(embed.instrument(span))
     }
 }
 
@@ -1,11 +1,14 @@
 mod fastembed_client;
 mod openai_client;
+mod resilient_client;
 pub use fastembed_client::{FastEmbedConfig, FastembedClient};
 pub use openai_client::{OpenAIClient, OpenAiConfig};
+pub use resilient_client::ResilientEmbedClient;
 
 use async_openai::error::OpenAIError;
 use data_structures::IDF;
 use data_structures::text_utils::process_text_to_words;
+use mockall::mock;
 use std::collections::HashMap;
 use std::future::Future;
 use std::pin::Pin;
@@ -20,6 +23,9 @@ pub enum EmbedClientError {
     Any(#[from] anyhow::Error),
     #[error("OpenAI client error: {0}")]
     OpenAI(#[from] OpenAIError),
+    /// The provider timed out or its circuit breaker is open; retrying later may succeed
+    #[error("Embedding provider unavailable: {0}")]
+    Unavailable(String),
 }
 
 pub trait EmbedClient {
@@ -38,6 +44,24 @@ pub trait EmbedClient {
     }
 }
 
+// `#[automock]` cannot tie the borrowed argument to the returned future's lifetime, so the mock
+// takes the string for any lifetime. `embed_sparse` keeps its default implementation.
+mock! {
+    pub EmbedClient {}
+
+    impl EmbedClient for EmbedClient {
+        fn embed_string<'a>(
+            &'a self,
+            string: &str,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<f32>, EmbedClientError>> + Send + 'a>>;
+
+        fn embed_strings<'a>(
+            &'a self,
+            strings: Vec<String>,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<Vec<f32>>, EmbedClientError>> + Send + 'a>>;
+    }
+}
+
 pub fn embed_sparse(text: &str, idf_map: &IDF) -> HashMap<u32, f32> {
     let mut map = HashMap::new();
 
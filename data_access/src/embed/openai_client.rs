@@ -6,7 +6,7 @@ use async_openai::{
 };
 use serde::Deserialize;
 use std::sync::Mutex;
-use tracing::info;
+use tracing::{Instrument, info, info_span};
 
 use super::EmbedClient;
 use super::EmbedClientError;
@@ -71,7 +71,13 @@ impl EmbedClient for OpenAIClient {
         &'a self,
         strings: Vec<String>,
     ) -> Pin<Box<dyn Future<Output = Result<Vec<Vec<f32>>, EmbedClientError>> + Send + 'a>> {
-        Box::pin(async move {
+        let span = info_span!(
+            "embed",
+            provider = "openai",
+            model = %self.model_name,
+            inputs = strings.len()
+        );
+        let embed = async move {
             if strings.is_empty() {
                 return Ok(Vec::new());
             }
@@ -93,10 +99,21 @@ impl EmbedClient for OpenAIClient {
                     .input(batch_strings.to_vec())
                     .build()?;
 
-                let response = self.client.embeddings().create(request).await?;
+                let response = match self.client.embeddings().create(request).await {
+                    Ok(response) => response,
+                    Err(e) => {
+                        metrics::counter!("tdp_embed_errors_total", "provider" => "openai")
+                            .increment(1);
+                        return Err(e.into());
+                    }
+                };
 
                 let tokens_used = response.usage.prompt_tokens;
                 let cost = OpenAIClient::cost_in_cents(&self.model_name, tokens_used);
+                metrics::counter!("tdp_openai_tokens_total", "model" => self.model_name.clone())
+                    .increment(tokens_used as u64);
+                metrics::gauge!("tdp_openai_cost_dollars", "model" => self.model_name.clone())
+                    .increment(cost as f64);
 
                 {
                     let mut total = self.total_costs.lock().unwrap();
@@ -121,7 +138,8 @@ impl EmbedClient for OpenAIClient {
             }
 
             Ok(embeddings)
-        })
+        };
+        Box::pin(embed.instrument(span))
     }
 }
 
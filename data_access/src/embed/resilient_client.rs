@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_openai::error::OpenAIError;
+use data_structures::IDF;
+
+use super::{EmbedClient, EmbedClientError};
+use crate::resilience::{CircuitBreaker, DependencyError, Resilience, ResiliencePolicy};
+
+/// Two attempts and the backoff between them fit into the searcher's 5s embed timeout, so a
+/// timed out attempt still gets one retry before the search falls back to sparse
+const EMBED_STRING_TIMEOUT: Duration = Duration::from_secs(2);
+/// Indexing embeds in batches of 100 with a pause after each one
+const EMBED_STRINGS_TIMEOUT: Duration = Duration::from_secs(300);
+
+impl DependencyError for EmbedClientError {
+    fn is_transient(&self) -> bool {
+        match self {
+            EmbedClientError::Unavailable(_) => true,
+            EmbedClientError::OpenAI(OpenAIError::Reqwest(_)) => true,
+            EmbedClientError::OpenAI(OpenAIError::ApiError(e)) => {
+                e.r#type.as_deref() == Some("server_error")
+            }
+            _ => false,
+        }
+    }
+
+    fn unavailable(message: String) -> Self {
+        EmbedClientError::Unavailable(message)
+    }
+}
+
+/// Wraps an [`EmbedClient`] with the timeouts, retries and circuit breaker of its policy.
+/// Operations are named `embed_string` and `embed_strings` in `timeouts_ms`.
+pub struct ResilientEmbedClient {
+    inner: Arc<dyn EmbedClient + Send + Sync>,
+    resilience: Resilience,
+}
+
+impl ResilientEmbedClient {
+    pub fn new(
+        dependency: &str,
+        inner: Arc<dyn EmbedClient + Send + Sync>,
+        policy: ResiliencePolicy,
+    ) -> Self {
+        Self {
+            inner,
+            resilience: Resilience::new(dependency, policy),
+        }
+    }
+
+    pub fn breaker(&self) -> Arc<CircuitBreaker> {
+        self.resilience.breaker()
+    }
+}
+
+impl EmbedClient for ResilientEmbedClient {
+    fn embed_string<'a>(
+        &'a self,
+        string: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<f32>, EmbedClientError>> + Send + 'a>> {
+        Box::pin(
+            self.resilience
+                .call("embed_string", EMBED_STRING_TIMEOUT, move || {
+                    self.inner.embed_string(string)
+                }),
+        )
+    }
+
+    fn embed_strings<'a>(
+        &'a self,
+        strings: Vec<String>,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<Vec<f32>>, EmbedClientError>> + Send + 'a>> {
+        Box::pin(
+            self.resilience
+                .call("embed_strings", EMBED_STRINGS_TIMEOUT, move || {
+                    self.inner.embed_strings(strings.clone())
+                }),
+        )
+    }
+
+    fn embed_sparse(&self, text: &str, idf_map: &IDF) -> HashMap<u32, f32> {
+        self.inner.embed_sparse(text, idf_map)
+    }
+}
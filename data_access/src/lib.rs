@@ -1,6 +1,10 @@
+pub mod api_keys;
 pub mod config;
 pub mod embed;
 pub mod file;
 pub mod metadata;
+pub mod migration;
 pub mod registry;
+pub mod resilience;
+pub mod sqlite_pool;
 pub mod vector;
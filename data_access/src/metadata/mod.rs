@@ -4,6 +4,7 @@ use data_structures::{
     IDF,
     content::{ContentItem, MarkdownTDP, PaperInfo, TocEntry},
     file::{League, TDPName, TeamName},
+    intermediate::{GrepMatch, GrepQuery},
 };
 use mockall::automock;
 pub use sqlite_client::{SqliteClient, SqliteConfig};
@@ -12,6 +13,9 @@ pub use sqlite_client::{SqliteClient, SqliteConfig};
 pub enum MetadataClientError {
     #[error("Internal error: {0}")]
     Internal(String),
+    #[error("Paper not found: {0}")]
+    PaperNotFound(String),
+    /// A missing item of a paper that exists, such as a content item or its abstract
     #[error("Not found: {0}")]
     NotFound(String),
     #[error("No vectors present")]
@@ -20,18 +24,29 @@ pub enum MetadataClientError {
     FieldMissing(String),
     #[error("Invalid vector dimension: {0}")]
     InvalidVectorDimension(String),
+    #[error(transparent)]
+    Migration(#[from] crate::migration::MigrationError),
 }
 #[automock]
 pub trait MetadataClient: Send + Sync {
+    /// Stores the IDF for an index version, replacing any IDF previously stored for it. The
+    /// version matches the vector collection the sparse embeddings were written to.
     fn store_idf<'a>(
         &'a self,
+        version: u32,
         idf: IDF,
     ) -> Pin<Box<dyn Future<Output = Result<(), MetadataClientError>> + Send + 'a>>;
 
     fn load_idf<'a>(
         &'a self,
+        version: u32,
     ) -> Pin<Box<dyn Future<Output = Result<IDF, MetadataClientError>> + Send + 'a>>;
 
+    fn delete_idf<'a>(
+        &'a self,
+        version: u32,
+    ) -> Pin<Box<dyn Future<Output = Result<(), MetadataClientError>> + Send + 'a>>;
+
     fn load_tdps<'a>(
         &'a self,
     ) -> Pin<Box<dyn Future<Output = Result<Vec<TDPName>, MetadataClientError>> + Send + 'a>>;
@@ -91,4 +106,8 @@ pub trait MetadataClient: Send + Sync {
         paper_lyt: String,
     ) -> Pin<Box<dyn Future<Output = Result<Vec<String>, MetadataClientError>> + Send + 'a>>;
 
+    fn grep<'a>(
+        &'a self,
+        query: GrepQuery,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<GrepMatch>, MetadataClientError>> + Send + 'a>>;
 }
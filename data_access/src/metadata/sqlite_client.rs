@@ -1,61 +1,42 @@
 use std::future::Future;
 use std::pin::Pin;
-use std::sync::{Arc, Mutex};
 
 use data_structures::IDF;
 use data_structures::content::{Author, ContentItem, ContentType, MarkdownTDP, PaperInfo, TocEntry};
+use data_structures::file::TDPName;
+use data_structures::intermediate::{GrepMatch, GrepQuery, GrepScope, find_matches};
 use rusqlite::{Connection, params};
 use serde::Deserialize;
 use tracing::info;
 
 use crate::metadata::{MetadataClient, MetadataClientError};
+use crate::migration::{Migration, MigrationError, MigrationStatus, Migrator};
+use crate::sqlite_pool::{DEFAULT_POOL_SIZE, SqlitePool};
 
 pub struct SqliteClient {
-    conn: Arc<Mutex<Connection>>,
+    pool: SqlitePool,
 }
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct SqliteConfig {
     pub filename: String,
+    /// Number of read-only connections. Defaults to 4.
+    pub pool_size: Option<usize>,
 }
 
-impl SqliteClient {
-    pub fn new(config: SqliteConfig) -> Self {
-        let conn = Connection::open(&config.filename).expect("Failed to open SQLite database");
-
-        // Enable WAL mode for better concurrency
-        conn.query_row("PRAGMA journal_mode=WAL;", [], |_| Ok(()))
-            .expect("Failed to set WAL mode");
-
-        let client = Self {
-            conn: Arc::new(Mutex::new(conn)),
-        };
+const DATABASE_NAME: &str = "metadata database";
 
-        client.ensure_database_idf();
-        client.ensure_database_paper_v2();
-
-        client
-    }
-
-    fn ensure_database_idf(&self) {
-        let conn = self.conn.lock().unwrap();
-
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS idf_index (
+static MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "idf, paper, author, toc_entry and reference tables",
+        sql: "
+            CREATE TABLE IF NOT EXISTS idf_index (
                 word TEXT NOT NULL UNIQUE,
                 idx INTEGER NOT NULL,
                 idf REAL NOT NULL
-            )",
-            [],
-        )
-        .expect("Failed to create table idf_index");
-    }
-
-    fn ensure_database_paper_v2(&self) {
-        let conn = self.conn.lock().unwrap();
-
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS paper (
+            );
+            CREATE TABLE IF NOT EXISTS paper (
                 paper_lyt TEXT PRIMARY KEY,
                 league TEXT NOT NULL,
                 year INTEGER NOT NULL,
@@ -64,24 +45,14 @@ impl SqliteClient {
                 abstract_text TEXT,
                 urls_json TEXT,
                 raw_markdown TEXT
-            )",
-            [],
-        )
-        .expect("Failed to create table paper");
-
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS author (
+            );
+            CREATE TABLE IF NOT EXISTS author (
                 paper_lyt TEXT NOT NULL,
                 name TEXT NOT NULL,
                 affiliation TEXT,
                 FOREIGN KEY (paper_lyt) REFERENCES paper(paper_lyt)
-            )",
-            [],
-        )
-        .expect("Failed to create table author");
-
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS toc_entry (
+            );
+            CREATE TABLE IF NOT EXISTS toc_entry (
                 paper_lyt TEXT NOT NULL,
                 content_seq INTEGER NOT NULL,
                 content_type TEXT NOT NULL,
@@ -91,62 +62,135 @@ impl SqliteClient {
                 image_path TEXT,
                 FOREIGN KEY (paper_lyt) REFERENCES paper(paper_lyt),
                 UNIQUE(paper_lyt, content_seq)
-            )",
-            [],
-        )
-        .expect("Failed to create table toc_entry");
-
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS reference (
+            );
+            CREATE TABLE IF NOT EXISTS reference (
                 paper_lyt TEXT NOT NULL,
                 seq INTEGER NOT NULL,
                 text TEXT NOT NULL,
                 FOREIGN KEY (paper_lyt) REFERENCES paper(paper_lyt),
                 UNIQUE(paper_lyt, seq)
-            )",
-            [],
+            );
+            CREATE INDEX IF NOT EXISTS paper_league ON paper (league);
+            CREATE INDEX IF NOT EXISTS paper_year ON paper (year);
+            CREATE INDEX IF NOT EXISTS paper_team ON paper (team);
+        ",
+    },
+    Migration {
+        // Trigram full-text index over raw markdown (content_seq NULL) and content item bodies,
+        // used to prefilter grep candidates.
+        version: 2,
+        description: "grep trigram index",
+        sql: "
+            CREATE VIRTUAL TABLE IF NOT EXISTS grep_index USING fts5(
+                paper_lyt UNINDEXED,
+                content_seq UNINDEXED,
+                content_type UNINDEXED,
+                text,
+                tokenize = 'trigram'
+            );
+            INSERT INTO grep_index (paper_lyt, content_seq, content_type, text)
+                SELECT paper_lyt, NULL, NULL, raw_markdown FROM paper
+                WHERE raw_markdown IS NOT NULL AND NOT EXISTS (SELECT 1 FROM grep_index);
+            INSERT INTO grep_index (paper_lyt, content_seq, content_type, text)
+                SELECT paper_lyt, content_seq, content_type, body FROM toc_entry
+                WHERE body IS NOT NULL AND body != ''
+                AND NOT EXISTS (SELECT 1 FROM grep_index WHERE content_seq IS NOT NULL);
+        ",
+    },
+    Migration {
+        // The IDF is stored per index version so the sparse vector indices always match the
+        // collection they were written to. Existing rows belong to the unversioned legacy
+        // collection, which is version 0.
+        version: 3,
+        description: "versioned idf_index",
+        sql: "
+            CREATE TABLE idf_index_versioned (
+                version INTEGER NOT NULL,
+                word TEXT NOT NULL,
+                idx INTEGER NOT NULL,
+                idf REAL NOT NULL,
+                UNIQUE(version, word)
+            );
+            INSERT INTO idf_index_versioned (version, word, idx, idf)
+                SELECT 0, word, idx, idf FROM idf_index;
+            DROP TABLE idf_index;
+            ALTER TABLE idf_index_versioned RENAME TO idf_index;
+        ",
+    },
+];
+
+impl SqliteClient {
+    /// Opens the database and checks its schema version. A new database is created at the
+    /// latest version; an existing one with pending migrations is rejected.
+    pub fn new(config: SqliteConfig) -> Result<Self, MetadataClientError> {
+        let pool = SqlitePool::open(
+            &config.filename,
+            config.pool_size.unwrap_or(DEFAULT_POOL_SIZE),
         )
-        .expect("Failed to create table reference");
+        .map_err(|source| MigrationError::Sqlite {
+            database: config.filename.clone(),
+            source,
+        })?;
+        pool.write_blocking(|conn| Self::migrator(&config).check(conn))?;
 
-        conn.execute("CREATE INDEX IF NOT EXISTS paper_league ON paper (league)", [])
-            .expect("Failed to create index on paper (league)");
+        Ok(Self { pool })
+    }
+
+    pub fn migration_status(config: &SqliteConfig) -> Result<MigrationStatus, MigrationError> {
+        let conn = Self::open(config)?;
+        Self::migrator(config).status(&conn)
+    }
+
+    pub fn migrate(config: &SqliteConfig) -> Result<Vec<&'static Migration>, MigrationError> {
+        let mut conn = Self::open(config)?;
+        Self::migrator(config).apply(&mut conn)
+    }
+
+    fn migrator(config: &SqliteConfig) -> Migrator {
+        Migrator::new(format!("{} ({})", DATABASE_NAME, config.filename), MIGRATIONS)
+    }
+
+    fn open(config: &SqliteConfig) -> Result<Connection, MigrationError> {
+        let map_err = |source| MigrationError::Sqlite {
+            database: config.filename.clone(),
+            source,
+        };
 
-        conn.execute("CREATE INDEX IF NOT EXISTS paper_year ON paper (year)", [])
-            .expect("Failed to create index on paper (year)");
+        let conn = Connection::open(&config.filename).map_err(map_err)?;
+
+        // Enable WAL mode for better concurrency
+        conn.query_row("PRAGMA journal_mode=WAL;", [], |_| Ok(()))
+            .map_err(map_err)?;
 
-        conn.execute("CREATE INDEX IF NOT EXISTS paper_team ON paper (team)", [])
-            .expect("Failed to create index on paper (team)");
+        Ok(conn)
     }
 }
 
 impl MetadataClient for SqliteClient {
     fn store_idf<'a>(
         &'a self,
+        version: u32,
         map: IDF,
     ) -> Pin<Box<dyn Future<Output = Result<(), MetadataClientError>> + Send + 'a>> {
-        let conn = self.conn.clone();
-
         Box::pin(async move {
-            tokio::task::spawn_blocking(move || {
-                let mut conn = conn.lock().unwrap();
-
+            self.pool.write(move |conn| {
                 let tx = conn
                     .transaction()
                     .map_err(|e| MetadataClientError::Internal(e.to_string()))?;
 
                 {
                     // Clear existing entries to ensure overwrite
-                    tx.execute("DELETE FROM idf_index", [])
+                    tx.execute("DELETE FROM idf_index WHERE version = ?1", params![version])
                         .map_err(|e| MetadataClientError::Internal(e.to_string()))?;
 
                     let mut stmt = tx
                         .prepare(
-                            "INSERT INTO idf_index (word, idx, idf) VALUES (?1, ?2, ?3)",
+                            "INSERT INTO idf_index (version, word, idx, idf) VALUES (?1, ?2, ?3, ?4)",
                         )
                         .map_err(|e| MetadataClientError::Internal(e.to_string()))?;
 
                     for (word, (idx, idf)) in map.iter() {
-                        stmt.execute(params![word, idx, idf])
+                        stmt.execute(params![version, word, idx, idf])
                             .map_err(|e| MetadataClientError::Internal(e.to_string()))?;
                     }
                 }
@@ -163,20 +207,17 @@ impl MetadataClient for SqliteClient {
 
     fn load_idf<'a>(
         &'a self,
+        version: u32,
     ) -> Pin<Box<dyn Future<Output = Result<IDF, MetadataClientError>> + Send + 'a>> {
-        let conn = self.conn.clone();
-
         Box::pin(async move {
-            tokio::task::spawn_blocking(move || {
-                let conn = conn.lock().unwrap();
-
+            self.pool.read(move |conn| {
                 let mut stmt = conn
-                    .prepare("SELECT word, idx, idf FROM idf_index")
+                    .prepare("SELECT word, idx, idf FROM idf_index WHERE version = ?1")
                     .map_err(|e| MetadataClientError::Internal(e.to_string()))?;
 
-                info!("Retrieving IDF from sqlite database..");
+                info!("Retrieving IDF version {version} from sqlite database..");
                 let rows = stmt
-                    .query_map([], |row| {
+                    .query_map(params![version], |row| {
                         Ok((
                             row.get::<_, String>(0)?,
                             row.get::<_, u32>(1)?,
@@ -201,6 +242,21 @@ impl MetadataClient for SqliteClient {
         })
     }
 
+    fn delete_idf<'a>(
+        &'a self,
+        version: u32,
+    ) -> Pin<Box<dyn Future<Output = Result<(), MetadataClientError>> + Send + 'a>> {
+        Box::pin(async move {
+            self.pool.write(move |conn| {
+                conn.execute("DELETE FROM idf_index WHERE version = ?1", params![version])
+                    .map_err(|e| MetadataClientError::Internal(e.to_string()))?;
+                Ok(())
+            })
+            .await
+            .map_err(|e| MetadataClientError::Internal(e.to_string()))?
+        })
+    }
+
     fn load_tdps<'a>(
         &'a self,
     ) -> Pin<
@@ -210,12 +266,8 @@ impl MetadataClient for SqliteClient {
                 + 'a,
         >,
     > {
-        let conn = self.conn.clone();
-
         Box::pin(async move {
-            tokio::task::spawn_blocking(move || {
-                let conn = conn.lock().unwrap();
-
+            self.pool.read(move |conn| {
                 let mut stmt = conn
                     .prepare("SELECT paper_lyt FROM paper")
                     .map_err(|e| MetadataClientError::Internal(e.to_string()))?;
@@ -251,12 +303,8 @@ impl MetadataClient for SqliteClient {
                 + 'a,
         >,
     > {
-        let conn = self.conn.clone();
-
         Box::pin(async move {
-            tokio::task::spawn_blocking(move || {
-                let conn = conn.lock().unwrap();
-
+            self.pool.read(move |conn| {
                 let mut stmt = conn
                     .prepare("SELECT DISTINCT team FROM paper")
                     .map_err(|e| MetadataClientError::Internal(e.to_string()))?;
@@ -290,12 +338,8 @@ impl MetadataClient for SqliteClient {
                 + 'a,
         >,
     > {
-        let conn = self.conn.clone();
-
         Box::pin(async move {
-            tokio::task::spawn_blocking(move || {
-                let conn = conn.lock().unwrap();
-
+            self.pool.read(move |conn| {
                 let mut stmt = conn
                     .prepare("SELECT DISTINCT league FROM paper")
                     .map_err(|e| MetadataClientError::Internal(e.to_string()))?;
@@ -327,11 +371,8 @@ impl MetadataClient for SqliteClient {
         &'a self,
         tdp_name: data_structures::file::TDPName,
     ) -> Pin<Box<dyn Future<Output = Result<String, MetadataClientError>> + Send + 'a>> {
-        let conn = self.conn.clone();
-
         Box::pin(async move {
-            tokio::task::spawn_blocking(move || {
-                let conn = conn.lock().unwrap();
+            self.pool.read(move |conn| {
                 let paper_lyt = tdp_name.get_paper_lyt();
 
                 let mut stmt = conn
@@ -342,7 +383,7 @@ impl MetadataClient for SqliteClient {
                     .query_row(params![paper_lyt], |row| row.get(0))
                     .map_err(|e| match e {
                         rusqlite::Error::QueryReturnedNoRows => {
-                            MetadataClientError::NotFound(paper_lyt.clone())
+                            MetadataClientError::PaperNotFound(paper_lyt.clone())
                         }
                         _ => MetadataClientError::Internal(e.to_string()),
                     })?;
@@ -357,12 +398,8 @@ impl MetadataClient for SqliteClient {
     fn print_analytics<'a>(
         &'a self,
     ) -> Pin<Box<dyn Future<Output = Result<(), MetadataClientError>> + Send + 'a>> {
-        let conn = self.conn.clone();
-
         Box::pin(async move {
-            tokio::task::spawn_blocking(move || {
-                let conn = conn.lock().unwrap();
-
+            self.pool.read(move |conn| {
                 let tdp_count: i64 = conn
                     .query_row(
                         "SELECT COUNT(*) FROM paper",
@@ -395,11 +432,8 @@ impl MetadataClient for SqliteClient {
         &'a self,
         tdp: MarkdownTDP,
     ) -> Pin<Box<dyn Future<Output = Result<(), MetadataClientError>> + Send + 'a>> {
-        let conn = self.conn.clone();
-
         Box::pin(async move {
-            tokio::task::spawn_blocking(move || {
-                let mut conn = conn.lock().unwrap();
+            self.pool.write(move |conn| {
                 let paper_lyt = tdp.name.get_paper_lyt();
                 let league = tdp.name.league.name();
                 let year = tdp.name.year;
@@ -424,6 +458,8 @@ impl MetadataClient for SqliteClient {
                         .map_err(|e| MetadataClientError::Internal(e.to_string()))?;
                     tx.execute("DELETE FROM paper WHERE paper_lyt = ?1", params![paper_lyt])
                         .map_err(|e| MetadataClientError::Internal(e.to_string()))?;
+                    tx.execute("DELETE FROM grep_index WHERE paper_lyt = ?1", params![paper_lyt])
+                        .map_err(|e| MetadataClientError::Internal(e.to_string()))?;
 
                     // Insert paper
                     tx.execute(
@@ -475,6 +511,22 @@ impl MetadataClient for SqliteClient {
                             .map_err(|e| MetadataClientError::Internal(e.to_string()))?;
                     }
                     drop(ref_stmt);
+
+                    // Insert grep index rows: the raw markdown and every non-empty content body
+                    let mut grep_stmt = tx
+                        .prepare("INSERT INTO grep_index (paper_lyt, content_seq, content_type, text) VALUES (?1, ?2, ?3, ?4)")
+                        .map_err(|e| MetadataClientError::Internal(e.to_string()))?;
+
+                    grep_stmt
+                        .execute(params![paper_lyt, None::<u32>, None::<String>, raw_markdown])
+                        .map_err(|e| MetadataClientError::Internal(e.to_string()))?;
+
+                    for item in tdp.content_items.iter().filter(|item| !item.body.is_empty()) {
+                        grep_stmt
+                            .execute(params![paper_lyt, item.content_seq, item.content_type.as_str(), item.body])
+                            .map_err(|e| MetadataClientError::Internal(e.to_string()))?;
+                    }
+                    drop(grep_stmt);
                 }
 
                 tx.commit()
@@ -491,12 +543,8 @@ impl MetadataClient for SqliteClient {
         &'a self,
         paper_lyt: String,
     ) -> Pin<Box<dyn Future<Output = Result<Vec<TocEntry>, MetadataClientError>> + Send + 'a>> {
-        let conn = self.conn.clone();
-
         Box::pin(async move {
-            tokio::task::spawn_blocking(move || {
-                let conn = conn.lock().unwrap();
-
+            self.pool.read(move |conn| {
                 let mut stmt = conn
                     .prepare("SELECT content_seq, content_type, depth, title FROM toc_entry WHERE paper_lyt = ?1 ORDER BY content_seq")
                     .map_err(|e| MetadataClientError::Internal(e.to_string()))?;
@@ -526,10 +574,7 @@ impl MetadataClient for SqliteClient {
                 }
 
                 if results.is_empty() {
-                    return Err(MetadataClientError::NotFound(format!(
-                        "No toc entries found for paper_lyt: {}",
-                        paper_lyt
-                    )));
+                    return Err(MetadataClientError::PaperNotFound(paper_lyt));
                 }
 
                 Ok(results)
@@ -544,12 +589,8 @@ impl MetadataClient for SqliteClient {
         paper_lyt: String,
         content_seq: u32,
     ) -> Pin<Box<dyn Future<Output = Result<ContentItem, MetadataClientError>> + Send + 'a>> {
-        let conn = self.conn.clone();
-
         Box::pin(async move {
-            tokio::task::spawn_blocking(move || {
-                let conn = conn.lock().unwrap();
-
+            self.pool.read(move |conn| {
                 let mut stmt = conn
                     .prepare("SELECT content_seq, content_type, depth, title, body, image_path FROM toc_entry WHERE paper_lyt = ?1 AND content_seq = ?2")
                     .map_err(|e| MetadataClientError::Internal(e.to_string()))?;
@@ -597,12 +638,8 @@ impl MetadataClient for SqliteClient {
         end_seq_exclusive: u32,
     ) -> Pin<Box<dyn Future<Output = Result<Vec<ContentItem>, MetadataClientError>> + Send + 'a>>
     {
-        let conn = self.conn.clone();
-
         Box::pin(async move {
-            tokio::task::spawn_blocking(move || {
-                let conn = conn.lock().unwrap();
-
+            self.pool.read(move |conn| {
                 let mut stmt = conn
                     .prepare(
                         "SELECT content_seq, content_type, depth, title, body, image_path
@@ -658,12 +695,8 @@ impl MetadataClient for SqliteClient {
         &'a self,
         paper_lyt: String,
     ) -> Pin<Box<dyn Future<Output = Result<String, MetadataClientError>> + Send + 'a>> {
-        let conn = self.conn.clone();
-
         Box::pin(async move {
-            tokio::task::spawn_blocking(move || {
-                let conn = conn.lock().unwrap();
-
+            self.pool.read(move |conn| {
                 let mut stmt = conn
                     .prepare("SELECT abstract_text FROM paper WHERE paper_lyt = ?1")
                     .map_err(|e| MetadataClientError::Internal(e.to_string()))?;
@@ -672,10 +705,7 @@ impl MetadataClient for SqliteClient {
                     .query_row(params![paper_lyt], |row| row.get(0))
                     .map_err(|e| match e {
                         rusqlite::Error::QueryReturnedNoRows => {
-                            MetadataClientError::NotFound(format!(
-                                "Paper not found for paper_lyt: {}",
-                                paper_lyt
-                            ))
+                            MetadataClientError::PaperNotFound(paper_lyt.clone())
                         }
                         _ => MetadataClientError::Internal(e.to_string()),
                     })?;
@@ -696,11 +726,8 @@ impl MetadataClient for SqliteClient {
         &'a self,
         paper_lyt: String,
     ) -> Pin<Box<dyn Future<Output = Result<Vec<String>, MetadataClientError>> + Send + 'a>> {
-        let conn = self.conn.clone();
-
         Box::pin(async move {
-            tokio::task::spawn_blocking(move || {
-                let conn = conn.lock().unwrap();
+            self.pool.read(move |conn| {
                 let mut stmt = conn
                     .prepare("SELECT text FROM reference WHERE paper_lyt = ?1 ORDER BY seq")
                     .map_err(|e| MetadataClientError::Internal(e.to_string()))?;
@@ -722,12 +749,8 @@ impl MetadataClient for SqliteClient {
         &'a self,
         paper_lyt: String,
     ) -> Pin<Box<dyn Future<Output = Result<PaperInfo, MetadataClientError>> + Send + 'a>> {
-        let conn = self.conn.clone();
-
         Box::pin(async move {
-            tokio::task::spawn_blocking(move || {
-                let conn = conn.lock().unwrap();
-
+            self.pool.read(move |conn| {
                 // Load title, urls from paper table
                 let (title, urls_json): (Option<String>, Option<String>) = conn
                     .query_row(
@@ -737,7 +760,7 @@ impl MetadataClient for SqliteClient {
                     )
                     .map_err(|e| match e {
                         rusqlite::Error::QueryReturnedNoRows => {
-                            MetadataClientError::NotFound(format!("Paper not found: {}", paper_lyt))
+                            MetadataClientError::PaperNotFound(paper_lyt.clone())
                         }
                         _ => MetadataClientError::Internal(e.to_string()),
                     })?;
@@ -783,6 +806,91 @@ impl MetadataClient for SqliteClient {
         })
     }
 
+    fn grep<'a>(
+        &'a self,
+        query: GrepQuery,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<GrepMatch>, MetadataClientError>> + Send + 'a>> {
+        Box::pin(async move {
+            self.pool.read(move |conn| {
+                let regex = query
+                    .build_regex()
+                    .map_err(|e| MetadataClientError::Internal(e.to_string()))?;
+
+                let scope_condition = match query.scope {
+                    GrepScope::Content => "content_seq IS NOT NULL",
+                    GrepScope::Markdown => "content_seq IS NULL",
+                };
+
+                // Use the trigram index when the pattern contains a long enough literal,
+                // otherwise fall back to scanning every row in scope.
+                let literal = query.required_literal();
+                let sql = match literal {
+                    Some(_) => format!(
+                        "SELECT paper_lyt, content_seq, content_type, text FROM grep_index
+                         WHERE grep_index MATCH ?1 AND {scope_condition} ORDER BY paper_lyt, content_seq"
+                    ),
+                    None => format!(
+                        "SELECT paper_lyt, content_seq, content_type, text FROM grep_index
+                         WHERE {scope_condition} ORDER BY paper_lyt, content_seq"
+                    ),
+                };
+
+                let mut stmt = conn
+                    .prepare(&sql)
+                    .map_err(|e| MetadataClientError::Internal(e.to_string()))?;
+
+                let map_row = |row: &rusqlite::Row| -> rusqlite::Result<(String, Option<u32>, Option<String>, String)> {
+                    Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+                };
+                let mut rows = match &literal {
+                    Some(literal) => {
+                        let phrase = format!("\"{}\"", literal.replace('"', "\"\""));
+                        stmt.query_map(params![phrase], map_row)
+                    }
+                    None => stmt.query_map([], map_row),
+                }
+                .map_err(|e| MetadataClientError::Internal(e.to_string()))?;
+
+                let filter = query.filter.unwrap_or_default();
+                let mut results = Vec::new();
+
+                while results.len() < query.limit {
+                    let Some(row) = rows.next() else { break };
+                    let (paper_lyt, content_seq, content_type, text) =
+                        row.map_err(|e| MetadataClientError::Internal(e.to_string()))?;
+
+                    let Ok(tdp_name) = TDPName::try_from(paper_lyt.as_str()) else { continue };
+                    if !filter.matches_tdp_name(&tdp_name) {
+                        continue;
+                    }
+                    if let (Some(content_types), Some(content_type)) = (&filter.content_types, &content_type)
+                        && !content_types.contains(content_type)
+                    {
+                        continue;
+                    }
+
+                    for (start, end, matched, context) in find_matches(&regex, &text) {
+                        if results.len() >= query.limit {
+                            break;
+                        }
+                        results.push(GrepMatch {
+                            paper_lyt: paper_lyt.clone(),
+                            content_seq,
+                            content_type: content_type.clone(),
+                            start,
+                            end,
+                            matched,
+                            context,
+                        });
+                    }
+                }
+
+                Ok(results)
+            })
+            .await
+            .map_err(|e| MetadataClientError::Internal(e.to_string()))?
+        })
+    }
 }
 
 #[cfg(test)]
@@ -802,8 +910,9 @@ mod tests {
 
         let config = SqliteConfig {
             filename: db_filename.clone(),
+            pool_size: None,
         };
-        let client = SqliteClient::new(config);
+        let client = SqliteClient::new(config).unwrap();
 
         // 1. Store and load IDF
         let mut map = IDF::new();
@@ -811,11 +920,11 @@ mod tests {
         map.insert("banana".to_string(), (2, 2.0));
 
         client
-            .store_idf(map.clone())
+            .store_idf(1, map.clone())
             .await
             .expect("Failed to store map");
 
-        let loaded_map = client.load_idf().await.expect("Failed to load map");
+        let loaded_map = client.load_idf(1).await.expect("Failed to load map");
         assert_eq!(map, loaded_map, "Loaded map should match stored map");
 
         // 2. Overwrite with new data
@@ -825,13 +934,13 @@ mod tests {
         // "banana" is removed
 
         client
-            .store_idf(map_new.clone())
+            .store_idf(1, map_new.clone())
             .await
             .expect("Failed to overwrite map");
 
         // 3. Verify overwrite
         let loaded_map_new = client
-            .load_idf()
+            .load_idf(1)
             .await
             .expect("Failed to load overwritten map");
         assert_eq!(
@@ -845,7 +954,19 @@ mod tests {
             "Old keys should be removed on overwrite"
         );
 
-        // 4. Cleanup
+        // 4. Versions are independent
+        client
+            .store_idf(2, map.clone())
+            .await
+            .expect("Failed to store second version");
+        assert_eq!(client.load_idf(1).await.unwrap(), map_new);
+        assert_eq!(client.load_idf(2).await.unwrap(), map);
+
+        client.delete_idf(1).await.expect("Failed to delete version");
+        assert!(client.load_idf(1).await.unwrap().is_empty());
+        assert_eq!(client.load_idf(2).await.unwrap(), map);
+
+        // 5. Cleanup
         drop(client);
         fs::remove_file(&db_filename).expect("Failed to delete database file");
         let _ = fs::remove_file(format!("{}-wal", db_filename));
@@ -864,10 +985,11 @@ mod tests {
 
         let config = SqliteConfig {
             filename: db_filename.to_string(),
+            pool_size: None,
         };
-        let client = SqliteClient::new(config);
+        let client = SqliteClient::new(config)?;
 
-        let idfs = client.load_idf().await?;
+        let idfs = client.load_idf(0).await?;
         println!("Number of entries in {db_filename} (IDF): {}", idfs.len());
 
         let tdps = client.load_tdps().await?;
@@ -889,12 +1011,12 @@ mod tests {
 
         let config = SqliteConfig {
             filename: db_filename.clone(),
+            pool_size: None,
         };
-        let client = SqliteClient::new(config);
+        let client = SqliteClient::new(config).unwrap();
 
         // Insert rows directly into the paper table
-        {
-            let conn = client.conn.lock().unwrap();
+        client.pool.write_blocking(|conn| {
             conn.execute(
                 "INSERT INTO paper (paper_lyt, league, year, team, raw_markdown) VALUES (?1, ?2, ?3, ?4, ?5)",
                 params!["soccer_smallsize__2019__RoboTeam_Twente", "Soccer SmallSize", 2019, "RoboTeam Twente", "# Test markdown 1"],
@@ -907,7 +1029,7 @@ mod tests {
                 "INSERT INTO paper (paper_lyt, league, year, team, raw_markdown) VALUES (?1, ?2, ?3, ?4, ?5)",
                 params!["soccer_midsize__2020__RoboTeam_Twente", "Soccer MidSize", 2020, "RoboTeam Twente", "# Test markdown 3"],
             ).unwrap();
-        }
+        });
 
         // Test load_teams
         let teams = client.load_teams().await.expect("Failed to load teams");
@@ -952,8 +1074,9 @@ mod tests {
 
         let config = SqliteConfig {
             filename: db_filename.clone(),
+            pool_size: None,
         };
-        let client = SqliteClient::new(config);
+        let client = SqliteClient::new(config).unwrap();
 
         let league = data_structures::file::League::try_from("soccer_smallsize").unwrap();
         let team = data_structures::file::TeamName::new("RoboTeam Twente");
@@ -1084,4 +1207,201 @@ mod tests {
         let _ = fs::remove_file(format!("{}-wal", db_filename));
         let _ = fs::remove_file(format!("{}-shm", db_filename));
     }
+
+    #[tokio::test]
+    async fn test_grep() {
+        use data_structures::content::FrontMatter;
+        use data_structures::filter::Filter;
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let db_filename = format!("test_grep_{}.db", timestamp);
+
+        let client = SqliteClient::new(SqliteConfig {
+            filename: db_filename.clone(),
+            pool_size: None,
+        })
+        .unwrap();
+
+        let league = data_structures::file::League::try_from("soccer_smallsize").unwrap();
+        let name = TDPName::new(league, 2024, data_structures::file::TeamName::new("ER-Force"));
+        let paper_lyt = name.get_paper_lyt();
+
+        let tdp = MarkdownTDP {
+            name,
+            front_matter: FrontMatter {
+                title: "ER-Force 2024".to_string(),
+                authors: vec![],
+                institutions: vec![],
+                urls: vec![],
+                abstract_text: None,
+            },
+            content_items: vec![
+                ContentItem {
+                    content_seq: 0,
+                    content_type: ContentType::Text,
+                    depth: 1,
+                    title: "Electronics".to_string(),
+                    body: "The main board uses an STM32F7 and an nRF24L01+ radio.".to_string(),
+                    image_path: None,
+                },
+                ContentItem {
+                    content_seq: 1,
+                    content_type: ContentType::Table,
+                    depth: 2,
+                    title: "Microcontrollers".to_string(),
+                    body: "| MCU |\n| --- |\n| STM32F4 |\n| stm32f7 |".to_string(),
+                    image_path: None,
+                },
+            ],
+            references: vec![],
+            raw_markdown: "# ER-Force 2024\n\nThe main board uses an STM32F7.".to_string(),
+        };
+
+        client.store_paper(tdp.clone()).await.expect("Failed to store paper");
+
+        let query = |pattern: &str, regex: bool, case_sensitive: bool, scope: GrepScope| GrepQuery {
+            pattern: pattern.to_string(),
+            regex,
+            case_sensitive,
+            scope,
+            filter: None,
+            limit: 10,
+        };
+
+        // Literal, case sensitive
+        let matches = client.grep(query("STM32F7", false, true, GrepScope::Content)).await.unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].paper_lyt, paper_lyt);
+        assert_eq!(matches[0].content_seq, Some(0));
+        assert_eq!(matches[0].content_type.as_deref(), Some("text"));
+        assert_eq!((matches[0].start, matches[0].end), (23, 30));
+
+        // Literal, case insensitive
+        let matches = client.grep(query("STM32F7", false, false, GrepScope::Content)).await.unwrap();
+        assert_eq!(matches.len(), 2);
+
+        // Special characters are matched verbatim in literal mode
+        let matches = client.grep(query("nRF24L01+", false, true, GrepScope::Content)).await.unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].matched, "nRF24L01+");
+
+        // Regex with an indexed literal
+        let matches = client.grep(query(r"STM32F\d", true, true, GrepScope::Content)).await.unwrap();
+        let found: Vec<&str> = matches.iter().map(|m| m.matched.as_str()).collect();
+        assert_eq!(found, vec!["STM32F7", "STM32F4"]);
+
+        // Regex without an indexed literal falls back to a full scan
+        let matches = client.grep(query(r"F\d", true, true, GrepScope::Content)).await.unwrap();
+        let found: Vec<&str> = matches.iter().map(|m| m.matched.as_str()).collect();
+        assert_eq!(found, vec!["F7", "F2", "F4"]);
+
+        // Markdown scope
+        let matches = client.grep(query("STM32F7", false, true, GrepScope::Markdown)).await.unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].content_seq, None);
+
+        // Filters
+        let mut filter = Filter::default();
+        filter.add_content_type("table".to_string());
+        let mut filtered = query("STM32F7", false, false, GrepScope::Content);
+        filtered.filter = Some(filter);
+        let matches = client.grep(filtered).await.unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].content_seq, Some(1));
+
+        let mut filter = Filter::default();
+        filter.add_year(2019);
+        let mut filtered = query("STM32F7", false, false, GrepScope::Content);
+        filtered.filter = Some(filter);
+        assert!(client.grep(filtered).await.unwrap().is_empty());
+
+        // Limit
+        let mut limited = query("STM32F", false, true, GrepScope::Content);
+        limited.limit = 1;
+        assert_eq!(client.grep(limited).await.unwrap().len(), 1);
+
+        // Re-storing the paper does not duplicate index rows
+        client.store_paper(tdp).await.expect("Failed to re-store paper");
+        let matches = client.grep(query("nRF24L01+", false, true, GrepScope::Content)).await.unwrap();
+        assert_eq!(matches.len(), 1);
+
+        // Cleanup
+        drop(client);
+        fs::remove_file(&db_filename).expect("Failed to delete database file");
+        let _ = fs::remove_file(format!("{}-wal", db_filename));
+        let _ = fs::remove_file(format!("{}-shm", db_filename));
+    }
+
+    #[test]
+    fn test_migrations_are_ordered() {
+        for (i, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.version, i as u32 + 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_legacy_database_requires_migration() {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let db_filename = format!("test_migrate_{}.db", timestamp);
+        let config = SqliteConfig {
+            filename: db_filename.clone(),
+            pool_size: None,
+        };
+
+        // A database created before schema versioning existed
+        {
+            let conn = Connection::open(&db_filename).unwrap();
+            conn.execute_batch(MIGRATIONS[0].sql).unwrap();
+            conn.execute(
+                "INSERT INTO paper (paper_lyt, league, year, team, raw_markdown) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params!["soccer_smallsize__2019__RoboTeam_Twente", "Soccer SmallSize", 2019, "RoboTeam Twente", "Uses an STM32F7"],
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO idf_index (word, idx, idf) VALUES (?1, ?2, ?3)",
+                params!["kicker", 0, 2.5],
+            )
+            .unwrap();
+        }
+
+        assert!(matches!(
+            SqliteClient::new(config.clone()),
+            Err(MetadataClientError::Migration(MigrationError::Pending { current: 0, .. }))
+        ));
+
+        let status = SqliteClient::migration_status(&config).unwrap();
+        assert_eq!(status.pending.len(), MIGRATIONS.len());
+
+        let applied = SqliteClient::migrate(&config).unwrap();
+        assert_eq!(applied.len(), MIGRATIONS.len());
+
+        let client = SqliteClient::new(config).unwrap();
+        let matches = client
+            .grep(GrepQuery {
+                pattern: "STM32F7".to_string(),
+                regex: false,
+                case_sensitive: true,
+                scope: GrepScope::Markdown,
+                filter: None,
+                limit: 10,
+            })
+            .await
+            .unwrap();
+        assert_eq!(matches.len(), 1, "grep index should be backfilled by the migration");
+
+        let idf = client.load_idf(0).await.unwrap();
+        assert_eq!(idf.get("kicker"), Some(&(0, 2.5)), "legacy IDF should become version 0");
+
+        // Cleanup
+        drop(client);
+        fs::remove_file(&db_filename).expect("Failed to delete database file");
+        let _ = fs::remove_file(format!("{}-wal", db_filename));
+        let _ = fs::remove_file(format!("{}-shm", db_filename));
+    }
 }
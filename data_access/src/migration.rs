@@ -0,0 +1,306 @@
+use rusqlite::{Connection, params};
+
+/// A forward-only schema change. Versions start at 1 and must be strictly increasing.
+#[derive(Debug)]
+pub struct Migration {
+    pub version: u32,
+    pub description: &'static str,
+    pub sql: &'static str,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum MigrationError {
+    #[error("SQLite error in {database}: {source}")]
+    Sqlite {
+        database: String,
+        #[source]
+        source: rusqlite::Error,
+    },
+    #[error(
+        "{database} is at schema version {current}, but this build requires version {latest} ({pending} pending migration(s)). Run `cargo run -p tools --bin migrate -- apply` to upgrade."
+    )]
+    Pending {
+        database: String,
+        current: u32,
+        latest: u32,
+        pending: usize,
+    },
+    #[error(
+        "{database} is at schema version {current}, which is newer than the latest version {latest} known to this build. Upgrade the application."
+    )]
+    TooNew {
+        database: String,
+        current: u32,
+        latest: u32,
+    },
+}
+
+#[derive(Debug)]
+pub struct MigrationStatus {
+    pub database: String,
+    pub current: u32,
+    pub latest: u32,
+    pub pending: Vec<&'static Migration>,
+}
+
+/// Applies an ordered list of migrations to a database and records them in `schema_version`.
+pub struct Migrator {
+    database: String,
+    migrations: &'static [Migration],
+}
+
+impl Migrator {
+    pub fn new(database: impl Into<String>, migrations: &'static [Migration]) -> Self {
+        Self {
+            database: database.into(),
+            migrations,
+        }
+    }
+
+    pub fn latest_version(&self) -> u32 {
+        self.migrations.last().map(|m| m.version).unwrap_or(0)
+    }
+
+    pub fn status(&self, conn: &Connection) -> Result<MigrationStatus, MigrationError> {
+        self.ensure_version_table(conn)?;
+        let current = self.current_version(conn)?;
+
+        Ok(MigrationStatus {
+            database: self.database.clone(),
+            current,
+            latest: self.latest_version(),
+            pending: self
+                .migrations
+                .iter()
+                .filter(|m| m.version > current)
+                .collect(),
+        })
+    }
+
+    /// Applies all pending migrations, each in its own transaction.
+    pub fn apply(&self, conn: &mut Connection) -> Result<Vec<&'static Migration>, MigrationError> {
+        let status = self.status(conn)?;
+        if status.current > status.latest {
+            return Err(MigrationError::TooNew {
+                database: self.database.clone(),
+                current: status.current,
+                latest: status.latest,
+            });
+        }
+
+        for migration in &status.pending {
+            let tx = conn.transaction().map_err(|e| self.sqlite_error(e))?;
+            tx.execute_batch(migration.sql)
+                .map_err(|e| self.sqlite_error(e))?;
+            tx.execute(
+                "INSERT INTO schema_version (version, description, applied_at) VALUES (?1, ?2, ?3)",
+                params![
+                    migration.version,
+                    migration.description,
+                    chrono::Utc::now().to_rfc3339()
+                ],
+            )
+            .map_err(|e| self.sqlite_error(e))?;
+            tx.commit().map_err(|e| self.sqlite_error(e))?;
+
+            tracing::info!(
+                "Applied migration {} ({}) to {}",
+                migration.version,
+                migration.description,
+                self.database
+            );
+        }
+
+        Ok(status.pending)
+    }
+
+    /// Startup check. A brand-new database is initialised with all migrations; an existing
+    /// database must already be at the latest version.
+    pub fn check(&self, conn: &mut Connection) -> Result<(), MigrationError> {
+        if self.is_empty(conn)? {
+            self.apply(conn)?;
+            return Ok(());
+        }
+
+        let status = self.status(conn)?;
+        if status.current > status.latest {
+            return Err(MigrationError::TooNew {
+                database: self.database.clone(),
+                current: status.current,
+                latest: status.latest,
+            });
+        }
+        if !status.pending.is_empty() {
+            return Err(MigrationError::Pending {
+                database: self.database.clone(),
+                current: status.current,
+                latest: status.latest,
+                pending: status.pending.len(),
+            });
+        }
+
+        Ok(())
+    }
+
+    fn is_empty(&self, conn: &Connection) -> Result<bool, MigrationError> {
+        let tables: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name != 'schema_version'",
+                [],
+                |row| row.get(0),
+            )
+            .map_err(|e| self.sqlite_error(e))?;
+        let current = if self.has_version_table(conn)? {
+            self.current_version(conn)?
+        } else {
+            0
+        };
+
+        Ok(tables == 0 && current == 0)
+    }
+
+    fn has_version_table(&self, conn: &Connection) -> Result<bool, MigrationError> {
+        conn.query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'schema_version'",
+            [],
+            |row| row.get::<_, i64>(0),
+        )
+        .map(|count| count > 0)
+        .map_err(|e| self.sqlite_error(e))
+    }
+
+    fn ensure_version_table(&self, conn: &Connection) -> Result<(), MigrationError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS schema_version (
+                version INTEGER PRIMARY KEY,
+                description TEXT NOT NULL,
+                applied_at TEXT NOT NULL
+            )",
+            [],
+        )
+        .map(|_| ())
+        .map_err(|e| self.sqlite_error(e))
+    }
+
+    fn current_version(&self, conn: &Connection) -> Result<u32, MigrationError> {
+        conn.query_row(
+            "SELECT COALESCE(MAX(version), 0) FROM schema_version",
+            [],
+            |row| row.get(0),
+        )
+        .map_err(|e| self.sqlite_error(e))
+    }
+
+    fn sqlite_error(&self, source: rusqlite::Error) -> MigrationError {
+        MigrationError::Sqlite {
+            database: self.database.clone(),
+            source,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static MIGRATIONS: &[Migration] = &[
+        Migration {
+            version: 1,
+            description: "create thing",
+            sql: "CREATE TABLE thing (id INTEGER PRIMARY KEY);",
+        },
+        Migration {
+            version: 2,
+            description: "add name to thing",
+            sql: "ALTER TABLE thing ADD COLUMN name TEXT;",
+        },
+    ];
+
+    #[test]
+    fn test_fresh_database_is_initialised() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        let migrator = Migrator::new("test.db", MIGRATIONS);
+
+        migrator.check(&mut conn).unwrap();
+
+        let status = migrator.status(&conn).unwrap();
+        assert_eq!(status.current, 2);
+        assert!(status.pending.is_empty());
+        conn.execute("INSERT INTO thing (name) VALUES ('a')", []).unwrap();
+    }
+
+    #[test]
+    fn test_existing_database_with_pending_migrations_fails() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        Migrator::new("test.db", &MIGRATIONS[..1]).apply(&mut conn).unwrap();
+
+        let migrator = Migrator::new("test.db", MIGRATIONS);
+        let err = migrator.check(&mut conn).unwrap_err();
+        assert!(matches!(
+            err,
+            MigrationError::Pending { current: 1, latest: 2, pending: 1, .. }
+        ));
+        assert!(err.to_string().contains("migrate"));
+
+        let applied = migrator.apply(&mut conn).unwrap();
+        assert_eq!(applied.len(), 1);
+        assert_eq!(applied[0].version, 2);
+        migrator.check(&mut conn).unwrap();
+    }
+
+    #[test]
+    fn test_legacy_database_without_version_table_fails() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("CREATE TABLE thing (id INTEGER PRIMARY KEY);").unwrap();
+
+        let migrator = Migrator::new("test.db", MIGRATIONS);
+        assert!(matches!(
+            migrator.check(&mut conn),
+            Err(MigrationError::Pending { current: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn test_newer_database_fails() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        Migrator::new("test.db", MIGRATIONS).apply(&mut conn).unwrap();
+
+        let migrator = Migrator::new("test.db", &MIGRATIONS[..1]);
+        assert!(matches!(
+            migrator.check(&mut conn),
+            Err(MigrationError::TooNew { current: 2, latest: 1, .. })
+        ));
+        assert!(migrator.apply(&mut conn).is_err());
+    }
+
+    #[test]
+    fn test_failed_migration_is_rolled_back() {
+        static BROKEN: &[Migration] = &[
+            Migration {
+                version: 1,
+                description: "create thing",
+                sql: "CREATE TABLE thing (id INTEGER PRIMARY KEY);",
+            },
+            Migration {
+                version: 2,
+                description: "broken",
+                sql: "CREATE TABLE other (id INTEGER); ALTER TABLE missing ADD COLUMN x;",
+            },
+        ];
+
+        let mut conn = Connection::open_in_memory().unwrap();
+        let migrator = Migrator::new("test.db", BROKEN);
+        assert!(migrator.apply(&mut conn).is_err());
+
+        let status = migrator.status(&conn).unwrap();
+        assert_eq!(status.current, 1);
+        let other: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM sqlite_master WHERE name = 'other'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(other, 0);
+    }
+}
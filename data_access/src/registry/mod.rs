@@ -3,15 +3,18 @@ pub use sqlite_client::{SqliteRegistryClient, SqliteRegistryConfig};
 
 use std::future::Future;
 use std::pin::Pin;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 #[derive(thiserror::Error, Debug)]
 pub enum RegistryError {
     #[error("Internal error: {0}")]
     Internal(String),
+    #[error(transparent)]
+    Migration(#[from] crate::migration::MigrationError),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct RegistryEntry {
     pub key: String,
     pub value: String,
@@ -1,12 +1,13 @@
 use std::future::Future;
 use std::pin::Pin;
-use std::sync::{Arc, Mutex};
 use hmac::{Hmac, Mac};
 use rusqlite::{Connection, params};
 use serde::Deserialize;
 use sha2::Sha256;
 use subtle::ConstantTimeEq;
+use crate::migration::{Migration, MigrationError, MigrationStatus, Migrator};
 use crate::registry::{RegistryEntry, RegistryClient, RegistryError};
+use crate::sqlite_pool::{DEFAULT_POOL_SIZE, SqlitePool, SqlitePoolError};
 
 type HmacSha256 = Hmac<Sha256>;
 
@@ -18,53 +19,74 @@ pub struct SqliteRegistryConfig {
     pub filename: String,
     pub master_password: Option<String>,
     pub salt: Option<String>,
+    /// Number of read-only connections. Defaults to 4.
+    pub pool_size: Option<usize>,
 }
 
 pub struct SqliteRegistryClient {
-    conn: Arc<Mutex<Connection>>,
+    pool: SqlitePool,
     salt: String,
 }
 
+const DATABASE_NAME: &str = "registry database";
+
+static MIGRATIONS: &[Migration] = &[Migration {
+    version: 1,
+    description: "config, team_entries and league_entries tables",
+    sql: "
+        CREATE TABLE IF NOT EXISTS config (
+            key   TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS team_entries (
+            id         INTEGER PRIMARY KEY AUTOINCREMENT,
+            team_name  TEXT NOT NULL,
+            key        TEXT NOT NULL,
+            value      TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_team_entries_team_name
+            ON team_entries(team_name);
+        CREATE TABLE IF NOT EXISTS league_entries (
+            id          INTEGER PRIMARY KEY AUTOINCREMENT,
+            league_name TEXT NOT NULL,
+            key         TEXT NOT NULL,
+            value       TEXT NOT NULL,
+            updated_at  TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_league_entries_league_name
+            ON league_entries(league_name);
+    ",
+}];
+
 impl SqliteRegistryClient {
-    pub fn new(config: SqliteRegistryConfig) -> Self {
-        let conn = Connection::open(&config.filename)
-            .expect("Failed to open SQLite database");
-
-        conn.execute_batch("PRAGMA journal_mode=WAL;")
-            .expect("Failed to enable WAL mode");
-
-        conn.execute_batch(
-            "CREATE TABLE IF NOT EXISTS config (
-                key   TEXT PRIMARY KEY,
-                value TEXT NOT NULL
-            );
-            CREATE TABLE IF NOT EXISTS team_entries (
-                id         INTEGER PRIMARY KEY AUTOINCREMENT,
-                team_name  TEXT NOT NULL,
-                key        TEXT NOT NULL,
-                value      TEXT NOT NULL,
-                updated_at TEXT NOT NULL
-            );
-            CREATE INDEX IF NOT EXISTS idx_team_entries_team_name
-                ON team_entries(team_name);
-            CREATE TABLE IF NOT EXISTS league_entries (
-                id          INTEGER PRIMARY KEY AUTOINCREMENT,
-                league_name TEXT NOT NULL,
-                key         TEXT NOT NULL,
-                value       TEXT NOT NULL,
-                updated_at  TEXT NOT NULL
-            );
-            CREATE INDEX IF NOT EXISTS idx_league_entries_league_name
-                ON league_entries(league_name);",
+    pub fn new(config: SqliteRegistryConfig) -> Result<Self, RegistryError> {
+        let pool = SqlitePool::open(
+            &config.filename,
+            config.pool_size.unwrap_or(DEFAULT_POOL_SIZE),
         )
-        .expect("Failed to create tables");
+        .map_err(|source| MigrationError::Sqlite {
+            database: config.filename.clone(),
+            source,
+        })?;
+        let salt = pool.write_blocking(|conn| Self::initialize(conn, &config))?;
+
+        Ok(SqliteRegistryClient { pool, salt })
+    }
+
+    /// Runs the migration check and makes sure the salt and master password hash are stored.
+    /// Returns the salt.
+    fn initialize(conn: &mut Connection, config: &SqliteRegistryConfig) -> Result<String, RegistryError> {
+        Self::migrator(config).check(conn)?;
+
+        let internal = |e: rusqlite::Error| RegistryError::Internal(e.to_string());
 
         let salt: String = if let Some(ref s) = config.salt {
             conn.execute(
                 "INSERT OR REPLACE INTO config (key, value) VALUES (?1, ?2)",
                 params![CONFIG_KEY_SALT, s],
             )
-            .expect("Failed to store salt");
+            .map_err(internal)?;
             s.clone()
         } else {
             let existing: Option<String> = conn
@@ -84,7 +106,7 @@ impl SqliteRegistryClient {
                     "INSERT INTO config (key, value) VALUES (?1, ?2)",
                     params![CONFIG_KEY_SALT, s],
                 )
-                .expect("Failed to store salt");
+                .map_err(internal)?;
                 s
             }
         };
@@ -105,14 +127,37 @@ impl SqliteRegistryClient {
                     "INSERT INTO config (key, value) VALUES (?1, ?2)",
                     params![CONFIG_KEY_MASTER_HASH, hash],
                 )
-                .expect("Failed to store master_hash");
+                .map_err(internal)?;
             }
         }
 
-        SqliteRegistryClient {
-            conn: Arc::new(Mutex::new(conn)),
-            salt,
-        }
+        Ok(salt)
+    }
+
+    pub fn migration_status(config: &SqliteRegistryConfig) -> Result<MigrationStatus, MigrationError> {
+        let conn = Self::open(config)?;
+        Self::migrator(config).status(&conn)
+    }
+
+    pub fn migrate(config: &SqliteRegistryConfig) -> Result<Vec<&'static Migration>, MigrationError> {
+        let mut conn = Self::open(config)?;
+        Self::migrator(config).apply(&mut conn)
+    }
+
+    fn migrator(config: &SqliteRegistryConfig) -> Migrator {
+        Migrator::new(format!("{} ({})", DATABASE_NAME, config.filename), MIGRATIONS)
+    }
+
+    fn open(config: &SqliteRegistryConfig) -> Result<Connection, MigrationError> {
+        let map_err = |source| MigrationError::Sqlite {
+            database: config.filename.clone(),
+            source,
+        };
+
+        let conn = Connection::open(&config.filename).map_err(map_err)?;
+        conn.execute_batch("PRAGMA journal_mode=WAL;").map_err(map_err)?;
+
+        Ok(conn)
     }
 
     fn compute_hmac(salt: &str, message: &str) -> String {
@@ -131,11 +176,14 @@ impl SqliteRegistryClient {
     }
 
 
-    fn get_entries(&self, table: &str, name_col: &str, name_val: &str) -> Result<Vec<RegistryEntry>, RegistryError> {
-        let conn = self.conn.lock().map_err(|e| {
-            RegistryError::Internal(format!("Lock poisoned: {e}"))
-        })?;
+    async fn get_entries(&self, table: &'static str, name_col: &'static str, name_val: &str) -> Result<Vec<RegistryEntry>, RegistryError> {
+        let name_val = name_val.to_string();
+        self.pool.read(move |conn| Self::query_entries(conn, table, name_col, &name_val))
+            .await
+            .map_err(pool_error)?
+    }
 
+    fn query_entries(conn: &Connection, table: &str, name_col: &str, name_val: &str) -> Result<Vec<RegistryEntry>, RegistryError> {
         let sql = format!("SELECT key, value, updated_at FROM {table} WHERE {name_col} = ?1 ORDER BY id");
         let mut stmt = conn.prepare_cached(&sql)
             .map_err(|e| RegistryError::Internal(e.to_string()))?;
@@ -155,12 +203,15 @@ impl SqliteRegistryClient {
         Ok(entries)
     }
 
-    fn set_entries(&self, table: &str, name_col: &str, name_val: &str, entries: Vec<(String, String)>) -> Result<(), RegistryError> {
-        let conn = self.conn.lock().map_err(|e| {
-            RegistryError::Internal(format!("Lock poisoned: {e}"))
-        })?;
+    async fn set_entries(&self, table: &'static str, name_col: &'static str, name_val: &str, entries: Vec<(String, String)>) -> Result<(), RegistryError> {
+        let name_val = name_val.to_string();
+        self.pool.write(move |conn| Self::replace_entries(conn, table, name_col, &name_val, entries))
+            .await
+            .map_err(pool_error)?
+    }
 
-        let tx = conn.unchecked_transaction().map_err(|e| {
+    fn replace_entries(conn: &mut Connection, table: &str, name_col: &str, name_val: &str, entries: Vec<(String, String)>) -> Result<(), RegistryError> {
+        let tx = conn.transaction().map_err(|e| {
             RegistryError::Internal(e.to_string())
         })?;
 
@@ -182,13 +233,17 @@ impl SqliteRegistryClient {
     }
 }
 
+fn pool_error(e: SqlitePoolError) -> RegistryError {
+    RegistryError::Internal(e.to_string())
+}
+
 impl RegistryClient for SqliteRegistryClient {
     fn get_team_metadata<'a>(
         &'a self,
         team_name: &'a str,
     ) -> Pin<Box<dyn Future<Output = Result<Vec<RegistryEntry>, RegistryError>> + Send + 'a>>
     {
-        Box::pin(async move { self.get_entries("team_entries", "team_name", team_name) })
+        Box::pin(async move { self.get_entries("team_entries", "team_name", team_name).await })
     }
 
     fn set_team_metadata<'a>(
@@ -197,7 +252,7 @@ impl RegistryClient for SqliteRegistryClient {
         entries: Vec<(String, String)>,
     ) -> Pin<Box<dyn Future<Output = Result<(), RegistryError>> + Send + 'a>>
     {
-        Box::pin(async move { self.set_entries("team_entries", "team_name", team_name, entries) })
+        Box::pin(async move { self.set_entries("team_entries", "team_name", team_name, entries).await })
     }
 
     fn verify_code<'a>(
@@ -212,17 +267,18 @@ impl RegistryClient for SqliteRegistryClient {
                 return Ok(true);
             }
 
-            let conn = self.conn.lock().map_err(|e| {
-                RegistryError::Internal(format!("Lock poisoned: {e}"))
-            })?;
-
-            let master_hash: Option<String> = conn
-                .query_row(
-                    "SELECT value FROM config WHERE key = ?1",
-                    params![CONFIG_KEY_MASTER_HASH],
-                    |row| row.get(0),
-                )
-                .ok();
+            let master_hash: Option<String> = self
+                .pool
+                .read(|conn| {
+                    conn.query_row(
+                        "SELECT value FROM config WHERE key = ?1",
+                        params![CONFIG_KEY_MASTER_HASH],
+                        |row| row.get(0),
+                    )
+                    .ok()
+                })
+                .await
+                .map_err(pool_error)?;
 
             if let Some(stored_hash) = master_hash {
                 let submitted_hash = Self::compute_hmac(&self.salt, code);
@@ -250,7 +306,7 @@ impl RegistryClient for SqliteRegistryClient {
         league_name: &'a str,
     ) -> Pin<Box<dyn Future<Output = Result<Vec<RegistryEntry>, RegistryError>> + Send + 'a>>
     {
-        Box::pin(async move { self.get_entries("league_entries", "league_name", league_name) })
+        Box::pin(async move { self.get_entries("league_entries", "league_name", league_name).await })
     }
 
     fn set_league_metadata<'a>(
@@ -259,7 +315,7 @@ impl RegistryClient for SqliteRegistryClient {
         entries: Vec<(String, String)>,
     ) -> Pin<Box<dyn Future<Output = Result<(), RegistryError>> + Send + 'a>>
     {
-        Box::pin(async move { self.set_entries("league_entries", "league_name", league_name, entries) })
+        Box::pin(async move { self.set_entries("league_entries", "league_name", league_name, entries).await })
     }
 }
 
@@ -272,7 +328,9 @@ mod tests {
             filename: ":memory:".to_string(),
             master_password: Some("test-master-pw".to_string()),
             salt: None,
+            pool_size: None,
         })
+        .unwrap()
     }
 
     #[tokio::test]
@@ -428,7 +486,9 @@ mod tests {
                 filename: db_path_str.clone(),
                 master_password: None,
                 salt: None,
-            });
+                pool_size: None,
+            })
+            .unwrap();
             client.generate_team_code("TeamA").await.unwrap()
         };
 
@@ -437,7 +497,9 @@ mod tests {
                 filename: db_path_str,
                 master_password: None,
                 salt: None,
-            });
+                pool_size: None,
+            })
+            .unwrap();
             client.generate_team_code("TeamA").await.unwrap()
         };
 
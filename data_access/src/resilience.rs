@@ -0,0 +1,489 @@
+//! Timeouts, retries and circuit breaking for calls to the embedding provider and Qdrant.
+//!
+//! Every call gets a per-operation timeout. Calls that time out or fail with a transient error
+//! are retried with jittered exponential backoff, and count towards the dependency's
+//! [`CircuitBreaker`]. Once it is open, calls fail fast with an "unavailable" error until a
+//! trial call gets through again.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+pub const DEFAULT_RETRIES: u32 = 2;
+pub const DEFAULT_RETRY_BACKOFF_MS: u64 = 200;
+pub const DEFAULT_FAILURE_THRESHOLD: u32 = 5;
+pub const DEFAULT_OPEN_MS: u64 = 30_000;
+
+/// Upper bound of a single retry delay
+const MAX_BACKOFF: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct ResilienceConfig {
+    pub embed: Option<ResiliencePolicy>,
+    pub vector: Option<ResiliencePolicy>,
+}
+
+/// Timeouts, retries and breaker of one dependency. Unset fields use the defaults.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct ResiliencePolicy {
+    /// Timeouts by operation name, e.g. `search_chunks = 3000`. Operations not listed keep
+    /// their client's default.
+    pub timeouts_ms: Option<HashMap<String, u64>>,
+    /// Extra attempts after a timeout or transient failure. Defaults to 2.
+    pub retries: Option<u32>,
+    /// Delay before the first retry, doubled for every further one and jittered. Defaults
+    /// to 200.
+    pub retry_backoff_ms: Option<u64>,
+    /// Consecutive failures that open the breaker. Defaults to 5.
+    pub failure_threshold: Option<u32>,
+    /// How long an open breaker fails calls fast before it lets trial calls through. Defaults
+    /// to 30000.
+    pub open_ms: Option<u64>,
+}
+
+impl ResiliencePolicy {
+    pub fn timeout(&self, operation: &str, default: Duration) -> Duration {
+        self.timeouts_ms
+            .as_ref()
+            .and_then(|timeouts| timeouts.get(operation))
+            .map(|ms| Duration::from_millis(*ms))
+            .unwrap_or(default)
+    }
+
+    pub fn retries(&self) -> u32 {
+        self.retries.unwrap_or(DEFAULT_RETRIES)
+    }
+
+    pub fn retry_backoff(&self) -> Duration {
+        Duration::from_millis(self.retry_backoff_ms.unwrap_or(DEFAULT_RETRY_BACKOFF_MS))
+    }
+
+    pub fn failure_threshold(&self) -> u32 {
+        self.failure_threshold
+            .unwrap_or(DEFAULT_FAILURE_THRESHOLD)
+            .max(1)
+    }
+
+    pub fn open_duration(&self) -> Duration {
+        Duration::from_millis(self.open_ms.unwrap_or(DEFAULT_OPEN_MS))
+    }
+}
+
+/// Errors of a client wrapped in [`Resilience`]
+pub trait DependencyError: Display + Sized {
+    /// Whether the dependency may succeed when asked again, such as on network errors. Other
+    /// errors are returned right away and do not count against the breaker.
+    fn is_transient(&self) -> bool;
+
+    /// The error for timed out calls and calls rejected by an open breaker
+    fn unavailable(message: String) -> Self;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakerState {
+    /// Calls go through
+    Closed,
+    /// Calls fail fast
+    Open,
+    /// The open period is over; a single trial call decides whether the breaker closes again
+    HalfOpen,
+}
+
+impl BreakerState {
+    /// Value of the `tdp_circuit_breaker_state` gauge
+    fn gauge_value(self) -> f64 {
+        match self {
+            BreakerState::Closed => 0.0,
+            BreakerState::HalfOpen => 1.0,
+            BreakerState::Open => 2.0,
+        }
+    }
+}
+
+/// Opens after `failure_threshold` consecutive failures of a dependency and rejects calls for
+/// `open_duration`. After that one trial call at a time is let through while the others keep
+/// failing fast: a success closes the breaker, a failure opens it again.
+pub struct CircuitBreaker {
+    name: String,
+    failure_threshold: u32,
+    open_duration: Duration,
+    inner: Mutex<BreakerInner>,
+}
+
+#[derive(Default)]
+struct BreakerInner {
+    failures: u32,
+    opened_at: Option<Instant>,
+    /// A half-open breaker's trial call is running
+    trial_in_flight: bool,
+}
+
+/// Permission to make one call. Dropping the permit of a trial call lets the next caller try,
+/// also when the trial was cancelled before it recorded an outcome.
+struct Permit<'a> {
+    breaker: &'a CircuitBreaker,
+    trial: bool,
+}
+
+impl Drop for Permit<'_> {
+    fn drop(&mut self) {
+        if self.trial {
+            self.breaker.lock().trial_in_flight = false;
+        }
+    }
+}
+
+impl CircuitBreaker {
+    pub fn new(name: &str, failure_threshold: u32, open_duration: Duration) -> Self {
+        let breaker = Self {
+            name: name.to_string(),
+            failure_threshold,
+            open_duration,
+            inner: Mutex::new(BreakerInner::default()),
+        };
+        breaker.report(BreakerState::Closed);
+        breaker
+    }
+
+    /// The dependency it guards, e.g. "openai" or "qdrant"
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn state(&self) -> BreakerState {
+        self.state_of(&self.lock())
+    }
+
+    fn state_of(&self, inner: &BreakerInner) -> BreakerState {
+        match inner.opened_at {
+            None => BreakerState::Closed,
+            Some(opened_at) if opened_at.elapsed() >= self.open_duration => BreakerState::HalfOpen,
+            Some(_) => BreakerState::Open,
+        }
+    }
+
+    /// A permit for a call, or `None` when the breaker is open or its trial call is running
+    fn permit(&self) -> Option<Permit<'_>> {
+        let mut inner = self.lock();
+        let trial = match self.state_of(&inner) {
+            BreakerState::Closed => false,
+            BreakerState::Open => return None,
+            BreakerState::HalfOpen if inner.trial_in_flight => return None,
+            BreakerState::HalfOpen => {
+                inner.trial_in_flight = true;
+                true
+            }
+        };
+        Some(Permit {
+            breaker: self,
+            trial,
+        })
+    }
+
+    /// The dependency answered, even if with an error of its own
+    fn record_success(&self) {
+        let mut inner = self.lock();
+        if inner.opened_at.is_some() {
+            tracing::info!("Circuit breaker for {} closed", self.name);
+        }
+        *inner = BreakerInner::default();
+        self.report(BreakerState::Closed);
+    }
+
+    fn record_failure(&self) {
+        let mut inner = self.lock();
+        inner.failures += 1;
+        let trial_failed = self.state_of(&inner) == BreakerState::HalfOpen;
+        if trial_failed || (inner.opened_at.is_none() && inner.failures >= self.failure_threshold) {
+            warn!(
+                "Circuit breaker for {} opened after {} consecutive failures",
+                self.name, inner.failures
+            );
+            inner.opened_at = Some(Instant::now());
+            self.report(BreakerState::Open);
+        }
+    }
+
+    fn report(&self, state: BreakerState) {
+        metrics::gauge!("tdp_circuit_breaker_state", "dependency" => self.name.clone())
+            .set(state.gauge_value());
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BreakerInner> {
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Timeouts, retries and the circuit breaker of one dependency
+pub struct Resilience {
+    policy: ResiliencePolicy,
+    breaker: Arc<CircuitBreaker>,
+}
+
+impl Resilience {
+    pub fn new(dependency: &str, policy: ResiliencePolicy) -> Self {
+        let breaker = CircuitBreaker::new(
+            dependency,
+            policy.failure_threshold(),
+            policy.open_duration(),
+        );
+        Self {
+            policy,
+            breaker: Arc::new(breaker),
+        }
+    }
+
+    pub fn breaker(&self) -> Arc<CircuitBreaker> {
+        self.breaker.clone()
+    }
+
+    /// Run `call` under the timeout of `operation`, retrying timeouts and transient failures.
+    /// Only for idempotent operations, since a timed out attempt may still have gone through.
+    pub async fn call<T, E, F, Fut>(
+        &self,
+        operation: &'static str,
+        default_timeout: Duration,
+        mut call: F,
+    ) -> Result<T, E>
+    where
+        E: DependencyError,
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let dependency = self.breaker.name();
+        let timeout = self.policy.timeout(operation, default_timeout);
+        let mut attempt = 0;
+        loop {
+            let Some(permit) = self.breaker.permit() else {
+                return Err(E::unavailable(format!(
+                    "{} circuit breaker is open",
+                    dependency
+                )));
+            };
+            attempt += 1;
+
+            let error = match tokio::time::timeout(timeout, call()).await {
+                Ok(Ok(value)) => {
+                    self.breaker.record_success();
+                    return Ok(value);
+                }
+                Ok(Err(e)) if !e.is_transient() => {
+                    self.breaker.record_success();
+                    return Err(e);
+                }
+                Ok(Err(e)) => e,
+                Err(_) => E::unavailable(format!(
+                    "{} {} timed out after {:?}",
+                    dependency, operation, timeout
+                )),
+            };
+            self.breaker.record_failure();
+            drop(permit);
+
+            if attempt > self.policy.retries() {
+                return Err(error);
+            }
+            let delay = self.backoff(attempt);
+            warn!(
+                "{} {} failed, retrying in {:?}: {}",
+                dependency, operation, delay, error
+            );
+            metrics::counter!(
+                "tdp_retries_total",
+                "dependency" => dependency.to_string(),
+                "operation" => operation
+            )
+            .increment(1);
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    /// Half of the exponential delay plus a random share of the other half, so clients that
+    /// failed together do not retry together
+    fn backoff(&self, attempt: u32) -> Duration {
+        let exponential = self
+            .policy
+            .retry_backoff()
+            .saturating_mul(2u32.saturating_pow(attempt - 1))
+            .min(MAX_BACKOFF);
+        let half = exponential / 2;
+        half + half.mul_f64(rand::rng().random::<f64>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[derive(Debug)]
+    enum TestError {
+        Transient,
+        Permanent,
+        Unavailable(String),
+    }
+
+    impl Display for TestError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{:?}", self)
+        }
+    }
+
+    impl DependencyError for TestError {
+        fn is_transient(&self) -> bool {
+            !matches!(self, TestError::Permanent)
+        }
+
+        fn unavailable(message: String) -> Self {
+            TestError::Unavailable(message)
+        }
+    }
+
+    fn resilience(retries: u32, failure_threshold: u32, open_ms: u64) -> Resilience {
+        Resilience::new(
+            "test",
+            ResiliencePolicy {
+                timeouts_ms: Some(HashMap::from([("slow".to_string(), 20)])),
+                retries: Some(retries),
+                retry_backoff_ms: Some(1),
+                failure_threshold: Some(failure_threshold),
+                open_ms: Some(open_ms),
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn test_retries_transient_failures() {
+        let resilience = resilience(2, 10, 1000);
+        let calls = AtomicU32::new(0);
+
+        let result = resilience
+            .call("op", Duration::from_secs(1), || async {
+                match calls.fetch_add(1, Ordering::SeqCst) {
+                    0 | 1 => Err(TestError::Transient),
+                    _ => Ok(42),
+                }
+            })
+            .await;
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        // Permanent errors are returned right away
+        calls.store(0, Ordering::SeqCst);
+        let result: Result<(), _> = resilience
+            .call("op", Duration::from_secs(1), || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(TestError::Permanent)
+            })
+            .await;
+        assert!(matches!(result, Err(TestError::Permanent)));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_timeout_per_operation() {
+        let resilience = resilience(0, 10, 1000);
+        let result = resilience
+            .call("slow", Duration::from_secs(10), || async {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                Ok::<_, TestError>(())
+            })
+            .await;
+        assert!(matches!(result, Err(TestError::Unavailable(m)) if m.contains("timed out")));
+    }
+
+    #[tokio::test]
+    async fn test_breaker_opens_and_recovers() {
+        let resilience = resilience(0, 2, 50);
+        let breaker = resilience.breaker();
+        let fail = || async { Err::<(), _>(TestError::Transient) };
+
+        let _ = resilience.call("op", Duration::from_secs(1), fail).await;
+        assert_eq!(breaker.state(), BreakerState::Closed);
+        let _ = resilience.call("op", Duration::from_secs(1), fail).await;
+        assert_eq!(breaker.state(), BreakerState::Open);
+
+        // Fails fast without calling
+        let calls = AtomicU32::new(0);
+        let result = resilience
+            .call("op", Duration::from_secs(1), || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Ok::<_, TestError>(())
+            })
+            .await;
+        assert!(matches!(result, Err(TestError::Unavailable(m)) if m.contains("open")));
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+        // A failed trial call opens it again, a successful one closes it
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert_eq!(breaker.state(), BreakerState::HalfOpen);
+        let _ = resilience.call("op", Duration::from_secs(1), fail).await;
+        assert_eq!(breaker.state(), BreakerState::Open);
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        let result = resilience
+            .call("op", Duration::from_secs(1), || async {
+                Ok::<_, TestError>(())
+            })
+            .await;
+        assert!(result.is_ok());
+        assert_eq!(breaker.state(), BreakerState::Closed);
+    }
+
+    #[tokio::test]
+    async fn test_half_open_lets_one_trial_through() {
+        let resilience = resilience(0, 1, 50);
+        let breaker = resilience.breaker();
+        let _ = resilience
+            .call("op", Duration::from_secs(1), || async {
+                Err::<(), _>(TestError::Transient)
+            })
+            .await;
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert_eq!(breaker.state(), BreakerState::HalfOpen);
+
+        let calls = AtomicU32::new(0);
+        let call = || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            Ok::<_, TestError>(())
+        };
+        let (trial, concurrent) = tokio::join!(
+            resilience.call("op", Duration::from_secs(1), call),
+            resilience.call("op", Duration::from_secs(1), call),
+        );
+        assert!(trial.is_ok());
+        assert!(matches!(concurrent, Err(TestError::Unavailable(m)) if m.contains("open")));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(breaker.state(), BreakerState::Closed);
+
+        // A cancelled trial lets the next caller try
+        let _ = resilience
+            .call("op", Duration::from_secs(1), || async {
+                Err::<(), _>(TestError::Transient)
+            })
+            .await;
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        let cancelled = tokio::time::timeout(
+            Duration::from_millis(5),
+            resilience.call("op", Duration::from_secs(1), call),
+        )
+        .await;
+        assert!(cancelled.is_err());
+        assert!(
+            resilience
+                .call("op", Duration::from_secs(1), call)
+                .await
+                .is_ok()
+        );
+    }
+}
@@ -0,0 +1,261 @@
+use std::ops::Deref;
+use std::path::Path;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+
+use rusqlite::{Connection, OpenFlags};
+use tracing::info_span;
+
+/// Default number of read-only connections per database.
+pub const DEFAULT_POOL_SIZE: usize = 4;
+
+/// How long a connection waits on a lock held by another connection before failing.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(thiserror::Error, Debug)]
+pub enum SqlitePoolError {
+    #[error("SQLite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+    #[error("SQLite task failed: {0}")]
+    Task(String),
+}
+
+/// A small SQLite connection pool: one writer connection plus `pool_size` read-only
+/// connections, all in WAL mode so readers never block on the writer. Every query runs on
+/// the blocking thread pool via `spawn_blocking`.
+///
+/// In-memory databases cannot be shared between connections, so for `:memory:` reads go
+/// through the writer connection.
+#[derive(Clone)]
+pub struct SqlitePool {
+    inner: Arc<PoolInner>,
+}
+
+struct PoolInner {
+    /// File name without its directory, to tell the databases apart in traces
+    name: String,
+    writer: Mutex<Connection>,
+    readers: Mutex<Vec<Connection>>,
+    reader_available: Condvar,
+    has_readers: bool,
+}
+
+impl SqlitePool {
+    /// Opens the writer connection and `pool_size` reader connections (at least one).
+    pub fn open(filename: &str, pool_size: usize) -> Result<Self, rusqlite::Error> {
+        let writer = Connection::open(filename)?;
+        writer.pragma_update(None, "journal_mode", "WAL")?;
+        writer.busy_timeout(BUSY_TIMEOUT)?;
+
+        let in_memory = filename.is_empty() || filename == ":memory:";
+        let mut readers = Vec::new();
+        if !in_memory {
+            for _ in 0..pool_size.max(1) {
+                let reader = Connection::open_with_flags(
+                    filename,
+                    OpenFlags::SQLITE_OPEN_READ_ONLY
+                        | OpenFlags::SQLITE_OPEN_URI
+                        | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+                )?;
+                reader.busy_timeout(BUSY_TIMEOUT)?;
+                readers.push(reader);
+            }
+        }
+
+        Ok(Self {
+            inner: Arc::new(PoolInner {
+                name: Path::new(filename)
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| filename.to_string()),
+                writer: Mutex::new(writer),
+                has_readers: !readers.is_empty(),
+                readers: Mutex::new(readers),
+                reader_available: Condvar::new(),
+            }),
+        })
+    }
+
+    /// Runs `f` on a read-only connection.
+    pub async fn read<T, F>(&self, f: F) -> Result<T, SqlitePoolError>
+    where
+        F: FnOnce(&Connection) -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let pool = self.clone();
+        let span = info_span!("sqlite.read", db.name = %self.inner.name);
+        tokio::task::spawn_blocking(move || {
+            let _entered = span.enter();
+            if pool.inner.has_readers {
+                let reader = pool.checkout_reader();
+                Ok(f(&reader))
+            } else {
+                Ok(f(&pool.lock_writer()))
+            }
+        })
+        .await
+        .map_err(|e| SqlitePoolError::Task(e.to_string()))?
+    }
+
+    /// Runs `f` on the writer connection. Writes are serialized.
+    pub async fn write<T, F>(&self, f: F) -> Result<T, SqlitePoolError>
+    where
+        F: FnOnce(&mut Connection) -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let pool = self.clone();
+        let span = info_span!("sqlite.write", db.name = %self.inner.name);
+        tokio::task::spawn_blocking(move || {
+            let _entered = span.enter();
+            Ok(f(&mut pool.lock_writer()))
+        })
+        .await
+        .map_err(|e| SqlitePoolError::Task(e.to_string()))?
+    }
+
+    /// Runs `f` on the writer connection on the current thread. Only meant for setup code
+    /// such as migrations, which runs before the pool is shared.
+    pub fn write_blocking<T>(&self, f: impl FnOnce(&mut Connection) -> T) -> T {
+        f(&mut self.lock_writer())
+    }
+
+    fn lock_writer(&self) -> std::sync::MutexGuard<'_, Connection> {
+        // A panic while holding the lock leaves the connection itself usable.
+        self.inner
+            .writer
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn checkout_reader(&self) -> PooledReader<'_> {
+        let mut idle = self
+            .inner
+            .readers
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        loop {
+            if let Some(conn) = idle.pop() {
+                return PooledReader {
+                    pool: &self.inner,
+                    conn: Some(conn),
+                };
+            }
+            idle = self
+                .inner
+                .reader_available
+                .wait(idle)
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+    }
+}
+
+/// A reader connection that is returned to the pool when dropped.
+struct PooledReader<'a> {
+    pool: &'a PoolInner,
+    conn: Option<Connection>,
+}
+
+impl Deref for PooledReader<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.conn.as_ref().expect("connection present until drop")
+    }
+}
+
+impl Drop for PooledReader<'_> {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            self.pool
+                .readers
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .push(conn);
+            self.pool.reader_available.notify_one();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_reads_see_committed_writes() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("pool.db");
+        let pool = SqlitePool::open(db_path.to_str().unwrap(), 2).unwrap();
+
+        pool.write(|conn| {
+            conn.execute_batch("CREATE TABLE t (x INTEGER); INSERT INTO t VALUES (1), (2);")
+        })
+        .await
+        .unwrap()
+        .unwrap();
+
+        let count: i64 = pool
+            .read(|conn| conn.query_row("SELECT COUNT(*) FROM t", [], |row| row.get(0)))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(count, 2);
+    }
+
+    #[tokio::test]
+    async fn test_readers_are_read_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("pool.db");
+        let pool = SqlitePool::open(db_path.to_str().unwrap(), 1).unwrap();
+
+        pool.write_blocking(|conn| conn.execute_batch("CREATE TABLE t (x INTEGER);"))
+            .unwrap();
+
+        let result = pool
+            .read(|conn| conn.execute("INSERT INTO t VALUES (1)", []))
+            .await
+            .unwrap();
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_reads_share_pool() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("pool.db");
+        let pool = SqlitePool::open(db_path.to_str().unwrap(), 2).unwrap();
+
+        pool.write_blocking(|conn| conn.execute_batch("CREATE TABLE t (x INTEGER); INSERT INTO t VALUES (7);"))
+            .unwrap();
+
+        let handles: Vec<_> = (0..16)
+            .map(|_| {
+                let pool = pool.clone();
+                tokio::spawn(async move {
+                    pool.read(|conn| conn.query_row("SELECT x FROM t", [], |row| row.get::<_, i64>(0)))
+                        .await
+                        .unwrap()
+                        .unwrap()
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            assert_eq!(handle.await.unwrap(), 7);
+        }
+        assert_eq!(pool.inner.readers.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_reads_use_writer() {
+        let pool = SqlitePool::open(":memory:", 4).unwrap();
+
+        pool.write_blocking(|conn| conn.execute_batch("CREATE TABLE t (x INTEGER); INSERT INTO t VALUES (3);"))
+            .unwrap();
+
+        let x: i64 = pool
+            .read(|conn| conn.query_row("SELECT x FROM t", [], |row| row.get(0)))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(x, 3);
+    }
+}
@@ -1,9 +1,12 @@
 mod qdrant_client;
+mod resilient_client;
 
 use async_trait::async_trait;
 use data_structures::filter::Filter;
 use data_structures::intermediate::Chunk;
-pub use qdrant_client::{QdrantClient, QdrantConfig};
+use mockall::automock;
+pub use qdrant_client::{QdrantClient, QdrantConfig, QdrantHnswConfig, QdrantQuantization};
+pub use resilient_client::ResilientVectorClient;
 use std::collections::HashMap;
 use uuid::Uuid;
 
@@ -19,13 +22,25 @@ pub enum VectorClientError {
     FieldMissing(String),
     #[error("Invalid vector dimension: {0}")]
     InvalidVectorDimension(String),
+    #[error("Validation failed: {0}")]
+    Validation(String),
+    /// The vector database could not be reached or timed out; retrying later may succeed
+    #[error("Vector database unavailable: {0}")]
+    Unavailable(String),
 }
 
+#[automock]
 #[async_trait]
 pub trait VectorClient {
     async fn store_chunk(&self, chunk: Chunk) -> Result<(), VectorClientError>;
     async fn get_all_chunks(&self) -> Result<Vec<Chunk>, VectorClientError>;
     async fn get_chunk_by_id(&self, id: Uuid) -> Result<Chunk, VectorClientError>;
+    /// Version of the index being served. The IDF stored under the same version must be used
+    /// to build sparse query vectors.
+    fn index_version(&self) -> u32;
+    /// Dense vector size of the collection being served, read from the database. Fails when
+    /// the collection no longer exists.
+    async fn embedding_size(&self) -> Result<u64, VectorClientError>;
     async fn search_chunks(
         &self,
         dense: Option<Vec<f32>>,
@@ -10,17 +10,19 @@ use point_id::PointIdOptions::Uuid as PointUuid;
 use qdrant_client::{
     Qdrant, QdrantError,
     qdrant::{
-        CollectionExistsRequest, Condition, CountPointsBuilder, CreateCollectionBuilder, Distance,
-        Fusion, GetCollectionInfoResponse, GetPointsBuilder, NamedVectors, PointId, PointStruct,
-        PrefetchQueryBuilder, Query, QueryPointsBuilder, RetrievedPoint, ScrollPointsBuilder,
-        SparseVector, SparseVectorConfig, SparseVectorParamsBuilder, UpsertPointsBuilder, Value,
-        Vector, VectorParamsBuilder, VectorParamsMap, Vectors, VectorsConfig, point_id,
-        vector_output, vectors, vectors_config, vectors_output::VectorsOptions,
+        BinaryQuantizationBuilder, Condition, CountPointsBuilder, CreateAliasBuilder,
+        CreateCollectionBuilder, CreateFieldIndexCollectionBuilder, Distance, FieldType, Fusion,
+        GetCollectionInfoResponse, GetPointsBuilder, HnswConfigDiffBuilder, NamedVectors,
+        PayloadSchemaType, PointId, PointStruct, PrefetchQueryBuilder, Query, QueryPointsBuilder,
+        RetrievedPoint, ScalarQuantizationBuilder, ScrollPointsBuilder, SparseVector,
+        SparseVectorConfig, SparseVectorParamsBuilder, UpsertPointsBuilder, Value, Vector,
+        VectorParamsBuilder, VectorParamsMap, Vectors, VectorsConfig, point_id, vector_output,
+        vectors, vectors_config, vectors_output::VectorsOptions,
     },
 };
 use serde::Deserialize;
 use std::collections::HashMap;
-use tracing::{info, instrument};
+use tracing::{info, instrument, warn};
 use uuid::Uuid;
 
 #[derive(thiserror::Error, Debug)]
@@ -33,23 +35,72 @@ pub enum QdrantClientError {
 
 impl From<QdrantError> for VectorClientError {
     fn from(value: QdrantError) -> Self {
-        VectorClientError::Internal(value.to_string())
+        // gRPC status codes DEADLINE_EXCEEDED (4) and UNAVAILABLE (14)
+        let unavailable = match &value {
+            QdrantError::ResponseError { status } => matches!(i32::from(status.code()), 4 | 14),
+            QdrantError::ResourceExhaustedError { .. } | QdrantError::Io(_) => true,
+            _ => false,
+        };
+        if unavailable {
+            VectorClientError::Unavailable(value.to_string())
+        } else {
+            VectorClientError::Internal(value.to_string())
+        }
     }
 }
 
+/// Client for one versioned chunk collection.
+///
+/// Indexing writes into a new `chunk_v{n}` collection, which is validated and then promoted by
+/// atomically repointing the `chunk` alias to it. Old versions are kept for rollback. The alias
+/// is resolved once in [`QdrantClient::new`], so a running server keeps using the collection
+/// that matches the IDF it loaded; it picks up a promoted version on restart.
 pub struct QdrantClient {
     client: Qdrant,
-    embedding_size: u64,
+    config: QdrantConfig,
+    collection: String,
+    version: u32,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+/// Collection tuning (`hnsw`, `on_disk`, `on_disk_payload`, `quantization`) is applied when a
+/// collection version is created. Existing versions keep their settings until the next reindex.
+#[derive(Debug, Default, Deserialize, Clone)]
 pub struct QdrantConfig {
     pub url: String,
     pub embedding_size: u64,
+    /// HNSW parameters for the dense vectors. Unset fields use Qdrant's defaults.
+    pub hnsw: Option<QdrantHnswConfig>,
+    /// Keep the original dense vectors on disk instead of in RAM.
+    pub on_disk: Option<bool>,
+    /// Keep the payload on disk instead of in RAM.
+    pub on_disk_payload: Option<bool>,
+    /// Quantize the dense vectors. Quantized vectors are always kept in RAM.
+    pub quantization: Option<QdrantQuantization>,
+}
+
+#[derive(Debug, Default, Deserialize, Clone)]
+pub struct QdrantHnswConfig {
+    pub m: Option<u64>,
+    pub ef_construct: Option<u64>,
+    pub full_scan_threshold: Option<u64>,
+    pub on_disk: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum QdrantQuantization {
+    /// int8 scalar quantization, 4x smaller
+    Scalar,
+    /// 1-bit binary quantization, 32x smaller. Works best with large embeddings.
+    Binary,
 }
 
 impl QdrantClient {
+    /// Alias pointing at the live versioned collection. Also the name of the legacy,
+    /// unversioned collection.
     const COLLECTION_NAME_CHUNK: &'static str = "chunk";
+    /// Index version of the legacy, unversioned `chunk` collection.
+    pub const LEGACY_VERSION: u32 = 0;
     const EMBEDDING_NAME_DENSE: &'static str = "dense";
     const EMBEDDING_NAME_SPARSE: &'static str = "sparse";
 
@@ -57,6 +108,16 @@ impl QdrantClient {
     const KEY_YEAR: &'static str = "year";
     const KEY_TEAM: &'static str = "team";
     const KEY_PAPER_LYT: &'static str = "paper_lyt";
+    const KEY_CONTENT_TYPE: &'static str = "content_type";
+
+    /// Payload fields used by `search_chunks` filters, with their index type.
+    const PAYLOAD_INDEXES: [(&'static str, FieldType); 5] = [
+        (Self::KEY_LEAGUE, FieldType::Keyword),
+        (Self::KEY_YEAR, FieldType::Integer),
+        (Self::KEY_TEAM, FieldType::Keyword),
+        (Self::KEY_PAPER_LYT, FieldType::Keyword),
+        (Self::KEY_CONTENT_TYPE, FieldType::Keyword),
+    ];
 
     pub async fn new(config: QdrantConfig) -> Result<Self, VectorClientError> {
         info!(
@@ -66,41 +127,42 @@ impl QdrantClient {
 
         let client = Qdrant::from_url(&config.url).build()?;
 
-        let collection_exists = client
-            .collection_exists(CollectionExistsRequest {
-                collection_name: Self::COLLECTION_NAME_CHUNK.to_string(),
-            })
-            .await?;
-
-        if !collection_exists {
-            let dense_vector_config = VectorsConfig {
-                config: Some(vectors_config::Config::ParamsMap(VectorParamsMap {
-                    map: HashMap::from([(
-                        Self::EMBEDDING_NAME_DENSE.to_string(),
-                        VectorParamsBuilder::new(config.embedding_size, Distance::Cosine).into(),
-                    )]),
-                })),
-            };
-
-            let sparse_vector_config = SparseVectorConfig {
-                map: HashMap::from([(
-                    Self::EMBEDDING_NAME_SPARSE.to_string(),
-                    SparseVectorParamsBuilder::default().into(),
-                )]),
-            };
-
-            let builder = CreateCollectionBuilder::new(Self::COLLECTION_NAME_CHUNK)
-                .vectors_config(dense_vector_config)
-                .sparse_vectors_config(sparse_vector_config);
-
-            client.create_collection(builder).await?;
-        }
+        let (collection, version) = match Self::resolve_alias(&client).await? {
+            Some(collection) => {
+                let version = parse_collection_version(&collection).ok_or_else(|| {
+                    VectorClientError::Internal(format!(
+                        "Alias '{}' points at unexpected collection '{collection}'",
+                        Self::COLLECTION_NAME_CHUNK
+                    ))
+                })?;
+                (collection, version)
+            }
+            None if Self::list_collection_names(&client)
+                .await?
+                .iter()
+                .any(|name| name == Self::COLLECTION_NAME_CHUNK) =>
+            {
+                warn!(
+                    "Using legacy unversioned collection '{}'. Run initialize to migrate to versioned collections.",
+                    Self::COLLECTION_NAME_CHUNK
+                );
+                (Self::COLLECTION_NAME_CHUNK.to_string(), Self::LEGACY_VERSION)
+            }
+            None => {
+                let collection = versioned_collection_name(1);
+                Self::create_collection(&client, &collection, &config).await?;
+                client
+                    .create_alias(CreateAliasBuilder::new(&collection, Self::COLLECTION_NAME_CHUNK))
+                    .await?;
+                (collection, 1)
+            }
+        };
 
         // Ensure collection matches given dimensions
-        let info = client.collection_info(Self::COLLECTION_NAME_CHUNK).await?;
-        let size = from_collection_info_get_size(info.clone());
-        let n = from_collection_info_get_n(info);
-        info!("Collection: size={size}, n={n}");
+        let info = client.collection_info(&collection).await?;
+        let size = from_collection_info_get_size(info.clone())?;
+        let n = from_collection_info_get_n(info)?;
+        info!("Collection {collection}: size={size}, n={n}");
 
         if size != config.embedding_size {
             return Err(VectorClientError::InvalidVectorDimension(format!(
@@ -109,12 +171,246 @@ impl QdrantClient {
             )));
         };
 
+        Self::ensure_payload_indexes(&client, &collection).await?;
+
         Ok(Self {
             client,
-            embedding_size: config.embedding_size,
+            config,
+            collection,
+            version,
         })
     }
 
+    /// Index version of the collection this client reads from and writes to.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn collection_name(&self) -> &str {
+        &self.collection
+    }
+
+    /// Version the `chunk` alias currently points at, or `None` if there is no alias yet.
+    pub async fn live_version(&self) -> Result<Option<u32>, VectorClientError> {
+        Ok(Self::resolve_alias(&self.client)
+            .await?
+            .as_deref()
+            .and_then(parse_collection_version))
+    }
+
+    /// All versioned collections, in ascending order.
+    pub async fn list_versions(&self) -> Result<Vec<u32>, VectorClientError> {
+        let mut versions: Vec<u32> = Self::list_collection_names(&self.client)
+            .await?
+            .iter()
+            .filter_map(|name| parse_collection_version(name))
+            .collect();
+        versions.sort_unstable();
+        Ok(versions)
+    }
+
+    /// Creates an empty collection for the next version and returns a client that writes to it.
+    /// The new version is not visible to searches until it is promoted.
+    pub async fn create_next_version(&self) -> Result<QdrantClient, VectorClientError> {
+        let latest = self.list_versions().await?.last().copied().unwrap_or(0);
+        let version = latest.max(self.version) + 1;
+        let collection = versioned_collection_name(version);
+
+        info!("Creating collection {collection}");
+        Self::create_collection(&self.client, &collection, &self.config).await?;
+
+        Ok(self.with_collection(collection, version))
+    }
+
+    /// Returns a client for an existing version, e.g. to promote it again for a rollback.
+    pub async fn open_version(&self, version: u32) -> Result<QdrantClient, VectorClientError> {
+        if !self.list_versions().await?.contains(&version) {
+            return Err(VectorClientError::NotFound(format!(
+                "Collection {} does not exist",
+                versioned_collection_name(version)
+            )));
+        }
+
+        Ok(self.with_collection(versioned_collection_name(version), version))
+    }
+
+    pub async fn count(&self) -> Result<u64, VectorClientError> {
+        let count = self
+            .client
+            .count(CountPointsBuilder::new(&self.collection).exact(true))
+            .await?
+            .result
+            .ok_or_else(|| VectorClientError::Internal("Count result missing".to_string()))?
+            .count;
+
+        Ok(count)
+    }
+
+    /// Checks that the collection has the configured dimensions and is not empty. When
+    /// `expected_points` is given, the point count must match it exactly. Returns the count.
+    pub async fn validate(&self, expected_points: Option<u64>) -> Result<u64, VectorClientError> {
+        let info = self.client.collection_info(&self.collection).await?;
+        let size = from_collection_info_get_size(info)?;
+        if size != self.config.embedding_size {
+            return Err(VectorClientError::Validation(format!(
+                "{} has embedding size {size}, expected {}",
+                self.collection, self.config.embedding_size
+            )));
+        }
+
+        let count = self.count().await?;
+        if count == 0 {
+            return Err(VectorClientError::Validation(format!(
+                "{} is empty",
+                self.collection
+            )));
+        }
+        if let Some(expected) = expected_points
+            && count != expected
+        {
+            return Err(VectorClientError::Validation(format!(
+                "{} has {count} points, expected {expected}",
+                self.collection
+            )));
+        }
+
+        Ok(count)
+    }
+
+    /// Atomically repoints the `chunk` alias to this client's collection.
+    ///
+    /// An alias cannot share its name with a collection, so the legacy unversioned `chunk`
+    /// collection is deleted first. That is the only step that is not reversible.
+    pub async fn promote(&self) -> Result<(), VectorClientError> {
+        if self.version == Self::LEGACY_VERSION {
+            return Err(VectorClientError::Validation(
+                "The legacy collection cannot be promoted".to_string(),
+            ));
+        }
+
+        if Self::list_collection_names(&self.client)
+            .await?
+            .iter()
+            .any(|name| name == Self::COLLECTION_NAME_CHUNK)
+        {
+            warn!(
+                "Deleting legacy collection '{}' to make room for the alias",
+                Self::COLLECTION_NAME_CHUNK
+            );
+            self.client
+                .delete_collection(Self::COLLECTION_NAME_CHUNK)
+                .await?;
+        }
+
+        // Creating an alias that already exists repoints it in a single atomic operation
+        self.client
+            .create_alias(CreateAliasBuilder::new(
+                &self.collection,
+                Self::COLLECTION_NAME_CHUNK,
+            ))
+            .await?;
+        info!(
+            "Alias '{}' now points at {}",
+            Self::COLLECTION_NAME_CHUNK,
+            self.collection
+        );
+
+        Ok(())
+    }
+
+    /// Deletes an old version. The live version cannot be deleted.
+    pub async fn delete_version(&self, version: u32) -> Result<(), VectorClientError> {
+        if self.live_version().await? == Some(version) {
+            return Err(VectorClientError::Validation(format!(
+                "Version {version} is live and cannot be deleted"
+            )));
+        }
+
+        self.client
+            .delete_collection(versioned_collection_name(version))
+            .await?;
+
+        Ok(())
+    }
+
+    fn with_collection(&self, collection: String, version: u32) -> QdrantClient {
+        QdrantClient {
+            client: self.client.clone(),
+            config: self.config.clone(),
+            collection,
+            version,
+        }
+    }
+
+    async fn resolve_alias(client: &Qdrant) -> Result<Option<String>, VectorClientError> {
+        Ok(client
+            .list_aliases()
+            .await?
+            .aliases
+            .into_iter()
+            .find(|alias| alias.alias_name == Self::COLLECTION_NAME_CHUNK)
+            .map(|alias| alias.collection_name))
+    }
+
+    async fn list_collection_names(client: &Qdrant) -> Result<Vec<String>, VectorClientError> {
+        Ok(client
+            .list_collections()
+            .await?
+            .collections
+            .into_iter()
+            .map(|collection| collection.name)
+            .collect())
+    }
+
+    async fn create_collection(
+        client: &Qdrant,
+        collection: &str,
+        config: &QdrantConfig,
+    ) -> Result<(), VectorClientError> {
+        client
+            .create_collection(create_collection_request(collection, config))
+            .await?;
+        Self::ensure_payload_indexes(client, collection).await?;
+
+        Ok(())
+    }
+
+    /// Creates the payload indexes used by search filters if they are missing. Without them
+    /// every filtered search scans the full payload.
+    async fn ensure_payload_indexes(
+        client: &Qdrant,
+        collection: &str,
+    ) -> Result<(), VectorClientError> {
+        let schema = client
+            .collection_info(collection)
+            .await?
+            .result
+            .map(|info| info.payload_schema)
+            .unwrap_or_default();
+
+        for (field, field_type) in Self::PAYLOAD_INDEXES {
+            match schema.get(field) {
+                Some(info) if info.data_type == payload_schema_type(field_type) as i32 => {}
+                Some(info) => warn!(
+                    "Payload index {field} on {collection} has type {:?}, expected {:?}",
+                    PayloadSchemaType::try_from(info.data_type),
+                    field_type
+                ),
+                None => {
+                    info!("Creating {field_type:?} payload index {field} on {collection}");
+                    client
+                        .create_field_index(
+                            CreateFieldIndexCollectionBuilder::new(collection, field, field_type)
+                                .wait(true),
+                        )
+                        .await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     pub async fn analytics(&self) -> Result<(), QdrantClientError> {
         let collections_list = self.client.list_collections().await?;
 
@@ -199,10 +495,10 @@ impl QdrantClient {
     }
 
     fn validate_embedding_size(&self, size: usize) -> Result<(), VectorClientError> {
-        if size != self.embedding_size as usize {
+        if size != self.config.embedding_size as usize {
             return Err(VectorClientError::InvalidVectorDimension(format!(
                 "Expected embedding size {} but got {}",
-                self.embedding_size, size
+                self.config.embedding_size, size
             )));
         }
 
@@ -212,6 +508,7 @@ impl QdrantClient {
 
 #[async_trait]
 impl VectorClient for QdrantClient {
+    #[instrument(name = "vector.store_chunk", skip_all, fields(collection = %self.collection))]
     async fn store_chunk(&self, chunk: Chunk) -> Result<(), VectorClientError> {
         self.validate_embedding_size(chunk.dense_embedding.len())?;
 
@@ -246,7 +543,7 @@ impl VectorClient for QdrantClient {
             (chunk.chunk_seq as i64).into(),
         );
         payload.insert(
-            "content_type".to_string(),
+            Self::KEY_CONTENT_TYPE.to_string(),
             chunk.content_type.as_str().into(),
         );
         payload.insert("title".to_string(), chunk.title.into());
@@ -282,7 +579,7 @@ impl VectorClient for QdrantClient {
 
         self.client
             .upsert_points(
-                UpsertPointsBuilder::new(Self::COLLECTION_NAME_CHUNK, vec![point]).wait(true),
+                UpsertPointsBuilder::new(&self.collection, vec![point]).wait(true),
             )
             .await?;
 
@@ -294,7 +591,7 @@ impl VectorClient for QdrantClient {
         info!("Retrieving all chunks from Qdrant");
         let count_response = self
             .client
-            .count(CountPointsBuilder::new(Self::COLLECTION_NAME_CHUNK).exact(true))
+            .count(CountPointsBuilder::new(&self.collection).exact(true))
             .await?;
 
         let total_count = count_response
@@ -310,7 +607,7 @@ impl VectorClient for QdrantClient {
         let scroll_response = self
             .client
             .scroll(
-                ScrollPointsBuilder::new(Self::COLLECTION_NAME_CHUNK)
+                ScrollPointsBuilder::new(&self.collection)
                     .with_payload(true)
                     .with_vectors(true)
                     .limit(total_count as u32),
@@ -325,6 +622,7 @@ impl VectorClient for QdrantClient {
         Ok(chunks)
     }
 
+    #[instrument(name = "vector.get_chunk", skip(self), fields(collection = %self.collection))]
     async fn get_chunk_by_id(&self, id: Uuid) -> Result<Chunk, VectorClientError> {
         let point_id = PointId {
             point_id_options: Some(PointUuid(id.to_string())),
@@ -333,7 +631,7 @@ impl VectorClient for QdrantClient {
         let retrieved_points = self
             .client
             .get_points(
-                GetPointsBuilder::new(Self::COLLECTION_NAME_CHUNK, vec![point_id])
+                GetPointsBuilder::new(&self.collection, vec![point_id])
                     .with_vectors(true)
                     .with_payload(true),
             )
@@ -350,6 +648,26 @@ impl VectorClient for QdrantClient {
         Ok(point.clone().into_chunk()?)
     }
 
+    fn index_version(&self) -> u32 {
+        self.version
+    }
+
+    #[instrument(name = "vector.embedding_size", skip_all, fields(collection = %self.collection))]
+    async fn embedding_size(&self) -> Result<u64, VectorClientError> {
+        let info = self.client.collection_info(&self.collection).await?;
+        from_collection_info_get_size(info)
+    }
+
+    #[instrument(
+        name = "vector.search_chunks",
+        skip_all,
+        fields(
+            collection = %self.collection,
+            dense = dense.is_some(),
+            sparse = sparse.is_some(),
+            limit = limit
+        )
+    )]
     async fn search_chunks(
         &self,
         dense: Option<Vec<f32>>,
@@ -361,7 +679,7 @@ impl VectorClient for QdrantClient {
             self.validate_embedding_size(d.len())?;
         }
 
-        let mut query_builder = QueryPointsBuilder::new(Self::COLLECTION_NAME_CHUNK)
+        let mut query_builder = QueryPointsBuilder::new(&self.collection)
             .limit(limit)
             .with_payload(true);
 
@@ -412,7 +730,7 @@ impl VectorClient for QdrantClient {
                 if !content_types.is_empty() {
                     info!("Adding content_type filter {:?}", content_types);
                     conditions.push(Condition::matches(
-                        "content_type",
+                        Self::KEY_CONTENT_TYPE,
                         content_types.into_iter().collect::<Vec<String>>(),
                     ));
                 }
@@ -520,7 +838,7 @@ impl IntoChunk for HashMap<String, Value> {
             .map(|i| i as u32)
             .ok_or_else(|| VectorClientError::FieldMissing("chunk_seq".to_string()))?;
 
-        let content_type = from_payload_get_string(&self, "content_type")
+        let content_type = from_payload_get_string(&self, QdrantClient::KEY_CONTENT_TYPE)
             .and_then(|s| ContentType::try_from(s.as_str()).ok())
             .unwrap_or_default();
 
@@ -590,26 +908,125 @@ fn from_point_get_sparse_vector(p: &RetrievedPoint) -> Option<HashMap<u32, f32>>
     }
 }
 
-fn from_collection_info_get_size(info: GetCollectionInfoResponse) -> u64 {
-    let params = info.result.unwrap().config.unwrap().params.unwrap();
-    let config = params.vectors_config.unwrap().config.unwrap();
-    let dimension = match config {
-        vectors_config::Config::Params(_vector_params) => {
-            todo!()
+fn create_collection_request(collection: &str, config: &QdrantConfig) -> CreateCollectionBuilder {
+    let mut dense_params = VectorParamsBuilder::new(config.embedding_size, Distance::Cosine);
+    if let Some(on_disk) = config.on_disk {
+        dense_params = dense_params.on_disk(on_disk);
+    }
+
+    let dense_vector_config = VectorsConfig {
+        config: Some(vectors_config::Config::ParamsMap(VectorParamsMap {
+            map: HashMap::from([(
+                QdrantClient::EMBEDDING_NAME_DENSE.to_string(),
+                dense_params.into(),
+            )]),
+        })),
+    };
+
+    let sparse_vector_config = SparseVectorConfig {
+        map: HashMap::from([(
+            QdrantClient::EMBEDDING_NAME_SPARSE.to_string(),
+            SparseVectorParamsBuilder::default().into(),
+        )]),
+    };
+
+    let mut builder = CreateCollectionBuilder::new(collection)
+        .vectors_config(dense_vector_config)
+        .sparse_vectors_config(sparse_vector_config);
+
+    if let Some(hnsw) = &config.hnsw {
+        let mut hnsw_config = HnswConfigDiffBuilder::default();
+        if let Some(m) = hnsw.m {
+            hnsw_config = hnsw_config.m(m);
         }
-        vectors_config::Config::ParamsMap(vector_params_map) => {
-            let params = vector_params_map
-                .map
-                .get(QdrantClient::EMBEDDING_NAME_DENSE)
-                .unwrap();
-            params.size
+        if let Some(ef_construct) = hnsw.ef_construct {
+            hnsw_config = hnsw_config.ef_construct(ef_construct);
         }
-    };
-    dimension
+        if let Some(full_scan_threshold) = hnsw.full_scan_threshold {
+            hnsw_config = hnsw_config.full_scan_threshold(full_scan_threshold);
+        }
+        if let Some(on_disk) = hnsw.on_disk {
+            hnsw_config = hnsw_config.on_disk(on_disk);
+        }
+        builder = builder.hnsw_config(hnsw_config);
+    }
+
+    if let Some(on_disk_payload) = config.on_disk_payload {
+        builder = builder.on_disk_payload(on_disk_payload);
+    }
+
+    match config.quantization {
+        Some(QdrantQuantization::Scalar) => {
+            builder = builder
+                .quantization_config(ScalarQuantizationBuilder::default().always_ram(true));
+        }
+        Some(QdrantQuantization::Binary) => {
+            builder = builder.quantization_config(BinaryQuantizationBuilder::new(true));
+        }
+        None => {}
+    }
+
+    builder
 }
 
-fn from_collection_info_get_n(info: GetCollectionInfoResponse) -> u64 {
-    info.result.unwrap().points_count.unwrap()
+fn payload_schema_type(field_type: FieldType) -> PayloadSchemaType {
+    match field_type {
+        FieldType::Keyword => PayloadSchemaType::Keyword,
+        FieldType::Integer => PayloadSchemaType::Integer,
+        FieldType::Float => PayloadSchemaType::Float,
+        FieldType::Geo => PayloadSchemaType::Geo,
+        FieldType::Text => PayloadSchemaType::Text,
+        FieldType::Bool => PayloadSchemaType::Bool,
+        FieldType::Datetime => PayloadSchemaType::Datetime,
+        FieldType::Uuid => PayloadSchemaType::Uuid,
+    }
+}
+
+fn versioned_collection_name(version: u32) -> String {
+    format!("{}_v{version}", QdrantClient::COLLECTION_NAME_CHUNK)
+}
+
+fn parse_collection_version(name: &str) -> Option<u32> {
+    name.strip_prefix(QdrantClient::COLLECTION_NAME_CHUNK)?
+        .strip_prefix("_v")?
+        .parse()
+        .ok()
+}
+
+/// Size of the dense vector, or an error when the collection lacks it, e.g. because it was
+/// created by something else
+fn from_collection_info_get_size(
+    info: GetCollectionInfoResponse,
+) -> Result<u64, VectorClientError> {
+    let missing = |field: &str| VectorClientError::FieldMissing(format!("collection info {field}"));
+    let config = info
+        .result
+        .ok_or_else(|| missing("result"))?
+        .config
+        .ok_or_else(|| missing("config"))?
+        .params
+        .ok_or_else(|| missing("params"))?
+        .vectors_config
+        .ok_or_else(|| missing("vectors_config"))?
+        .config
+        .ok_or_else(|| missing("vectors_config.config"))?;
+    match config {
+        vectors_config::Config::Params(_) => Err(VectorClientError::Validation(format!(
+            "collection has a single unnamed vector, expected a named '{}' vector",
+            QdrantClient::EMBEDDING_NAME_DENSE
+        ))),
+        vectors_config::Config::ParamsMap(vector_params_map) => vector_params_map
+            .map
+            .get(QdrantClient::EMBEDDING_NAME_DENSE)
+            .map(|params| params.size)
+            .ok_or_else(|| missing(QdrantClient::EMBEDDING_NAME_DENSE)),
+    }
+}
+
+fn from_collection_info_get_n(info: GetCollectionInfoResponse) -> Result<u64, VectorClientError> {
+    info.result
+        .and_then(|result| result.points_count)
+        .ok_or_else(|| VectorClientError::FieldMissing("collection info points_count".to_string()))
 }
 
 #[cfg(test)]
@@ -618,7 +1035,14 @@ mod tests {
     use std::collections::HashMap;
     use std::time::Duration;
 
-    use crate::vector::{QdrantClient, QdrantConfig, VectorClient};
+    use super::{
+        create_collection_request, from_collection_info_get_size, parse_collection_version,
+        versioned_collection_name,
+    };
+    use crate::vector::{
+        QdrantClient, QdrantConfig, QdrantHnswConfig, QdrantQuantization, VectorClient,
+    };
+    use qdrant_client::qdrant::quantization_config::Quantization;
     use data_structures::content::ContentType;
     use data_structures::file::{League, TeamName};
     use data_structures::filter::Filter;
@@ -634,12 +1058,97 @@ mod tests {
         vec.into_iter().map(|f| f / len).collect()
     }
 
+    #[test]
+    fn test_collection_versions() {
+        assert_eq!(versioned_collection_name(3), "chunk_v3");
+        assert_eq!(parse_collection_version("chunk_v3"), Some(3));
+        assert_eq!(parse_collection_version("chunk_v12"), Some(12));
+        assert_eq!(parse_collection_version("chunk"), None);
+        assert_eq!(parse_collection_version("chunk_vx"), None);
+        assert_eq!(parse_collection_version("other_v1"), None);
+    }
+
+    #[test]
+    fn test_collection_size_errors() {
+        use crate::vector::VectorClientError;
+        use qdrant_client::qdrant::{
+            CollectionConfig, CollectionInfo, CollectionParams, GetCollectionInfoResponse,
+            VectorParams, VectorsConfig, vectors_config,
+        };
+
+        let empty = GetCollectionInfoResponse::default();
+        assert!(matches!(
+            from_collection_info_get_size(empty),
+            Err(VectorClientError::FieldMissing(_))
+        ));
+
+        let single_vector = GetCollectionInfoResponse {
+            result: Some(CollectionInfo {
+                config: Some(CollectionConfig {
+                    params: Some(CollectionParams {
+                        vectors_config: Some(VectorsConfig {
+                            config: Some(vectors_config::Config::Params(VectorParams::default())),
+                        }),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert!(matches!(
+            from_collection_info_get_size(single_vector),
+            Err(VectorClientError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn test_create_collection_request_tuning() {
+        let request = create_collection_request(
+            "chunk_v1",
+            &QdrantConfig {
+                embedding_size: 3,
+                ..Default::default()
+            },
+        )
+        .build();
+        assert!(request.hnsw_config.is_none());
+        assert!(request.quantization_config.is_none());
+        assert!(request.on_disk_payload.is_none());
+
+        let request = create_collection_request(
+            "chunk_v1",
+            &QdrantConfig {
+                embedding_size: 3,
+                hnsw: Some(QdrantHnswConfig {
+                    m: Some(32),
+                    ef_construct: Some(200),
+                    ..Default::default()
+                }),
+                on_disk: Some(true),
+                on_disk_payload: Some(true),
+                quantization: Some(QdrantQuantization::Scalar),
+                ..Default::default()
+            },
+        )
+        .build();
+
+        let hnsw = request.hnsw_config.unwrap();
+        assert_eq!((hnsw.m, hnsw.ef_construct, hnsw.on_disk), (Some(32), Some(200), None));
+        assert_eq!(request.on_disk_payload, Some(true));
+        assert!(matches!(
+            request.quantization_config.unwrap().quantization,
+            Some(Quantization::Scalar(_))
+        ));
+    }
+
     #[tokio::test]
     async fn test_create_client() -> Result<(), Box<dyn std::error::Error>> {
         let client = QdrantClient::new(QdrantConfig {
             url: "http://localhost:6334".to_string(),
             embedding_size: 1536,
-
+            ..Default::default()
         })
         .await;
 
@@ -653,7 +1162,7 @@ mod tests {
         let client = QdrantClient::new(QdrantConfig {
             url: "http://localhost:6334".to_string(),
             embedding_size: 1536,
-
+            ..Default::default()
         })
         .await;
 
@@ -682,7 +1191,7 @@ mod tests {
         let client = QdrantClient::new(QdrantConfig {
             url: "http://localhost:7334".to_string(),
             embedding_size: 3,
-
+            ..Default::default()
         })
         .await;
 
@@ -743,7 +1252,7 @@ mod tests {
         let client = QdrantClient::new(QdrantConfig {
             url: "http://localhost:7334".to_string(),
             embedding_size: 3,
-
+            ..Default::default()
         })
         .await;
 
@@ -868,4 +1377,80 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_versioned_collections() -> Result<(), anyhow::Error> {
+        let _image = GenericImage::new("qdrant/qdrant", "v1.16")
+            .with_exposed_port(6333.tcp())
+            .with_exposed_port(6334.tcp())
+            .with_mapped_port(7333, 6333.tcp())
+            .with_mapped_port(7334, 6334.tcp())
+            .start()
+            .await
+            .expect("Failed to start Qdrant");
+
+        sleep(Duration::from_secs(2)).await;
+
+        let config = QdrantConfig {
+            url: "http://localhost:7334".to_string(),
+            embedding_size: 3,
+            ..Default::default()
+        };
+
+        // A fresh instance starts with an empty, live version 1
+        let client = QdrantClient::new(config.clone()).await?;
+        assert_eq!(client.version(), 1);
+        assert_eq!(client.live_version().await?, Some(1));
+
+        let schema = client
+            .client
+            .collection_info(client.collection_name())
+            .await?
+            .result
+            .unwrap()
+            .payload_schema;
+        for (field, _) in QdrantClient::PAYLOAD_INDEXES {
+            assert!(schema.contains_key(field), "missing payload index {field}");
+        }
+
+        let chunk = Chunk {
+            dense_embedding: normalize(vec![1.0, 3.0, 2.0]),
+            sparse_embedding: HashMap::from([(1, 1.0)]),
+            paper_lyt: "soccer_smallsize__1998__test_team".to_string(),
+            league: League::SoccerSmallSize,
+            year: 1998,
+            team: TeamName::new("test_team"),
+            content_seq: 0,
+            chunk_seq: 0,
+            content_type: ContentType::default(),
+            title: String::new(),
+            image_path: None,
+            text: "test_text".to_string(),
+        };
+        client.store_chunk(chunk.clone()).await?;
+
+        // Writing to a new version does not affect the live one
+        let next = client.create_next_version().await?;
+        assert_eq!(next.version(), 2);
+        assert!(next.validate(None).await.is_err(), "empty version must not validate");
+
+        next.store_chunk(chunk.clone()).await?;
+        assert!(next.validate(Some(2)).await.is_err());
+        assert_eq!(next.validate(Some(1)).await?, 1);
+        assert_eq!(client.live_version().await?, Some(1));
+
+        next.promote().await?;
+        assert_eq!(client.live_version().await?, Some(2));
+        assert_eq!(QdrantClient::new(config.clone()).await?.version(), 2);
+        assert_eq!(client.list_versions().await?, vec![1, 2]);
+
+        // Roll back, then remove the old version
+        client.open_version(1).await?.promote().await?;
+        assert_eq!(client.live_version().await?, Some(1));
+        assert!(client.delete_version(1).await.is_err(), "live version must not be deleted");
+        client.delete_version(2).await?;
+        assert_eq!(client.list_versions().await?, vec![1]);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use data_structures::filter::Filter;
+use data_structures::intermediate::Chunk;
+use uuid::Uuid;
+
+use super::{VectorClient, VectorClientError};
+use crate::resilience::{CircuitBreaker, DependencyError, Resilience, ResiliencePolicy};
+
+const SEARCH_TIMEOUT: Duration = Duration::from_secs(5);
+const GET_CHUNK_TIMEOUT: Duration = Duration::from_secs(5);
+const STORE_CHUNK_TIMEOUT: Duration = Duration::from_secs(30);
+/// Scrolls through the whole collection
+const GET_ALL_CHUNKS_TIMEOUT: Duration = Duration::from_secs(300);
+
+impl DependencyError for VectorClientError {
+    fn is_transient(&self) -> bool {
+        matches!(self, VectorClientError::Unavailable(_))
+    }
+
+    fn unavailable(message: String) -> Self {
+        VectorClientError::Unavailable(message)
+    }
+}
+
+/// Wraps a [`VectorClient`] with the timeouts, retries and circuit breaker of its policy.
+/// Operations are named after the trait methods in `timeouts_ms`. Storing is retried too: chunks
+/// are upserted under an ID derived from their content, so a repeated store is harmless.
+pub struct ResilientVectorClient {
+    inner: Arc<dyn VectorClient + Send + Sync>,
+    resilience: Resilience,
+}
+
+impl ResilientVectorClient {
+    pub fn new(
+        dependency: &str,
+        inner: Arc<dyn VectorClient + Send + Sync>,
+        policy: ResiliencePolicy,
+    ) -> Self {
+        Self {
+            inner,
+            resilience: Resilience::new(dependency, policy),
+        }
+    }
+
+    pub fn breaker(&self) -> Arc<CircuitBreaker> {
+        self.resilience.breaker()
+    }
+}
+
+#[async_trait]
+impl VectorClient for ResilientVectorClient {
+    async fn store_chunk(&self, chunk: Chunk) -> Result<(), VectorClientError> {
+        self.resilience
+            .call("store_chunk", STORE_CHUNK_TIMEOUT, || {
+                self.inner.store_chunk(chunk.clone())
+            })
+            .await
+    }
+
+    async fn get_all_chunks(&self) -> Result<Vec<Chunk>, VectorClientError> {
+        self.resilience
+            .call("get_all_chunks", GET_ALL_CHUNKS_TIMEOUT, || {
+                self.inner.get_all_chunks()
+            })
+            .await
+    }
+
+    async fn get_chunk_by_id(&self, id: Uuid) -> Result<Chunk, VectorClientError> {
+        self.resilience
+            .call("get_chunk_by_id", GET_CHUNK_TIMEOUT, || {
+                self.inner.get_chunk_by_id(id)
+            })
+            .await
+    }
+
+    fn index_version(&self) -> u32 {
+        self.inner.index_version()
+    }
+
+    async fn embedding_size(&self) -> Result<u64, VectorClientError> {
+        self.resilience
+            .call("embedding_size", GET_CHUNK_TIMEOUT, || {
+                self.inner.embedding_size()
+            })
+            .await
+    }
+
+    async fn search_chunks(
+        &self,
+        dense: Option<Vec<f32>>,
+        sparse: Option<HashMap<u32, f32>>,
+        limit: u64,
+        filter: Option<Filter>,
+    ) -> Result<Vec<(Chunk, f32)>, VectorClientError> {
+        self.resilience
+            .call("search_chunks", SEARCH_TIMEOUT, || {
+                self.inner
+                    .search_chunks(dense.clone(), sparse.clone(), limit, filter.clone())
+            })
+            .await
+    }
+}
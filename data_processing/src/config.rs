@@ -1,16 +1,26 @@
+use std::time::Duration;
+
 use serde::Deserialize;
 
 pub const DEFAULT_HIGHLIGHT_IDF_THRESHOLD: f32 = 1.5;
+pub const DEFAULT_EMBED_TIMEOUT_MS: u64 = 5000;
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct DataProcessingConfig {
     pub tdps_markdown_root: String,
     pub tdps_pdf_root: String,
     pub highlight_idf_threshold: Option<f32>,
+    /// How long a search waits for the query's dense embedding before falling back to sparse
+    /// search. Defaults to 5000.
+    pub embed_timeout_ms: Option<u64>,
 }
 
 impl DataProcessingConfig {
     pub fn highlight_idf_threshold(&self) -> f32 {
         self.highlight_idf_threshold.unwrap_or(DEFAULT_HIGHLIGHT_IDF_THRESHOLD)
     }
+
+    pub fn embed_timeout(&self) -> Duration {
+        Duration::from_millis(self.embed_timeout_ms.unwrap_or(DEFAULT_EMBED_TIMEOUT_MS))
+    }
 }
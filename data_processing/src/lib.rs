@@ -4,3 +4,4 @@ pub mod embed;
 pub mod markdown_parser;
 pub mod search;
 pub mod text;
+pub mod thumbnail;
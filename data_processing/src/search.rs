@@ -1,5 +1,7 @@
 use std::collections::HashMap;
+use std::future::Future;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use data_access::embed::{EmbedClient, extract_highlight_terms};
 use data_access::metadata::MetadataClient;
@@ -11,8 +13,10 @@ use data_structures::{
     filter::Filter,
     intermediate::{BreadcrumbEntry, SearchResult, SearchResultChunk, SearchSuggestions},
 };
-use tracing::{info, warn};
+use tracing::{Instrument, info, info_span, instrument, warn};
+use uuid::Uuid;
 
+use crate::config::DEFAULT_EMBED_TIMEOUT_MS;
 use crate::text::match_terms;
 
 /// Compute the breadcrumb path for a given content_seq within a table of contents.
@@ -42,6 +46,18 @@ fn compute_breadcrumbs(toc: &[TocEntry], content_seq: u32) -> Vec<BreadcrumbEntr
     crumbs
 }
 
+/// Run one stage of a search in its own span and record its duration under
+/// `tdp_search_stage_duration_seconds{stage}`
+async fn stage<T>(stage: &'static str, future: impl Future<Output = T>) -> T {
+    let start = Instant::now();
+    let output = future
+        .instrument(info_span!("search_stage", otel.name = %format!("search.{}", stage), stage))
+        .await;
+    metrics::histogram!("tdp_search_stage_duration_seconds", "stage" => stage)
+        .record(start.elapsed().as_secs_f64());
+    output
+}
+
 pub struct Searcher {
     pub embed_client: Arc<dyn EmbedClient + Send + Sync>,
     pub vector_client: Arc<dyn VectorClient + Send + Sync>,
@@ -50,6 +66,8 @@ pub struct Searcher {
     pub teams: Vec<String>,
     pub leagues: Vec<String>,
     pub highlight_idf_threshold: f32,
+    /// Dense embeddings taking longer than this are given up on in favour of sparse search
+    pub embed_timeout: Duration,
 }
 
 impl Searcher {
@@ -70,9 +88,38 @@ impl Searcher {
             teams,
             leagues,
             highlight_idf_threshold,
+            embed_timeout: Duration::from_millis(DEFAULT_EMBED_TIMEOUT_MS),
         }
     }
 
+    pub fn with_embed_timeout(mut self, embed_timeout: Duration) -> Self {
+        self.embed_timeout = embed_timeout;
+        self
+    }
+
+    /// Dense embedding of the query, or `None` when the provider failed or timed out. Searches
+    /// then carry on with sparse vectors only, so the site keeps working during outages.
+    async fn embed_dense(&self, query: &str) -> Option<Vec<f32>> {
+        let embed = self.embed_client.embed_string(query);
+        let reason = match tokio::time::timeout(self.embed_timeout, embed).await {
+            Ok(Ok(dense)) => return Some(dense),
+            Ok(Err(e)) => {
+                warn!("Dense embedding failed, falling back to sparse search: {e}");
+                "error"
+            }
+            Err(_) => {
+                warn!(
+                    "Dense embedding timed out after {:?}, falling back to sparse search",
+                    self.embed_timeout
+                );
+                "timeout"
+            }
+        };
+        metrics::counter!("tdp_search_degraded_total", "reason" => reason).increment(1);
+        None
+    }
+
+    #[instrument(skip(self, query, filter))]
     pub async fn search(
         &self,
         query: String,
@@ -87,30 +134,41 @@ impl Searcher {
         let query_trim = query.trim();
         if query_trim.is_empty() {
             return Ok(SearchResult {
+                result_set_id: Uuid::new_v4().to_string(),
                 query,
                 filter,
                 chunks: Vec::new(),
                 suggestions: SearchSuggestions::default(),
                 highlight_terms: Vec::new(),
+                degraded: false,
             });
         }
 
-        let dense = if matches!(search_type, EmbedType::DENSE | EmbedType::HYBRID) {
-            Some(self.embed_client.embed_string(query_trim).await?)
-        } else {
-            None
-        };
+        let (dense, sparse, degraded) = stage("embed", async {
+            let wants_dense = matches!(search_type, EmbedType::DENSE | EmbedType::HYBRID);
+            let dense = if wants_dense {
+                self.embed_dense(query_trim).await
+            } else {
+                None
+            };
+            let degraded = wants_dense && dense.is_none();
 
-        let sparse = if matches!(search_type, EmbedType::SPARSE | EmbedType::HYBRID) {
-            Some(self.embed_client.embed_sparse(query_trim, &self.idf_map))
-        } else {
-            None
-        };
+            let sparse = if matches!(search_type, EmbedType::SPARSE | EmbedType::HYBRID) || degraded
+            {
+                Some(self.embed_client.embed_sparse(query_trim, &self.idf_map))
+            } else {
+                None
+            };
+            (dense, sparse, degraded)
+        })
+        .await;
 
-        let results = self
-            .vector_client
-            .search_chunks(dense, sparse, limit, filter.clone())
-            .await?;
+        let results = stage(
+            "vector_query",
+            self.vector_client
+                .search_chunks(dense, sparse, limit, filter.clone()),
+        )
+        .await?;
 
         // Collect unique paper_lyts to batch-load ToCs
         let unique_paper_lyts: Vec<String> = {
@@ -128,21 +186,29 @@ impl Searcher {
         };
 
         // Load ToCs for breadcrumb computation
-        let mut toc_cache: HashMap<String, Vec<TocEntry>> = HashMap::new();
-        for paper_lyt in unique_paper_lyts {
-            match self.metadata_client.load_toc(paper_lyt.clone()).await {
-                Ok(toc) => {
-                    toc_cache.insert(paper_lyt, toc);
-                }
-                Err(e) => {
-                    warn!("Failed to load ToC for {}: {}", paper_lyt, e);
+        let toc_cache = stage("toc_load", async {
+            let mut toc_cache: HashMap<String, Vec<TocEntry>> = HashMap::new();
+            for paper_lyt in unique_paper_lyts {
+                match self.metadata_client.load_toc(paper_lyt.clone()).await {
+                    Ok(toc) => {
+                        toc_cache.insert(paper_lyt, toc);
+                    }
+                    Err(e) => {
+                        warn!("Failed to load ToC for {}: {}", paper_lyt, e);
+                    }
                 }
             }
-        }
+            toc_cache
+        })
+        .await;
 
-        let team_suggestions = match_terms(self.teams.clone(), query_trim.to_string(), Some(0.8));
-        let league_suggestions =
-            match_terms(self.leagues.clone(), query_trim.to_string(), Some(0.8));
+        let (team_suggestions, league_suggestions) = stage("suggestions", async {
+            (
+                match_terms(self.teams.clone(), query_trim.to_string(), Some(0.8)),
+                match_terms(self.leagues.clone(), query_trim.to_string(), Some(0.8)),
+            )
+        })
+        .await;
 
         let chunks = results
             .into_iter()
@@ -161,6 +227,7 @@ impl Searcher {
         let highlight_terms = extract_highlight_terms(query_trim, &self.idf_map, self.highlight_idf_threshold);
 
         Ok(SearchResult {
+            result_set_id: Uuid::new_v4().to_string(),
             query,
             filter,
             chunks,
@@ -169,6 +236,84 @@ impl Searcher {
                 leagues: league_suggestions,
             },
             highlight_terms,
+            degraded,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use data_access::embed::{EmbedClientError, MockEmbedClient};
+    use data_access::metadata::MockMetadataClient;
+    use data_access::vector::MockVectorClient;
+
+    /// Embeds the query after `delay`, or fails when `fail` is set
+    fn embed_client(fail: bool, delay: Duration) -> MockEmbedClient {
+        let mut embed_client = MockEmbedClient::new();
+        embed_client.expect_embed_string().returning(move |_| {
+            Box::pin(async move {
+                tokio::time::sleep(delay).await;
+                if fail {
+                    Err(EmbedClientError::Internal("quota exceeded".to_string()))
+                } else {
+                    Ok(vec![1.0, 0.0])
+                }
+            })
+        });
+        embed_client
+    }
+
+    /// Expects a single query, with a dense vector only when `dense` is set
+    fn vector_client(dense: bool) -> MockVectorClient {
+        let mut vector_client = MockVectorClient::new();
+        vector_client.expect_index_version().return_const(1u32);
+        vector_client
+            .expect_search_chunks()
+            .withf(move |d, sparse, _, _| d.is_some() == dense && sparse.is_some())
+            .times(1)
+            .returning(|_, _, _, _| Ok(Vec::new()));
+        vector_client
+    }
+
+    fn searcher(embed_client: MockEmbedClient, vector_client: MockVectorClient) -> Searcher {
+        Searcher::new(
+            Arc::new(embed_client),
+            Arc::new(vector_client),
+            Arc::new(MockMetadataClient::new()),
+            Arc::new(IDF::new()),
+            Vec::new(),
+            Vec::new(),
+            1.5,
+        )
+        .with_embed_timeout(Duration::from_millis(50))
+    }
+
+    #[tokio::test]
+    async fn test_search_falls_back_to_sparse() {
+        let healthy = searcher(embed_client(false, Duration::ZERO), vector_client(true));
+        let result = healthy
+            .search("ball".to_string(), None, None, EmbedType::HYBRID)
+            .await
+            .unwrap();
+        assert!(!result.degraded);
+
+        let failing = searcher(embed_client(true, Duration::ZERO), vector_client(false));
+        let result = failing
+            .search("ball".to_string(), None, None, EmbedType::DENSE)
+            .await
+            .unwrap();
+        assert!(result.degraded);
+
+        let slow = searcher(
+            embed_client(false, Duration::from_secs(5)),
+            vector_client(false),
+        );
+        let result = slow
+            .search("ball".to_string(), None, None, EmbedType::HYBRID)
+            .await
+            .unwrap();
+        assert!(result.degraded);
+    }
+}
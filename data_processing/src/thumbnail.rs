@@ -0,0 +1,184 @@
+//! Resized, re-encoded copies of paper figures. Copies are generated on first request and
+//! cached on disk next to each other as `{cache_dir}/{path}.{width}.{ext}`.
+
+use std::fs;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageReader};
+
+const JPEG_QUALITY: u8 = 80;
+
+/// Requested widths are rounded up to a multiple of this, so arbitrary `?w=` values cannot fill
+/// the cache with near-identical copies
+const WIDTH_STEP: u32 = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThumbnailFormat {
+    Webp,
+    Jpeg,
+    Png,
+}
+
+impl ThumbnailFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ThumbnailFormat::Webp => "webp",
+            ThumbnailFormat::Jpeg => "jpg",
+            ThumbnailFormat::Png => "png",
+        }
+    }
+}
+
+impl TryFrom<&str> for ThumbnailFormat {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value.to_lowercase().as_str() {
+            "webp" => Ok(ThumbnailFormat::Webp),
+            "jpeg" | "jpg" => Ok(ThumbnailFormat::Jpeg),
+            "png" => Ok(ThumbnailFormat::Png),
+            other => Err(format!("Unknown image format: '{}'", other)),
+        }
+    }
+}
+
+/// Whether thumbnails can be made of `path`. Vector and unknown formats cannot.
+pub fn is_supported(path: &Path) -> bool {
+    image::ImageFormat::from_path(path).is_ok_and(|format| format.reading_enabled())
+}
+
+/// Round `requested` up to the next width step, within `[WIDTH_STEP, max_width]`
+pub fn snap_width(requested: u32, max_width: u32) -> u32 {
+    requested
+        .div_ceil(WIDTH_STEP)
+        .max(1)
+        .saturating_mul(WIDTH_STEP)
+        .min(max_width.max(WIDTH_STEP))
+}
+
+/// Where the copy of `relative_path` (a figure path relative to the TDP root) is cached
+pub fn cache_path(
+    cache_dir: &Path,
+    relative_path: &Path,
+    width: u32,
+    format: ThumbnailFormat,
+) -> PathBuf {
+    let mut name = relative_path.as_os_str().to_os_string();
+    name.push(format!(".{}.{}", width, format.extension()));
+    cache_dir.join(name)
+}
+
+/// Make sure `target` holds `source` scaled down to at most `width` pixels wide, regenerating
+/// it when missing or older than the source. Images narrower than `width` are not enlarged.
+pub fn ensure_thumbnail(
+    source: &Path,
+    target: &Path,
+    width: u32,
+    format: ThumbnailFormat,
+) -> anyhow::Result<()> {
+    let source_modified = fs::metadata(source)?.modified()?;
+    if let Ok(cached) = fs::metadata(target)
+        && cached.modified()? >= source_modified
+    {
+        return Ok(());
+    }
+
+    let image = ImageReader::open(source)?
+        .with_guessed_format()?
+        .decode()
+        .with_context(|| format!("Failed to decode {}", source.display()))?;
+    let image = if image.width() > width {
+        image.resize(width, u32::MAX, FilterType::CatmullRom)
+    } else {
+        image
+    };
+
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // Concurrent requests for the same copy each write their own file; the last rename wins
+    let mut temp_name = target.as_os_str().to_os_string();
+    temp_name.push(format!(".{}.tmp", uuid::Uuid::new_v4()));
+    let temp = PathBuf::from(temp_name);
+
+    let written = write_image(&image, &temp, format);
+    if written.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    written?;
+    fs::rename(&temp, target)?;
+    Ok(())
+}
+
+fn write_image(image: &DynamicImage, path: &Path, format: ThumbnailFormat) -> anyhow::Result<()> {
+    let mut writer = BufWriter::new(fs::File::create(path)?);
+    match format {
+        // JPEG has no alpha channel, and the WebP encoder only takes 8-bit RGB(A)
+        ThumbnailFormat::Jpeg => {
+            let rgb = DynamicImage::from(image.to_rgb8());
+            rgb.write_with_encoder(JpegEncoder::new_with_quality(&mut writer, JPEG_QUALITY))?;
+        }
+        ThumbnailFormat::Webp => {
+            let rgba = DynamicImage::from(image.to_rgba8());
+            rgba.write_to(&mut writer, image::ImageFormat::WebP)?;
+        }
+        ThumbnailFormat::Png => image.write_to(&mut writer, image::ImageFormat::Png)?,
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GenericImageView, RgbImage};
+
+    #[test]
+    fn test_snap_width() {
+        assert_eq!(snap_width(320, 1600), 320);
+        assert_eq!(snap_width(300, 1600), 320);
+        assert_eq!(snap_width(0, 1600), 64);
+        assert_eq!(snap_width(5000, 1600), 1600);
+    }
+
+    #[test]
+    fn test_cache_path() {
+        let path = cache_path(
+            Path::new("cache"),
+            Path::new("soccer/smallsize/2024/paper/robot.png"),
+            320,
+            ThumbnailFormat::Webp,
+        );
+        assert_eq!(
+            path,
+            Path::new("cache/soccer/smallsize/2024/paper/robot.png.320.webp")
+        );
+    }
+
+    #[test]
+    fn test_ensure_thumbnail() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("robot.png");
+        RgbImage::new(200, 100).save(&source).unwrap();
+        assert!(is_supported(&source));
+        assert!(!is_supported(&dir.path().join("robot.svg")));
+
+        let target = cache_path(
+            &dir.path().join("cache"),
+            Path::new("robot.png"),
+            64,
+            ThumbnailFormat::Jpeg,
+        );
+        ensure_thumbnail(&source, &target, 64, ThumbnailFormat::Jpeg).unwrap();
+        let thumbnail = image::open(&target).unwrap();
+        assert_eq!(thumbnail.dimensions(), (64, 32));
+
+        // Narrower images keep their size
+        let target = dir.path().join("cache/robot.png.640.webp");
+        ensure_thumbnail(&source, &target, 640, ThumbnailFormat::Webp).unwrap();
+        assert_eq!(image::open(&target).unwrap().dimensions(), (200, 100));
+    }
+}
@@ -1,3 +1,4 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::file::TDPName;
@@ -41,7 +42,7 @@ impl TryFrom<&str> for ContentType {
 // Author & FrontMatter
 // ---------------------------------------------------------------------------
 
-#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
 pub struct Author {
     pub name: String,
     pub affiliation: Option<String>,
@@ -57,7 +58,7 @@ pub struct FrontMatter {
 }
 
 /// Summary metadata for a paper: title, authors, institutions, and URLs.
-#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
 pub struct PaperInfo {
     pub title: String,
     pub authors: Vec<Author>,
@@ -87,6 +88,23 @@ pub struct TocEntry {
     pub title: String,
 }
 
+/// URL of a figure as extracted from the paper, served by the web server's `/tdps` route.
+/// `image_path` is relative to the paper's folder, as in `ContentItem::image_path`.
+pub fn figure_url(paper_lyt: &str, image_path: &str) -> String {
+    format!("/tdps/{}/{}", paper_lyt, image_path)
+}
+
+/// URL of a preview-sized WebP copy of a figure, generated by the web server on first request
+pub fn thumbnail_url(paper_lyt: &str, image_path: &str) -> String {
+    format!("/images/{}/{}", paper_lyt, image_path)
+}
+
+/// A site-relative URL such as [`figure_url`] resolved against the public website, for clients
+/// outside the browser
+pub fn absolute_url(website_url: &str, url: &str) -> String {
+    format!("{}{}", website_url.trim_end_matches('/'), url)
+}
+
 // ---------------------------------------------------------------------------
 // MarkdownTDP
 // ---------------------------------------------------------------------------
@@ -129,4 +147,21 @@ mod tests {
         assert!(item.body.is_empty());
         assert!(item.image_path.is_none());
     }
+
+    #[test]
+    fn test_figure_urls() {
+        let paper = "soccer_smallsize__2024__RoboTeam_Twente";
+        assert_eq!(
+            figure_url(paper, "images/robot.png"),
+            "/tdps/soccer_smallsize__2024__RoboTeam_Twente/images/robot.png"
+        );
+        assert_eq!(
+            thumbnail_url(paper, "images/robot.png"),
+            "/images/soccer_smallsize__2024__RoboTeam_Twente/images/robot.png"
+        );
+        assert_eq!(
+            absolute_url("https://tdps.example.org/", "/images/a/b.png"),
+            "https://tdps.example.org/images/a/b.png"
+        );
+    }
 }
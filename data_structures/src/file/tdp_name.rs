@@ -1,3 +1,4 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::file::{League, LeagueParseError, TeamName};
@@ -16,7 +17,7 @@ pub enum TDPParseError {
     NoFileStem,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct TDPName {
     pub league: League,
     pub team_name: TeamName,
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::content::ContentType;
@@ -17,7 +17,7 @@ pub struct ChunkMetadata {
     pub source_id: Option<String>,
 }
 
-#[derive(Clone, Serialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Chunk {
     pub dense_embedding: Vec<f32>,
     pub sparse_embedding: HashMap<u32, f32>,
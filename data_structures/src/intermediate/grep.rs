@@ -0,0 +1,186 @@
+use crate::filter::Filter;
+use regex::{Regex, RegexBuilder};
+use regex_syntax::hir::{Hir, HirKind};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Minimum literal length the trigram index can use as a prefilter.
+pub const GREP_MIN_INDEXED_LITERAL: usize = 3;
+
+/// Number of characters of surrounding text included on each side of a match.
+const GREP_CONTEXT_CHARS: usize = 40;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GrepScope {
+    /// Match against the body of individual content items (paragraphs, tables, image captions)
+    #[default]
+    Content,
+    /// Match against the full raw markdown of each paper
+    Markdown,
+}
+
+impl GrepScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GrepScope::Content => "content",
+            GrepScope::Markdown => "markdown",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GrepQuery {
+    pub pattern: String,
+    pub regex: bool,
+    pub case_sensitive: bool,
+    pub scope: GrepScope,
+    pub filter: Option<Filter>,
+    pub limit: usize,
+}
+
+impl GrepQuery {
+    /// Compiles the pattern. Literal patterns are escaped so they match verbatim.
+    pub fn build_regex(&self) -> Result<Regex, regex::Error> {
+        let pattern = if self.regex {
+            self.pattern.clone()
+        } else {
+            regex::escape(&self.pattern)
+        };
+
+        RegexBuilder::new(&pattern)
+            .case_insensitive(!self.case_sensitive)
+            .size_limit(1 << 20)
+            .build()
+    }
+
+    /// Returns the longest literal that every match must contain, if it is long enough to be
+    /// looked up in the trigram index. `None` means candidates have to be scanned in full.
+    pub fn required_literal(&self) -> Option<String> {
+        let literal = if self.regex {
+            let hir = regex_syntax::Parser::new().parse(&self.pattern).ok()?;
+            required_literal(&hir)?
+        } else {
+            self.pattern.clone()
+        };
+
+        (literal.chars().count() >= GREP_MIN_INDEXED_LITERAL).then_some(literal)
+    }
+}
+
+fn required_literal(hir: &Hir) -> Option<String> {
+    match hir.kind() {
+        HirKind::Literal(literal) => String::from_utf8(literal.0.to_vec()).ok(),
+        HirKind::Capture(capture) => required_literal(&capture.sub),
+        HirKind::Repetition(repetition) if repetition.min > 0 => required_literal(&repetition.sub),
+        HirKind::Concat(subs) => subs
+            .iter()
+            .filter_map(required_literal)
+            .max_by_key(|literal| literal.chars().count()),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct GrepMatch {
+    pub paper_lyt: String,
+    /// Content item the match was found in. `None` for matches in the raw markdown.
+    pub content_seq: Option<u32>,
+    pub content_type: Option<String>,
+    /// Character offset (not byte offset) of the start of the match
+    pub start: usize,
+    /// Character offset (exclusive) of the end of the match
+    pub end: usize,
+    pub matched: String,
+    pub context: String,
+}
+
+/// Finds all matches of `regex` in `text` and converts byte offsets to character offsets.
+/// Returns `(start, end, matched, context)` tuples.
+pub fn find_matches(regex: &Regex, text: &str) -> Vec<(usize, usize, String, String)> {
+    let mut results = Vec::new();
+    let mut last_byte = 0;
+    let mut last_char = 0;
+
+    for m in regex.find_iter(text) {
+        if m.as_str().is_empty() {
+            continue;
+        }
+
+        let start = last_char + text[last_byte..m.start()].chars().count();
+        let end = start + m.as_str().chars().count();
+        last_byte = m.end();
+        last_char = end;
+
+        let before: String = {
+            let mut chars: Vec<char> = text[..m.start()].chars().rev().take(GREP_CONTEXT_CHARS).collect();
+            chars.reverse();
+            chars.into_iter().collect()
+        };
+        let after: String = text[m.end()..].chars().take(GREP_CONTEXT_CHARS).collect();
+        let context = format!("{}{}{}", before, m.as_str(), after).replace('\n', " ");
+
+        results.push((start, end, m.as_str().to_string(), context));
+    }
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(pattern: &str, regex: bool, case_sensitive: bool) -> GrepQuery {
+        GrepQuery {
+            pattern: pattern.to_string(),
+            regex,
+            case_sensitive,
+            scope: GrepScope::Content,
+            filter: None,
+            limit: 10,
+        }
+    }
+
+    #[test]
+    fn test_literal_pattern_is_escaped() {
+        let regex = query("nRF24L01+", false, true).build_regex().unwrap();
+        assert!(regex.is_match("uses an nRF24L01+ radio"));
+        assert!(!regex.is_match("uses an nRF24L011 radio"));
+    }
+
+    #[test]
+    fn test_case_insensitive() {
+        let regex = query("stm32f7", false, false).build_regex().unwrap();
+        assert!(regex.is_match("An STM32F7 microcontroller"));
+
+        let regex = query("stm32f7", false, true).build_regex().unwrap();
+        assert!(!regex.is_match("An STM32F7 microcontroller"));
+    }
+
+    #[test]
+    fn test_required_literal() {
+        assert_eq!(query("ER-Force", false, true).required_literal(), Some("ER-Force".to_string()));
+        assert_eq!(query("ab", false, true).required_literal(), None);
+        assert_eq!(query(r"STM32F\d+", true, true).required_literal(), Some("STM32F".to_string()));
+        assert_eq!(query(r"(nRF24)L01\+?", true, true).required_literal(), Some("nRF24".to_string()));
+        assert_eq!(query("foo|barbaz", true, true).required_literal(), None);
+        assert_eq!(query(r"\d+ km/h", true, true).required_literal(), Some(" km/h".to_string()));
+    }
+
+    #[test]
+    fn test_find_matches_char_offsets() {
+        let regex = query("robot", false, false).build_regex().unwrap();
+        let text = "Der Roboter — ein robot. Robot!";
+        let matches = find_matches(&regex, text);
+
+        assert_eq!(matches.len(), 3);
+        assert_eq!((matches[0].0, matches[0].1), (4, 9));
+        assert_eq!((matches[1].0, matches[1].1), (18, 23));
+        assert_eq!((matches[2].0, matches[2].1), (25, 30));
+        assert_eq!(matches[2].2, "Robot");
+
+        let chars: Vec<char> = text.chars().collect();
+        let slice: String = chars[matches[1].0..matches[1].1].iter().collect();
+        assert_eq!(slice, "robot");
+    }
+}
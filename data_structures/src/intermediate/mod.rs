@@ -1,10 +1,12 @@
 mod chunk;
+mod grep;
 mod navigation;
 mod search;
 
 use std::collections::HashMap;
 
 pub use chunk::{Chunk, ChunkMetadata};
+pub use grep::{GREP_MIN_INDEXED_LITERAL, GrepMatch, GrepQuery, GrepScope, find_matches};
 pub use navigation::{BreadcrumbEntry, SectionResult};
 pub use search::{SearchResult, SearchResultChunk, SearchSuggestions};
 
@@ -1,3 +1,4 @@
+use crate::content::{figure_url, thumbnail_url};
 use crate::file::{League, TeamName};
 use crate::filter::Filter;
 use crate::intermediate::{BreadcrumbEntry, Chunk};
@@ -6,11 +7,17 @@ use serde::Serialize;
 
 #[derive(Debug, Default, Clone, Serialize, JsonSchema)]
 pub struct SearchResult {
+    /// Unique ID of this result set. Follow-up requests pass it back so clicks can be linked
+    /// to the search that produced them.
+    pub result_set_id: String,
     pub query: String,
     pub filter: Option<Filter>,
     pub chunks: Vec<SearchResultChunk>,
     pub suggestions: SearchSuggestions,
     pub highlight_terms: Vec<String>,
+    /// The dense embedding of the query failed or timed out, so only keyword (sparse) matching
+    /// was used
+    pub degraded: bool,
 }
 
 #[derive(Debug, Clone, Serialize, JsonSchema)]
@@ -27,10 +34,24 @@ pub struct SearchResultChunk {
     pub score: f32,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub breadcrumbs: Vec<BreadcrumbEntry>,
+    /// Original figure, for image chunks
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub figure_url: Option<String>,
+    /// Preview-sized copy of the figure, for image chunks
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumbnail_url: Option<String>,
 }
 
 impl From<Chunk> for SearchResultChunk {
     fn from(chunk: Chunk) -> Self {
+        let figure_url = chunk
+            .image_path
+            .as_deref()
+            .map(|path| figure_url(&chunk.paper_lyt, path));
+        let thumbnail_url = chunk
+            .image_path
+            .as_deref()
+            .map(|path| thumbnail_url(&chunk.paper_lyt, path));
         Self {
             paper_lyt: chunk.paper_lyt,
             league: chunk.league,
@@ -43,6 +64,8 @@ impl From<Chunk> for SearchResultChunk {
             text: chunk.text,
             score: 0.0,
             breadcrumbs: Vec::new(),
+            figure_url,
+            thumbnail_url,
         }
     }
 }
@@ -3,7 +3,7 @@ use std::sync::Arc;
 use tokio::task;
 use tracing::warn;
 
-use crate::{Event, EventListener, EventSource};
+use crate::{Event, EventContext, EventListener, EventSource};
 
 // ---------------------------------------------------------------------------
 // EventDispatcher
@@ -26,21 +26,27 @@ impl EventDispatcher {
 
     pub fn dispatch(&self, source: EventSource, event: Event) {
         let event = Arc::new(event);
+        let context = EventContext::current();
 
         for listener in &self.listeners {
             let listener = Arc::clone(listener);
             let source = source.clone();
             let event = Arc::clone(&event);
 
-            task::spawn(async move {
+            task::spawn(context.clone().scope(async move {
                 if let Err(e) = listener.on_event(&source, &event).await {
+                    metrics::counter!(
+                        "tdp_event_listener_failures_total",
+                        "listener" => listener.name().to_string()
+                    )
+                    .increment(1);
                     warn!(
                         listener = listener.name(),
                         error = %e,
                         "event listener failed"
                     );
                 }
-            });
+            }));
         }
     }
 }
@@ -64,9 +70,11 @@ mod tests {
 
     // -- RecordingListener --------------------------------------------------
 
+    type Recorded = (String, String, Option<String>); // (source, event_type, api_key_id)
+
     #[derive(Clone)]
     struct RecordingListener {
-        events: Arc<Mutex<Vec<(String, String)>>>, // (source, event_type)
+        events: Arc<Mutex<Vec<Recorded>>>,
     }
 
     impl RecordingListener {
@@ -76,7 +84,7 @@ mod tests {
             }
         }
 
-        fn recorded(&self) -> Vec<(String, String)> {
+        fn recorded(&self) -> Vec<Recorded> {
             self.events.lock().unwrap().clone()
         }
     }
@@ -88,10 +96,11 @@ mod tests {
             source: &EventSource,
             event: &Event,
         ) -> Result<(), EventListenerError> {
-            self.events
-                .lock()
-                .unwrap()
-                .push((source.as_str().to_string(), event.event_type().to_string()));
+            self.events.lock().unwrap().push((
+                source.as_str().to_string(),
+                event.event_type().to_string(),
+                EventContext::current().api_key_id,
+            ));
             Ok(())
         }
 
@@ -130,6 +139,9 @@ mod tests {
             year_filter: None,
             team_filter: None,
             content_type_filter: None,
+            result_set_id: "rs".into(),
+            results: vec![],
+            degraded: false,
         })
     }
 
@@ -150,6 +162,26 @@ mod tests {
         assert_eq!(recorded.len(), 1);
         assert_eq!(recorded[0].0, "web");
         assert_eq!(recorded[0].1, "search");
+        assert_eq!(recorded[0].2, None);
+    }
+
+    #[tokio::test]
+    async fn listeners_see_the_dispatching_context() {
+        let listener = Arc::new(RecordingListener::new());
+        let mut dispatcher = EventDispatcher::new();
+        dispatcher.register(listener.clone());
+
+        let context = EventContext {
+            api_key_id: Some("key-1".into()),
+            ..Default::default()
+        };
+        context
+            .scope(async { dispatcher.dispatch(EventSource::Web, make_search_event()) })
+            .await;
+
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+        assert_eq!(listener.recorded()[0].2.as_deref(), Some("key-1"));
     }
 
     #[tokio::test]
@@ -24,6 +24,40 @@ impl EventSource {
     }
 }
 
+// ---------------------------------------------------------------------------
+// EventContext
+// ---------------------------------------------------------------------------
+
+/// Request-scoped details that apply to every event dispatched while handling a request.
+/// Middleware sets it with `scope`; the dispatcher carries it over to listeners, which read
+/// it with `current`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventContext {
+    /// ID of the API key the request authenticated with
+    pub api_key_id: Option<String>,
+    /// Crawler classification of the client: "human", "crawler", "llm_agent" or "scraper"
+    pub client_class: Option<String>,
+    /// IP of the client, which tells apart the searches of different users
+    pub client_ip: Option<String>,
+    /// Set by the crawler `exclude` policy. Analytics listeners skip these events.
+    pub exclude_from_analytics: bool,
+}
+
+tokio::task_local! {
+    static EVENT_CONTEXT: EventContext;
+}
+
+impl EventContext {
+    pub async fn scope<F: std::future::Future>(self, future: F) -> F::Output {
+        EVENT_CONTEXT.scope(self, future).await
+    }
+
+    /// The context of the current task, empty outside `scope`
+    pub fn current() -> Self {
+        EVENT_CONTEXT.try_with(Clone::clone).unwrap_or_default()
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Event structs
 // ---------------------------------------------------------------------------
@@ -37,6 +71,30 @@ pub struct SearchEvent {
     pub year_filter: Option<String>,
     pub team_filter: Option<String>,
     pub content_type_filter: Option<String>,
+    /// Referenced by follow-up events that originate from this search
+    pub result_set_id: String,
+    /// Returned chunks, in rank order
+    pub results: Vec<SearchEventResult>,
+    /// Dense embedding failed, so the search fell back to sparse only
+    pub degraded: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchEventResult {
+    pub paper_lyt: String,
+    pub content_seq: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GrepEvent {
+    pub pattern: String,
+    pub regex: bool,
+    pub case_sensitive: bool,
+    pub scope: String,
+    pub result_count: usize,
+    pub league_filter: Option<String>,
+    pub year_filter: Option<String>,
+    pub team_filter: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -86,6 +144,7 @@ pub struct GetSectionEvent {
     pub content_seq: u32,
     pub include_children: bool,
     pub items_returned: usize,
+    pub result_set_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -127,11 +186,13 @@ pub struct HttpRequestEvent {
 pub struct PaperOpenEvent {
     pub paper_id: String,
     pub referrer: Option<String>,
+    pub result_set_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct PdfOpenEvent {
     pub paper_id: String,
+    pub result_set_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -160,6 +221,30 @@ pub struct GetReferencesEvent {
     pub paper: String,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportDatasetEvent {
+    pub format: String,
+    pub schema_version: u32,
+    pub row_count: usize,
+    pub include_dense: bool,
+    pub include_sparse: bool,
+    pub league_filter: Option<String>,
+    pub year_filter: Option<String>,
+    pub team_filter: Option<String>,
+    pub content_type_filter: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ThrottledEvent {
+    /// Route class whose limit was hit: "search", "content" or "write", or "quota" when an
+    /// API key used up its daily quota
+    pub route_class: String,
+    pub path: String,
+    pub ip: Option<String>,
+    /// Seconds until the client may retry
+    pub retry_after_secs: u64,
+}
+
 // ---------------------------------------------------------------------------
 // Event enum
 // ---------------------------------------------------------------------------
@@ -168,6 +253,7 @@ pub struct GetReferencesEvent {
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum Event {
     Search(SearchEvent),
+    Grep(GrepEvent),
     ListLeagues(ListLeaguesEvent),
     ListYears(ListYearsEvent),
     ListTeams(ListTeamsEvent),
@@ -188,12 +274,15 @@ pub enum Event {
     GetLeagueInfo(GetLeagueInfoEvent),
     UpdateTeamInfo(UpdateTeamInfoEvent),
     GetReferences(GetReferencesEvent),
+    ExportDataset(ExportDatasetEvent),
+    Throttled(ThrottledEvent),
 }
 
 impl Event {
     pub fn event_type(&self) -> &'static str {
         match self {
             Event::Search(_) => "search",
+            Event::Grep(_) => "grep",
             Event::ListLeagues(_) => "list_leagues",
             Event::ListYears(_) => "list_years",
             Event::ListTeams(_) => "list_teams",
@@ -214,6 +303,8 @@ impl Event {
             Event::GetLeagueInfo(_) => "get_league_info",
             Event::UpdateTeamInfo(_) => "update_team_info",
             Event::GetReferences(_) => "get_references",
+            Event::ExportDataset(_) => "export_dataset",
+            Event::Throttled(_) => "throttled",
         }
     }
 }
@@ -233,6 +324,9 @@ pub enum EventListenerError {
     #[error("HTTP error: {0}")]
     Http(#[from] reqwest::Error),
 
+    #[error(transparent)]
+    Migration(#[from] data_access::migration::MigrationError),
+
     #[error("{0}")]
     Other(String),
 }
@@ -266,7 +360,20 @@ mod tests {
                 year_filter: None,
                 team_filter: None,
                 content_type_filter: None,
+                result_set_id: "rs".into(),
+                results: vec![],
+                degraded: false,
             }), "search"),
+            (Event::Grep(GrepEvent {
+                pattern: "STM32F7".into(),
+                regex: false,
+                case_sensitive: true,
+                scope: "content".into(),
+                result_count: 2,
+                league_filter: None,
+                year_filter: None,
+                team_filter: None,
+            }), "grep"),
             (Event::ListLeagues(ListLeaguesEvent { result_count: 3 }), "list_leagues"),
             (Event::ListYears(ListYearsEvent { league: None, team: None, result_count: 2 }), "list_years"),
             (Event::ListTeams(ListTeamsEvent { hint: None, result_count: 1 }), "list_teams"),
@@ -274,18 +381,20 @@ mod tests {
             (Event::GetAbstract(GetAbstractEvent { paper: "p".into() }), "get_abstract"),
             (Event::GetPaperInfo(GetPaperInfoEvent { paper: "p".into() }), "get_paper_info"),
             (Event::GetTableOfContents(GetTableOfContentsEvent { paper: "p".into() }), "get_table_of_contents"),
-            (Event::GetSection(GetSectionEvent { paper: "p".into(), content_seq: 1, include_children: false, items_returned: 0 }), "get_section"),
+            (Event::GetSection(GetSectionEvent { paper: "p".into(), content_seq: 1, include_children: false, items_returned: 0, result_set_id: None }), "get_section"),
             (Event::GetParagraph(GetParagraphEvent { paper: "p".into(), content_seq: 1 }), "get_paragraph"),
             (Event::GetTable(GetTableEvent { paper: "p".into(), content_seq: 1 }), "get_table"),
             (Event::GetImage(GetImageEvent { paper: "p".into(), content_seq: 1 }), "get_image"),
             (Event::GetTdpContents(GetTdpContentsEvent { league: "l".into(), year: "y".into(), team: "t".into() }), "get_tdp_contents"),
             (Event::HttpRequest(HttpRequestEvent { method: "GET".into(), path: "/".into(), status: 200, duration_ms: 10, ip: None, user_agent: "ua".into() }), "http_request"),
-            (Event::PaperOpen(PaperOpenEvent { paper_id: "p".into(), referrer: None }), "paper_open"),
-            (Event::PdfOpen(PdfOpenEvent { paper_id: "p".into() }), "pdf_open"),
+            (Event::PaperOpen(PaperOpenEvent { paper_id: "p".into(), referrer: None, result_set_id: None }), "paper_open"),
+            (Event::PdfOpen(PdfOpenEvent { paper_id: "p".into(), result_set_id: None }), "pdf_open"),
             (Event::Suggestion(SuggestionEvent { message: "test suggestion".into() }), "suggestion"),
             (Event::GetTeamInfo(GetTeamInfoEvent { team: "t".into() }), "get_team_info"),
             (Event::UpdateTeamInfo(UpdateTeamInfoEvent { team: "t".into(), entries: vec![] }), "update_team_info"),
             (Event::GetReferences(GetReferencesEvent { paper: "p".into() }), "get_references"),
+            (Event::ExportDataset(ExportDatasetEvent { format: "jsonl".into(), schema_version: 1, row_count: 0, include_dense: false, include_sparse: false, league_filter: None, year_filter: None, team_filter: None, content_type_filter: None }), "export_dataset"),
+            (Event::Throttled(ThrottledEvent { route_class: "search".into(), path: "/api/search".into(), ip: None, retry_after_secs: 1 }), "throttled"),
         ];
 
         for (event, expected) in cases {
@@ -309,6 +418,9 @@ mod tests {
             year_filter: None,
             team_filter: None,
             content_type_filter: None,
+            result_set_id: "rs".into(),
+            results: vec![],
+            degraded: false,
         });
 
         let json = serde_json::to_value(&event).unwrap();
@@ -326,8 +438,8 @@ mod tests {
             Event::ListLeagues(ListLeaguesEvent { result_count: 5 }),
             Event::GetAbstract(GetAbstractEvent { paper: "test_paper".into() }),
             Event::GetPaperInfo(GetPaperInfoEvent { paper: "test_paper".into() }),
-            Event::PaperOpen(PaperOpenEvent { paper_id: "id".into(), referrer: Some("https://example.com".into()) }),
-            Event::PdfOpen(PdfOpenEvent { paper_id: "test_paper".into() }),
+            Event::PaperOpen(PaperOpenEvent { paper_id: "id".into(), referrer: Some("https://example.com".into()), result_set_id: Some("rs".into()) }),
+            Event::PdfOpen(PdfOpenEvent { paper_id: "test_paper".into(), result_set_id: None }),
             Event::Suggestion(SuggestionEvent { message: "improve search".into() }),
             Event::GetReferences(GetReferencesEvent { paper: "test_paper".into() }),
         ];
@@ -1,14 +1,18 @@
 use std::future::Future;
+use std::net::IpAddr;
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use async_trait::async_trait;
+use data_access::migration::{Migration, MigrationError, MigrationStatus, Migrator};
+use data_access::sqlite_pool::{DEFAULT_POOL_SIZE, SqlitePool, SqlitePoolError};
+use hmac::{Hmac, Mac};
 use rusqlite::Connection;
 use serde::Deserialize;
-use tokio::sync::Mutex;
-use tokio::task;
+use sha2::Sha256;
 
-use crate::{Event, EventListener, EventListenerError, EventSource};
+use crate::{Event, EventContext, EventListener, EventListenerError, EventSource};
 
 // ---------------------------------------------------------------------------
 // Config
@@ -17,12 +21,100 @@ use crate::{Event, EventListener, EventListenerError, EventSource};
 #[derive(Debug, Deserialize, Clone)]
 pub struct SqliteListenerConfig {
     pub filename: String,
+    /// Number of read-only connections. Defaults to 4.
+    pub pool_size: Option<usize>,
+    /// Days to keep raw events. Older days are folded into `daily_rollups` and deleted.
+    /// Events are kept forever when unset.
+    pub retention_days: Option<u32>,
+    /// How client IPs are stored. Defaults to raw.
+    pub ip_storage: Option<IpStorage>,
+    /// Secret salt for `ip_storage = "hash"`
+    pub ip_salt: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IpStorage {
+    #[default]
+    Raw,
+    /// Keep the /24 network of IPv4 addresses and the /48 of IPv6 addresses
+    Truncate,
+    /// Keep a salted HMAC of the address, still good for counting distinct clients
+    Hash,
+}
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Debug, Clone)]
+enum IpAnonymizer {
+    Raw,
+    Truncate,
+    Hash(String),
+}
+
+impl IpAnonymizer {
+    fn new(config: &SqliteListenerConfig) -> Result<Self, EventListenerError> {
+        match config.ip_storage.unwrap_or_default() {
+            IpStorage::Raw => Ok(Self::Raw),
+            IpStorage::Truncate => Ok(Self::Truncate),
+            IpStorage::Hash => match config.ip_salt.as_deref() {
+                Some(salt) if !salt.is_empty() => Ok(Self::Hash(salt.to_string())),
+                _ => Err(EventListenerError::Other(
+                    "ip_storage = \"hash\" requires a non-empty ip_salt".into(),
+                )),
+            },
+        }
+    }
+
+    /// The form in which `ip` is stored. Unparseable addresses are dropped when truncating.
+    fn anonymize(&self, ip: &str) -> Option<String> {
+        match self {
+            Self::Raw => Some(ip.to_string()),
+            Self::Truncate => match ip.parse::<IpAddr>().ok()? {
+                IpAddr::V4(v4) => {
+                    let [a, b, c, _] = v4.octets();
+                    Some(format!("{a}.{b}.{c}.0"))
+                }
+                IpAddr::V6(v6) => {
+                    let s = v6.segments();
+                    Some(format!("{:x}:{:x}:{:x}::", s[0], s[1], s[2]))
+                }
+            },
+            Self::Hash(salt) => {
+                let mut mac = HmacSha256::new_from_slice(salt.as_bytes())
+                    .expect("HMAC accepts any key size");
+                mac.update(ip.as_bytes());
+                Some(hex::encode(mac.finalize().into_bytes())[..16].to_string())
+            }
+        }
+    }
 }
 
 // ---------------------------------------------------------------------------
-// ActivityEvent (query result)
+// RetentionSummary
 // ---------------------------------------------------------------------------
 
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RetentionSummary {
+    /// Events older than this day (YYYY-MM-DD) were rolled up
+    pub cutoff: String,
+    pub events_deleted: usize,
+}
+
+// ---------------------------------------------------------------------------
+// Query results
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DailyRollup {
+    pub day: String,
+    pub event_type: String,
+    pub source: String,
+    /// Lowercased query for search events, empty otherwise
+    pub query: String,
+    pub count: i64,
+}
+
 #[derive(Debug, Clone)]
 pub struct ActivityEvent {
     pub id: i64,
@@ -30,34 +122,26 @@ pub struct ActivityEvent {
     pub source: String,
     pub event_type: String,
     pub payload: Option<String>,
+    /// API key the request that caused the event authenticated with
+    pub api_key_id: Option<String>,
+    /// Crawler classification of the client that caused the event
+    pub client_class: Option<String>,
+    /// IP of the client that caused the event, in its stored form (see `ip_storage`)
+    pub client_ip: Option<String>,
 }
 
 // ---------------------------------------------------------------------------
 // SqliteListener
 // ---------------------------------------------------------------------------
 
-pub struct SqliteListener {
-    conn: Arc<Mutex<Connection>>,
-}
+const DATABASE_NAME: &str = "activity database";
 
-impl SqliteListener {
-    pub fn new(config: &SqliteListenerConfig) -> Result<Self, EventListenerError> {
-        let conn = Connection::open(&config.filename)
-            .map_err(EventListenerError::Database)?;
-
-        conn.pragma_update(None, "journal_mode", "WAL")
-            .map_err(EventListenerError::Database)?;
-
-        Self::ensure_schema(&conn)?;
-
-        Ok(Self {
-            conn: Arc::new(Mutex::new(conn)),
-        })
-    }
-
-    fn ensure_schema(conn: &Connection) -> Result<(), EventListenerError> {
-        conn.execute_batch(
-            "CREATE TABLE IF NOT EXISTS events (
+static MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "events table",
+        sql: "
+            CREATE TABLE IF NOT EXISTS events (
                 id         INTEGER PRIMARY KEY AUTOINCREMENT,
                 timestamp  TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
                 source     TEXT NOT NULL,
@@ -66,11 +150,79 @@ impl SqliteListener {
             );
             CREATE INDEX IF NOT EXISTS idx_events_source     ON events(source);
             CREATE INDEX IF NOT EXISTS idx_events_event_type ON events(event_type);
-            CREATE INDEX IF NOT EXISTS idx_events_timestamp  ON events(timestamp);",
+            CREATE INDEX IF NOT EXISTS idx_events_timestamp  ON events(timestamp);
+        ",
+    },
+    Migration {
+        version: 2,
+        description: "request context columns on events and daily_rollups table",
+        sql: "
+            ALTER TABLE events ADD COLUMN api_key_id TEXT;
+            ALTER TABLE events ADD COLUMN client_class TEXT;
+            ALTER TABLE events ADD COLUMN client_ip TEXT;
+            CREATE TABLE IF NOT EXISTS daily_rollups (
+                day        TEXT NOT NULL,
+                event_type TEXT NOT NULL,
+                source     TEXT NOT NULL,
+                query      TEXT NOT NULL DEFAULT '',
+                count      INTEGER NOT NULL,
+                PRIMARY KEY (day, event_type, source, query)
+            );
+        ",
+    },
+];
+
+pub struct SqliteListener {
+    pool: SqlitePool,
+    retention_days: Option<u32>,
+    ip_anonymizer: IpAnonymizer,
+    /// Unix day of the last automatic retention pass
+    last_retention_day: AtomicU64,
+}
+
+impl SqliteListener {
+    pub fn new(config: &SqliteListenerConfig) -> Result<Self, EventListenerError> {
+        let pool = SqlitePool::open(
+            &config.filename,
+            config.pool_size.unwrap_or(DEFAULT_POOL_SIZE),
         )
         .map_err(EventListenerError::Database)?;
+        let ip_anonymizer = IpAnonymizer::new(config)?;
 
-        Ok(())
+        pool.write_blocking(|conn| Self::migrator(config).check(conn))?;
+
+        Ok(Self {
+            pool,
+            retention_days: config.retention_days,
+            ip_anonymizer,
+            last_retention_day: AtomicU64::new(0),
+        })
+    }
+
+    pub fn migration_status(config: &SqliteListenerConfig) -> Result<MigrationStatus, MigrationError> {
+        let conn = Self::open(config)?;
+        Self::migrator(config).status(&conn)
+    }
+
+    pub fn migrate(config: &SqliteListenerConfig) -> Result<Vec<&'static Migration>, MigrationError> {
+        let mut conn = Self::open(config)?;
+        Self::migrator(config).apply(&mut conn)
+    }
+
+    fn migrator(config: &SqliteListenerConfig) -> Migrator {
+        Migrator::new(format!("{} ({})", DATABASE_NAME, config.filename), MIGRATIONS)
+    }
+
+    fn open(config: &SqliteListenerConfig) -> Result<Connection, MigrationError> {
+        let map_err = |source| MigrationError::Sqlite {
+            database: config.filename.clone(),
+            source,
+        };
+
+        let conn = Connection::open(&config.filename).map_err(map_err)?;
+        conn.execute_batch("PRAGMA journal_mode=WAL;").map_err(map_err)?;
+
+        Ok(conn)
     }
 
     pub fn query_events(
@@ -82,19 +234,8 @@ impl SqliteListener {
     ) -> Pin<Box<dyn Future<Output = Result<Vec<ActivityEvent>, EventListenerError>> + Send + '_>>
     {
         Box::pin(async move {
-            let conn = self.conn.clone();
-
-            task::spawn_blocking(move || {
-                // We need to block on the async mutex from a sync context inside
-                // spawn_blocking. Instead, we'll build the connection Arc and
-                // acquire it via tokio's blocking helpers. However, since we're
-                // already in spawn_blocking, we can use try_lock or block on it.
-                // The safest approach: use a std Mutex instead. But we already
-                // have a tokio Mutex. We'll use Handle::current().block_on.
-                let rt = tokio::runtime::Handle::current();
-                let conn = rt.block_on(conn.lock());
-
-                let mut sql = String::from("SELECT id, timestamp, source, event_type, payload FROM events WHERE 1=1");
+            self.pool.read(move |conn| {
+                let mut sql = String::from("SELECT id, timestamp, source, event_type, payload, api_key_id, client_class, client_ip FROM events WHERE 1=1");
                 let mut params: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
 
                 if let Some(ref s) = source {
@@ -129,6 +270,9 @@ impl SqliteListener {
                             source: row.get(2)?,
                             event_type: row.get(3)?,
                             payload: row.get(4)?,
+                            api_key_id: row.get(5)?,
+                            client_class: row.get(6)?,
+                            client_ip: row.get(7)?,
                         })
                     })
                     .map_err(EventListenerError::Database)?;
@@ -141,11 +285,155 @@ impl SqliteListener {
                 Ok(events)
             })
             .await
-            .unwrap_or_else(|e| Err(EventListenerError::Other(format!("join error: {e}"))))
+            .map_err(pool_error)?
         })
     }
 }
 
+impl SqliteListener {
+    /// Fold every whole day older than the retention window into `daily_rollups` and delete
+    /// those raw events. Searches are counted per lowercased query, other events per type and
+    /// source only. Returns `None` when no retention window is configured.
+    pub fn apply_retention(
+        &self,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<RetentionSummary>, EventListenerError>> + Send + '_>>
+    {
+        Box::pin(async move {
+            let Some(days) = self.retention_days else {
+                return Ok(None);
+            };
+
+            self.pool
+                .write(move |conn| {
+                    let tx = conn.transaction().map_err(EventListenerError::Database)?;
+                    let cutoff: String = tx
+                        .query_row("SELECT date('now', ?1)", [format!("-{days} days")], |row| {
+                            row.get(0)
+                        })
+                        .map_err(EventListenerError::Database)?;
+
+                    tx.execute(
+                        "INSERT INTO daily_rollups (day, event_type, source, query, count)
+                         SELECT substr(timestamp, 1, 10), event_type, source,
+                                CASE WHEN event_type = 'search'
+                                     THEN COALESCE(lower(trim(json_extract(payload, '$.query'))), '')
+                                     ELSE '' END AS q,
+                                COUNT(*)
+                         FROM events WHERE timestamp < ?1
+                         GROUP BY 1, 2, 3, 4
+                         ON CONFLICT (day, event_type, source, query)
+                         DO UPDATE SET count = count + excluded.count",
+                        [&cutoff],
+                    )
+                    .map_err(EventListenerError::Database)?;
+                    let events_deleted = tx
+                        .execute("DELETE FROM events WHERE timestamp < ?1", [&cutoff])
+                        .map_err(EventListenerError::Database)?;
+                    tx.commit().map_err(EventListenerError::Database)?;
+
+                    Ok(Some(RetentionSummary {
+                        cutoff,
+                        events_deleted,
+                    }))
+                })
+                .await
+                .map_err(pool_error)?
+        })
+    }
+
+    /// Run `apply_retention` once per day, piggybacking on incoming events. A failed pass is
+    /// logged and retried on the next event; it does not fail the event, which is already stored.
+    async fn apply_retention_daily(&self) {
+        if self.retention_days.is_none() {
+            return;
+        }
+        let today = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() / 86_400)
+            .unwrap_or_default();
+        if self.last_retention_day.load(Ordering::Relaxed) == today {
+            return;
+        }
+        match self.apply_retention().await {
+            Ok(summary) => {
+                self.last_retention_day.store(today, Ordering::Relaxed);
+                if let Some(summary) = summary {
+                    tracing::info!(
+                        "Activity retention: rolled up and deleted {} events before {}",
+                        summary.events_deleted,
+                        summary.cutoff
+                    );
+                }
+            }
+            Err(e) => tracing::warn!("Activity retention failed, retrying on the next event: {}", e),
+        }
+    }
+
+    /// Daily rollups, newest day first
+    pub fn query_rollups(
+        &self,
+        since: Option<String>,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<DailyRollup>, EventListenerError>> + Send + '_>>
+    {
+        Box::pin(async move {
+            self.pool
+                .read(move |conn| {
+                    let mut stmt = conn
+                        .prepare(
+                            "SELECT day, event_type, source, query, count FROM daily_rollups
+                             WHERE day >= COALESCE(?1, '') ORDER BY day DESC, count DESC",
+                        )
+                        .map_err(EventListenerError::Database)?;
+                    let rows = stmt
+                        .query_map([since], |row| {
+                            Ok(DailyRollup {
+                                day: row.get(0)?,
+                                event_type: row.get(1)?,
+                                source: row.get(2)?,
+                                query: row.get(3)?,
+                                count: row.get(4)?,
+                            })
+                        })
+                        .map_err(EventListenerError::Database)?;
+                    rows.collect::<Result<Vec<_>, _>>()
+                        .map_err(EventListenerError::Database)
+                })
+                .await
+                .map_err(pool_error)?
+        })
+    }
+
+    /// Delete every stored event recorded for `ip`, matching both the raw address and the form
+    /// it is stored in under the current `ip_storage` setting. Returns the number of deleted
+    /// events. With truncation this removes the whole network the address belongs to.
+    pub fn purge_ip(
+        &self,
+        ip: String,
+    ) -> Pin<Box<dyn Future<Output = Result<usize, EventListenerError>> + Send + '_>> {
+        Box::pin(async move {
+            let stored = self.ip_anonymizer.anonymize(&ip).unwrap_or_else(|| ip.clone());
+            self.pool
+                .write(move |conn| {
+                    conn.execute(
+                        "DELETE FROM events
+                         WHERE json_extract(payload, '$.ip') IN (?1, ?2) OR client_ip IN (?1, ?2)",
+                        [&ip, &stored],
+                    )
+                    .map_err(EventListenerError::Database)
+                })
+                .await
+                .map_err(pool_error)?
+        })
+    }
+}
+
+fn pool_error(e: SqlitePoolError) -> EventListenerError {
+    match e {
+        SqlitePoolError::Sqlite(e) => EventListenerError::Database(e),
+        SqlitePoolError::Task(e) => EventListenerError::Other(e),
+    }
+}
+
 // ---------------------------------------------------------------------------
 // EventListener impl
 // ---------------------------------------------------------------------------
@@ -157,25 +445,51 @@ impl EventListener for SqliteListener {
         source: &EventSource,
         event: &Event,
     ) -> Result<(), EventListenerError> {
-        let payload = serde_json::to_string(event)?;
+        let context = EventContext::current();
+        if context.exclude_from_analytics {
+            return Ok(());
+        }
+
+        let payload = match event {
+            Event::HttpRequest(e) => {
+                let mut e = e.clone();
+                e.ip = e.ip.and_then(|ip| self.ip_anonymizer.anonymize(&ip));
+                serde_json::to_string(&Event::HttpRequest(e))?
+            }
+            Event::Throttled(e) => {
+                let mut e = e.clone();
+                e.ip = e.ip.and_then(|ip| self.ip_anonymizer.anonymize(&ip));
+                serde_json::to_string(&Event::Throttled(e))?
+            }
+            _ => serde_json::to_string(event)?,
+        };
         let event_type = event.event_type().to_string();
         let source_str = source.as_str().to_string();
-        let conn = self.conn.clone();
-
-        task::spawn_blocking(move || {
-            let rt = tokio::runtime::Handle::current();
-            let conn = rt.block_on(conn.lock());
-
-            conn.execute(
-                "INSERT INTO events (source, event_type, payload) VALUES (?1, ?2, ?3)",
-                rusqlite::params![source_str, event_type, payload],
-            )
-            .map_err(EventListenerError::Database)?;
+        let client_ip = context
+            .client_ip
+            .and_then(|ip| self.ip_anonymizer.anonymize(&ip));
+
+        self.pool
+            .write(move |conn| {
+                conn.execute(
+                    "INSERT INTO events (source, event_type, payload, api_key_id, client_class, client_ip)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    rusqlite::params![
+                        source_str,
+                        event_type,
+                        payload,
+                        context.api_key_id,
+                        context.client_class,
+                        client_ip
+                    ],
+                )
+                .map_err(EventListenerError::Database)
+            })
+            .await
+            .map_err(pool_error)??;
 
-            Ok(())
-        })
-        .await
-        .unwrap_or_else(|e| Err(EventListenerError::Other(format!("join error: {e}"))))
+        self.apply_retention_daily().await;
+        Ok(())
     }
 
     fn name(&self) -> &str {
@@ -192,11 +506,51 @@ mod tests {
     use super::*;
     use crate::SearchEvent;
 
-    fn make_listener() -> SqliteListener {
-        let config = SqliteListenerConfig {
+    fn make_config() -> SqliteListenerConfig {
+        SqliteListenerConfig {
             filename: ":memory:".into(),
-        };
-        SqliteListener::new(&config).unwrap()
+            pool_size: None,
+            retention_days: None,
+            ip_storage: None,
+            ip_salt: None,
+        }
+    }
+
+    fn make_listener() -> SqliteListener {
+        SqliteListener::new(&make_config()).unwrap()
+    }
+
+    fn make_http_event(ip: &str) -> Event {
+        Event::HttpRequest(crate::HttpRequestEvent {
+            method: "GET".into(),
+            path: "/".into(),
+            status: 200,
+            duration_ms: 5,
+            ip: Some(ip.into()),
+            user_agent: "test".into(),
+        })
+    }
+
+    async fn log_request(listener: &SqliteListener, ip: &str) {
+        listener
+            .on_event(&EventSource::Web, &make_http_event(ip))
+            .await
+            .unwrap();
+    }
+
+    async fn stored_ips(listener: &SqliteListener) -> Vec<Option<String>> {
+        let events = listener
+            .query_events(None, Some("http_request".into()), None, None)
+            .await
+            .unwrap();
+        events
+            .iter()
+            .map(|e| {
+                let payload: serde_json::Value =
+                    serde_json::from_str(e.payload.as_ref().unwrap()).unwrap();
+                payload["ip"].as_str().map(|s| s.to_string())
+            })
+            .collect()
     }
 
     fn make_search_event() -> Event {
@@ -208,6 +562,9 @@ mod tests {
             year_filter: None,
             team_filter: None,
             content_type_filter: None,
+            result_set_id: "rs".into(),
+            results: vec![],
+            degraded: false,
         })
     }
 
@@ -229,6 +586,39 @@ mod tests {
         let payload: serde_json::Value =
             serde_json::from_str(events[0].payload.as_ref().unwrap()).unwrap();
         assert_eq!(payload["query"], "navigation");
+        assert_eq!(events[0].api_key_id, None);
+    }
+
+    #[tokio::test]
+    async fn on_event_stores_the_request_context() {
+        let listener = make_listener();
+        let context = EventContext {
+            api_key_id: Some("0123456789abcdef".into()),
+            client_class: Some("llm_agent".into()),
+            client_ip: Some("203.0.113.7".into()),
+            exclude_from_analytics: false,
+        };
+
+        context
+            .scope(listener.on_event(&EventSource::Mcp, &make_search_event()))
+            .await
+            .unwrap();
+
+        let events = listener.query_events(None, None, None, None).await.unwrap();
+        assert_eq!(events[0].api_key_id.as_deref(), Some("0123456789abcdef"));
+        assert_eq!(events[0].client_class.as_deref(), Some("llm_agent"));
+        assert_eq!(events[0].client_ip.as_deref(), Some("203.0.113.7"));
+
+        let excluded = EventContext {
+            client_class: Some("scraper".into()),
+            exclude_from_analytics: true,
+            ..Default::default()
+        };
+        excluded
+            .scope(listener.on_event(&EventSource::Web, &make_search_event()))
+            .await
+            .unwrap();
+        assert_eq!(listener.query_events(None, None, None, None).await.unwrap().len(), 1);
     }
 
     #[tokio::test]
@@ -293,4 +683,211 @@ mod tests {
         // Most recent first (DESC) — the second insert should be first
         assert!(events[0].id > events[1].id);
     }
+
+    #[tokio::test]
+    async fn ip_storage_truncate_and_hash() {
+        let truncating = SqliteListener::new(&SqliteListenerConfig {
+            ip_storage: Some(IpStorage::Truncate),
+            ..make_config()
+        })
+        .unwrap();
+        log_request(&truncating, "203.0.113.57").await;
+        log_request(&truncating, "2001:db8:85a3::8a2e:370:7334").await;
+        log_request(&truncating, "not-an-ip").await;
+        let mut ips = stored_ips(&truncating).await;
+        ips.sort();
+        assert_eq!(
+            ips,
+            vec![None, Some("2001:db8:85a3::".into()), Some("203.0.113.0".into())]
+        );
+
+        let hashing = SqliteListener::new(&SqliteListenerConfig {
+            ip_storage: Some(IpStorage::Hash),
+            ip_salt: Some("secret".into()),
+            ..make_config()
+        })
+        .unwrap();
+        log_request(&hashing, "203.0.113.57").await;
+        log_request(&hashing, "203.0.113.57").await;
+        let ips = stored_ips(&hashing).await;
+        assert_eq!(ips[0], ips[1]);
+        let hashed = ips[0].clone().unwrap();
+        assert_eq!(hashed.len(), 16);
+        assert!(!hashed.contains("203"));
+
+        // Hashing without a salt is a configuration error
+        let unsalted = SqliteListener::new(&SqliteListenerConfig {
+            ip_storage: Some(IpStorage::Hash),
+            ..make_config()
+        });
+        assert!(unsalted.is_err());
+    }
+
+    #[tokio::test]
+    async fn purge_ip_matches_stored_form() {
+        let listener = SqliteListener::new(&SqliteListenerConfig {
+            ip_storage: Some(IpStorage::Hash),
+            ip_salt: Some("secret".into()),
+            ..make_config()
+        })
+        .unwrap();
+        log_request(&listener, "198.51.100.1").await;
+        log_request(&listener, "198.51.100.2").await;
+        listener
+            .on_event(&EventSource::Web, &make_search_event())
+            .await
+            .unwrap();
+        let context = EventContext {
+            client_ip: Some("198.51.100.1".into()),
+            ..Default::default()
+        };
+        context
+            .scope(listener.on_event(&EventSource::Web, &make_search_event()))
+            .await
+            .unwrap();
+
+        assert_eq!(listener.purge_ip("198.51.100.1".into()).await.unwrap(), 2);
+        assert_eq!(listener.purge_ip("198.51.100.1".into()).await.unwrap(), 0);
+        assert_eq!(stored_ips(&listener).await.len(), 1);
+        let remaining = listener.query_events(None, None, None, None).await.unwrap();
+        assert_eq!(remaining.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn retention_rolls_up_old_days() {
+        let listener = SqliteListener::new(&SqliteListenerConfig {
+            retention_days: Some(30),
+            ..make_config()
+        })
+        .unwrap();
+        assert!(make_listener().apply_retention().await.unwrap().is_none());
+
+        listener
+            .pool
+            .write_blocking(|conn| {
+                conn.execute_batch(
+                    r#"INSERT INTO events (timestamp, source, event_type, payload) VALUES
+                        ('2020-01-01T10:00:00.000Z', 'web', 'search', '{"query":"Kicker "}'),
+                        ('2020-01-01T11:00:00.000Z', 'web', 'search', '{"query":"kicker"}'),
+                        ('2020-01-01T12:00:00.000Z', 'mcp', 'list_leagues', '{"result_count":5}'),
+                        ('2020-01-02T12:00:00.000Z', 'web', 'search', '{"query":"kicker"}');"#,
+                )
+            })
+            .unwrap();
+        listener
+            .on_event(&EventSource::Web, &make_search_event())
+            .await
+            .unwrap();
+
+        // The daily pass already ran on the insert above
+        let summary = listener.apply_retention().await.unwrap().unwrap();
+        assert_eq!(summary.events_deleted, 0);
+
+        let remaining = listener.query_events(None, None, None, None).await.unwrap();
+        assert_eq!(remaining.len(), 1);
+
+        let rollups = listener.query_rollups(None).await.unwrap();
+        let row = |day: &str, event_type: &str, source: &str, query: &str, count| DailyRollup {
+            day: day.into(),
+            event_type: event_type.into(),
+            source: source.into(),
+            query: query.into(),
+            count,
+        };
+        assert_eq!(
+            rollups,
+            vec![
+                row("2020-01-02", "search", "web", "kicker", 1),
+                row("2020-01-01", "search", "web", "kicker", 2),
+                row("2020-01-01", "list_leagues", "mcp", "", 1),
+            ]
+        );
+        let since = listener.query_rollups(Some("2020-01-02".into())).await.unwrap();
+        assert_eq!(since.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn failed_retention_is_retried_without_failing_events() {
+        let listener = SqliteListener::new(&SqliteListenerConfig {
+            retention_days: Some(30),
+            ..make_config()
+        })
+        .unwrap();
+        // Without the rollup table the retention pass fails
+        listener
+            .pool
+            .write_blocking(|conn| {
+                conn.execute_batch(
+                    "INSERT INTO events (timestamp, source, event_type, payload)
+                     VALUES ('2020-01-01T10:00:00.000Z', 'web', 'list_leagues', '{}');
+                     ALTER TABLE daily_rollups RENAME TO daily_rollups_moved;",
+                )
+            })
+            .unwrap();
+
+        listener
+            .on_event(&EventSource::Web, &make_search_event())
+            .await
+            .unwrap();
+        assert_eq!(listener.query_events(None, None, None, None).await.unwrap().len(), 2);
+
+        listener
+            .pool
+            .write_blocking(|conn| {
+                conn.execute_batch("ALTER TABLE daily_rollups_moved RENAME TO daily_rollups;")
+            })
+            .unwrap();
+        listener
+            .on_event(&EventSource::Web, &make_search_event())
+            .await
+            .unwrap();
+        assert_eq!(listener.query_events(None, None, None, None).await.unwrap().len(), 2);
+        assert_eq!(listener.query_rollups(None).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn legacy_database_requires_migration() {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let db_filename = format!("test_activity_migrate_{}.db", timestamp);
+        let config = SqliteListenerConfig {
+            filename: db_filename.clone(),
+            ..make_config()
+        };
+
+        // A database created before schema versioning existed
+        {
+            let conn = Connection::open(&db_filename).unwrap();
+            conn.execute_batch(MIGRATIONS[0].sql).unwrap();
+            conn.execute(
+                "INSERT INTO events (source, event_type, payload) VALUES ('web', 'search', '{}')",
+                [],
+            )
+            .unwrap();
+        }
+
+        assert!(matches!(
+            SqliteListener::new(&config),
+            Err(EventListenerError::Migration(MigrationError::Pending { current: 0, .. }))
+        ));
+
+        let status = SqliteListener::migration_status(&config).unwrap();
+        assert_eq!(status.pending.len(), MIGRATIONS.len());
+
+        let applied = SqliteListener::migrate(&config).unwrap();
+        assert_eq!(applied.len(), MIGRATIONS.len());
+
+        let listener = SqliteListener::new(&config).unwrap();
+        let events = listener.query_events(None, None, None, None).await.unwrap();
+        assert_eq!(events.len(), 1);
+        assert!(events[0].client_ip.is_none());
+
+        // Cleanup
+        drop(listener);
+        std::fs::remove_file(&db_filename).expect("Failed to delete database file");
+        let _ = std::fs::remove_file(format!("{}-wal", db_filename));
+        let _ = std::fs::remove_file(format!("{}-shm", db_filename));
+    }
 }
@@ -38,8 +38,9 @@ impl TelegramListener {
 
         match event {
             Event::Search(e) => {
+                let fallback = if e.degraded { ", sparse fallback" } else { "" };
                 let mut msg = format!(
-                    "[{src}] Search: '{}' ({}, {} results)",
+                    "[{src}] Search: '{}' ({}{fallback}, {} results)",
                     e.query, e.search_type, e.result_count
                 );
                 if let Some(ref f) = e.league_filter {
@@ -56,6 +57,23 @@ impl TelegramListener {
                 }
                 Some(msg)
             }
+            Event::Grep(e) => {
+                let mode = if e.regex { "regex" } else { "literal" };
+                let mut msg = format!(
+                    "[{src}] Grep: '{}' ({mode}, {}, {} results)",
+                    e.pattern, e.scope, e.result_count
+                );
+                if let Some(ref f) = e.league_filter {
+                    msg.push_str(&format!("\n  league: {f}"));
+                }
+                if let Some(ref f) = e.year_filter {
+                    msg.push_str(&format!("\n  year: {f}"));
+                }
+                if let Some(ref f) = e.team_filter {
+                    msg.push_str(&format!("\n  team: {f}"));
+                }
+                Some(msg)
+            }
             Event::GetAbstract(e) => {
                 Some(format!("[{src}] Get abstract: {}", e.paper))
             }
@@ -97,6 +115,10 @@ impl TelegramListener {
                 Some(format!("[{src}] PDF opened: {}", e.paper_id))
             }
             Event::Suggestion(e) => Some(format!("[{src}] Suggestion: {}", e.message)),
+            Event::ExportDataset(e) => Some(format!(
+                "[{src}] Dataset export: {} chunks as {} (schema v{})",
+                e.row_count, e.format, e.schema_version
+            )),
             // Noisy events — skip
             Event::ListLeagues(_)
             | Event::ListYears(_)
@@ -105,7 +127,8 @@ impl TelegramListener {
             | Event::HttpRequest(_)
             | Event::GetTeamInfo(_)
             | Event::GetLeagueInfo(_)
-            | Event::UpdateTeamInfo(_) => None,
+            | Event::UpdateTeamInfo(_)
+            | Event::Throttled(_) => None,
         }
     }
 }
@@ -184,6 +207,9 @@ mod tests {
             year_filter: None,
             team_filter: None,
             content_type_filter: None,
+            result_set_id: "rs".into(),
+            results: vec![],
+            degraded: false,
         });
 
         let msg = listener
@@ -208,6 +234,9 @@ mod tests {
             year_filter: None,
             team_filter: None,
             content_type_filter: None,
+            result_set_id: "rs".into(),
+            results: vec![],
+            degraded: false,
         });
 
         let msg = listener
@@ -218,6 +247,52 @@ mod tests {
         assert!(!msg.contains("\n"));
     }
 
+    #[test]
+    fn format_degraded_search() {
+        let listener = make_listener();
+        let event = Event::Search(SearchEvent {
+            query: "ball detection".into(),
+            search_type: "hybrid".into(),
+            result_count: 5,
+            league_filter: None,
+            year_filter: None,
+            team_filter: None,
+            content_type_filter: None,
+            result_set_id: "rs".into(),
+            results: vec![],
+            degraded: true,
+        });
+
+        let msg = listener
+            .format_message(&EventSource::Web, &event)
+            .unwrap();
+        assert!(msg.contains("(hybrid, sparse fallback, 5 results)"));
+    }
+
+    #[test]
+    fn format_grep_event() {
+        let listener = make_listener();
+        let event = Event::Grep(GrepEvent {
+            pattern: "nRF24L01+".into(),
+            regex: false,
+            case_sensitive: true,
+            scope: "content".into(),
+            result_count: 4,
+            league_filter: None,
+            year_filter: Some("2024".into()),
+            team_filter: None,
+        });
+
+        let msg = listener
+            .format_message(&EventSource::Mcp, &event)
+            .unwrap();
+        assert!(msg.contains("[mcp]"));
+        assert!(msg.contains("'nRF24L01+'"));
+        assert!(msg.contains("literal"));
+        assert!(msg.contains("4 results"));
+        assert!(msg.contains("year: 2024"));
+    }
+
     #[test]
     fn format_get_abstract() {
         let listener = make_listener();
@@ -240,6 +315,7 @@ mod tests {
             content_seq: 5,
             include_children: true,
             items_returned: 3,
+            result_set_id: None,
         });
 
         let msg = listener
@@ -256,6 +332,7 @@ mod tests {
         let event = Event::PaperOpen(PaperOpenEvent {
             paper_id: "my_paper".into(),
             referrer: Some("https://google.com".into()),
+            result_set_id: Some("rs".into()),
         });
 
         let msg = listener
@@ -271,6 +348,7 @@ mod tests {
         let listener = make_listener();
         let event = Event::PdfOpen(PdfOpenEvent {
             paper_id: "soccer_smallsize__2024__RoboTeam".into(),
+            result_set_id: None,
         });
 
         let msg = listener
@@ -287,6 +365,7 @@ mod tests {
         let event = Event::PaperOpen(PaperOpenEvent {
             paper_id: "my_paper".into(),
             referrer: None,
+            result_set_id: None,
         });
 
         let msg = listener
@@ -325,6 +404,28 @@ mod tests {
         assert!(msg.contains("soccer_smallsize__2024__RoboTeam"));
     }
 
+    #[test]
+    fn format_export_dataset() {
+        let listener = make_listener();
+        let event = Event::ExportDataset(ExportDatasetEvent {
+            format: "parquet".into(),
+            schema_version: 1,
+            row_count: 1234,
+            include_dense: true,
+            include_sparse: false,
+            league_filter: Some("Soccer SmallSize".into()),
+            year_filter: None,
+            team_filter: None,
+            content_type_filter: None,
+        });
+
+        let msg = listener
+            .format_message(&EventSource::Web, &event)
+            .unwrap();
+        assert!(msg.contains("Dataset export"));
+        assert!(msg.contains("1234 chunks as parquet"));
+    }
+
     #[test]
     fn skipped_events_return_none() {
         let listener = make_listener();
@@ -354,6 +455,12 @@ mod tests {
                 ip: None,
                 user_agent: "test".into(),
             }),
+            Event::Throttled(ThrottledEvent {
+                route_class: "search".into(),
+                path: "/api/search".into(),
+                ip: Some("203.0.113.7".into()),
+                retry_after_secs: 2,
+            }),
         ];
 
         for event in skipped {
@@ -44,11 +44,13 @@ export interface SearchSuggestions {
 }
 
 export interface SearchResult {
+	result_set_id: string;
 	query: string;
 	filter: Filter | null;
 	chunks: SearchResultChunk[];
 	suggestions: SearchSuggestions;
 	highlight_terms: string[];
+	degraded: boolean;
 }
 
 export interface Filter {
@@ -3,8 +3,11 @@ import { error } from '@sveltejs/kit';
 import { getTeamInfo } from '$lib/api';
 import type { RegistryEntry } from '$lib/api';
 
-export const load: PageLoad = async ({ params, fetch }) => {
+export const load: PageLoad = async ({ params, url, fetch }) => {
   const paper_lyt = params.id;
+  // Set when the paper was opened from a search result, links the open back to that search
+  const resultSetId = url.searchParams.get('rs');
+  const resultSetQuery = resultSetId ? `?result_set_id=${encodeURIComponent(resultSetId)}` : '';
 
   // Parse paper_lyt: league__year__team
   const parts = paper_lyt.split('__');
@@ -21,11 +24,12 @@ export const load: PageLoad = async ({ params, fetch }) => {
     getTeamInfo(teamPrettyName, fetch).catch((): RegistryEntry[] => [])
   ]);
 
-  fetch(`/api/papers/${encodeURIComponent(paper_lyt)}/open`, { method: 'POST' }).catch(() => {});
+  fetch(`/api/papers/${encodeURIComponent(paper_lyt)}/open${resultSetQuery}`, { method: 'POST' }).catch(() => {});
 
   return {
     rawMarkdown,
     paper_lyt,
+    resultSetQuery,
     teamEntries,
     teamPrettyName,
     leagueMachine,
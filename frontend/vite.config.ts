@@ -18,6 +18,10 @@ export default defineConfig({
 			'/pdfs': {
 				target: 'http://localhost:50000',
 				changeOrigin: true
+			},
+			'/images': {
+				target: 'http://localhost:50000',
+				changeOrigin: true
 			}
 		}
 	}
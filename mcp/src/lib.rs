@@ -0,0 +1,87 @@
+//! MCP over streamable HTTP, served by `serve`
+
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::http::{StatusCode, header};
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::{Json, Router};
+use configuration::AppConfig;
+use configuration::bootstrap::Services;
+use rmcp::transport::streamable_http_server::{
+    StreamableHttpService, session::local::LocalSessionManager,
+};
+
+mod middleware;
+mod resources;
+mod server;
+mod state;
+
+use server::AppServer;
+use state::AppState;
+
+/// Prometheus scrape endpoint
+async fn metrics_handler() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        configuration::metrics::render(),
+    )
+}
+
+/// Liveness: the process is up and serving requests
+async fn healthz_handler() -> impl IntoResponse {
+    Json(serde_json::json!({ "status": "ok" }))
+}
+
+/// Readiness: 200 when every dependency check passes, 503 with the failing ones otherwise
+async fn readyz_handler(State(services): State<Services>) -> impl IntoResponse {
+    let readiness = configuration::health::readiness(&services).await;
+    let status = if readiness.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(readiness))
+}
+
+/// The MCP frontend over the shared services, at `/mcp`, its metrics at `/metrics` and the
+/// `/healthz` and `/readyz` probes. Open to everyone; API keys are optional.
+pub fn router(services: &Services, config: &AppConfig) -> Router {
+    let rate_limiter = config
+        .rate_limit
+        .as_ref()
+        .map(|c| Arc::new(api::rate_limit::RateLimiter::new(c)));
+    let state = AppState::new(
+        services.metadata_client.clone(),
+        services.searcher.clone(),
+        services.dispatcher.clone(),
+        services.registry.clone(),
+        config.website_url.clone(),
+        rate_limiter,
+        services.api_keys.clone(),
+    );
+    let server = AppServer::new(state.clone());
+
+    let mcp_service = StreamableHttpService::new(
+        move || Ok(server.clone()),
+        LocalSessionManager::default().into(),
+        Default::default(),
+    );
+
+    Router::new()
+        .nest_service("/mcp", mcp_service)
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            middleware::rate_limit,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            state,
+            middleware::api_key_auth,
+        ))
+        .layer(axum::middleware::from_fn(middleware::classify))
+        .layer(axum::middleware::from_fn(middleware::trace_request))
+        .route("/metrics", get(metrics_handler))
+        .route("/healthz", get(healthz_handler))
+        .route("/readyz", get(readyz_handler).with_state(services.clone()))
+}
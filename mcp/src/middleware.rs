@@ -0,0 +1,229 @@
+use api::auth::{AuthError, authenticate, bearer_token, seconds_until_quota_reset};
+use api::rate_limit::{ClientId, RouteClass, client_ip};
+use axum::body::{Body, to_bytes};
+use axum::extract::ConnectInfo;
+use axum::http::{Extensions, HeaderMap, HeaderValue, Request, StatusCode, header};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use configuration::telemetry;
+use serde_json::{Value, json};
+use std::net::SocketAddr;
+use tracing::Instrument;
+
+use crate::state::AppState;
+
+/// JSON-RPC requests are small; larger bodies are rejected with 413 rather than passed on
+/// unclassified, which would let them skip the rate limit
+const MAX_INSPECTED_BODY: usize = 1024 * 1024;
+
+/// Route class of a single JSON-RPC message. Resources serve the same paper text as the
+/// content tools, so reading and listing them counts as content.
+fn message_class(message: &Value) -> Option<RouteClass> {
+    match message.get("method").and_then(Value::as_str)? {
+        "tools/call" => message
+            .pointer("/params/name")
+            .and_then(Value::as_str)
+            .map(RouteClass::for_tool),
+        "resources/read" | "resources/list" => Some(RouteClass::Content),
+        _ => None,
+    }
+}
+
+/// Route class of the first tool call or resource request in a JSON-RPC message or batch.
+/// Other methods (initialize, tools/list, notifications) are not limited.
+fn request_class(body: &[u8]) -> Option<RouteClass> {
+    let message: Value = serde_json::from_slice(body).ok()?;
+    let messages = match message {
+        Value::Array(batch) => batch,
+        single => vec![single],
+    };
+    messages.iter().find_map(message_class)
+}
+
+/// Run the request in a span and return the trace ID in `x-trace-id`. The span travels with
+/// the request, so tool calls become its children.
+pub async fn trace_request(mut request: Request<Body>, next: Next) -> Response {
+    let span = telemetry::request_span(request.method(), request.uri().path(), request.headers());
+    request.extensions_mut().insert(span.clone());
+
+    let mut response = next.run(request).instrument(span.clone()).await;
+
+    span.record("http.response.status_code", response.status().as_u16());
+    if let Some(trace_id) =
+        telemetry::trace_id(&span).and_then(|trace_id| HeaderValue::from_str(&trace_id).ok())
+    {
+        response.headers_mut().insert("x-trace-id", trace_id);
+    }
+    response
+}
+
+/// Buffer the JSON-RPC body and tag tool calls and resource requests with their `RouteClass`
+/// extension, which the auth and rate limit layers below read
+pub async fn classify(request: Request<Body>, next: Next) -> Response {
+    let (mut parts, body) = request.into_parts();
+    let Ok(bytes) = to_bytes(body, MAX_INSPECTED_BODY).await else {
+        return (StatusCode::PAYLOAD_TOO_LARGE, "Request body too large").into_response();
+    };
+    if let Some(class) = request_class(&bytes) {
+        parts.extensions.insert(class);
+    }
+    next.run(Request::from_parts(parts, Body::from(bytes)))
+        .await
+}
+
+fn request_ip(request: &Request<Body>) -> Option<String> {
+    parts_ip(request.headers(), request.extensions())
+}
+
+/// The proxy-reported client IP, or the socket address for clients connecting directly
+pub(crate) fn parts_ip(headers: &HeaderMap, extensions: &Extensions) -> Option<String> {
+    client_ip(
+        headers.get("x-forwarded-for").and_then(|v| v.to_str().ok()),
+        headers.get("x-real-ip").and_then(|v| v.to_str().ok()),
+        extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip()),
+    )
+}
+
+fn error_response(status: StatusCode, message: String) -> Response {
+    (status, axum::Json(json!({ "error": message }))).into_response()
+}
+
+/// Authenticate `Authorization: Bearer` API keys. Requests without one stay anonymous. Only
+/// tool calls and resource requests count towards a key's quota. The key reaches tool handlers
+/// through the request extensions rmcp passes along; see `AppServer::call_tool`.
+pub async fn api_key_auth(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    mut request: Request<Body>,
+    next: Next,
+) -> Response {
+    let Some(ref api_keys) = state.api_keys else {
+        return next.run(request).await;
+    };
+    let authorization = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok());
+    let Some(token) = bearer_token(authorization).map(str::to_string) else {
+        return next.run(request).await;
+    };
+    let class = request.extensions().get::<RouteClass>().copied();
+
+    match authenticate(api_keys.as_ref(), &token, class).await {
+        Ok(key) => {
+            request.extensions_mut().insert(ClientId::ApiKey(key.id));
+            next.run(request).await
+        }
+        Err(AuthError::InvalidKey) => {
+            let mut response =
+                error_response(StatusCode::UNAUTHORIZED, AuthError::InvalidKey.to_string());
+            response
+                .headers_mut()
+                .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+            response
+        }
+        Err(e @ AuthError::QuotaExceeded { .. }) => {
+            let retry_after_secs = seconds_until_quota_reset();
+            state.dispatcher.dispatch(
+                event_processing::EventSource::Mcp,
+                event_processing::Event::Throttled(event_processing::ThrottledEvent {
+                    route_class: "quota".to_string(),
+                    path: request.uri().path().to_string(),
+                    ip: request_ip(&request),
+                    retry_after_secs,
+                }),
+            );
+
+            let mut response = error_response(StatusCode::TOO_MANY_REQUESTS, e.to_string());
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(retry_after_secs));
+            response
+        }
+        Err(e) => {
+            tracing::error!("API key check failed: {}", e);
+            error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to check API key".to_string(),
+            )
+        }
+    }
+}
+
+pub async fn rate_limit(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let Some(ref limiter) = state.rate_limiter else {
+        return next.run(request).await;
+    };
+    let Some(class) = request.extensions().get::<RouteClass>().copied() else {
+        return next.run(request).await;
+    };
+
+    let ip = request_ip(&request);
+    let client = match request.extensions().get::<ClientId>() {
+        Some(client) => client.clone(),
+        None => ip.clone().map_or(ClientId::Unknown, ClientId::Ip),
+    };
+
+    let Err(retry_after) = limiter.check(class, &client) else {
+        return next.run(request).await;
+    };
+    let retry_after_secs = retry_after.as_secs_f64().ceil() as u64;
+
+    state.dispatcher.dispatch(
+        event_processing::EventSource::Mcp,
+        event_processing::Event::Throttled(event_processing::ThrottledEvent {
+            route_class: class.as_str().to_string(),
+            path: request.uri().path().to_string(),
+            ip,
+            retry_after_secs,
+        }),
+    );
+
+    let mut response = error_response(
+        StatusCode::TOO_MANY_REQUESTS,
+        format!(
+            "Too many {} requests, retry in {}s",
+            class.as_str(),
+            retry_after_secs
+        ),
+    );
+    response
+        .headers_mut()
+        .insert(header::RETRY_AFTER, HeaderValue::from(retry_after_secs));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_class() {
+        let call = br#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"search","arguments":{"query":"kicker"}}}"#;
+        assert_eq!(request_class(call), Some(RouteClass::Search));
+
+        let batch = br#"[{"jsonrpc":"2.0","method":"notifications/initialized"},{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"get_section"}}]"#;
+        assert_eq!(request_class(batch), Some(RouteClass::Content));
+
+        let list = br#"{"jsonrpc":"2.0","id":3,"method":"tools/list"}"#;
+        assert_eq!(request_class(list), None);
+        assert_eq!(request_class(b""), None);
+    }
+
+    #[test]
+    fn test_request_class_resources() {
+        let read = br#"{"jsonrpc":"2.0","id":4,"method":"resources/read","params":{"uri":"tdp://soccer_smallsize__2024__RoboTeam_Twente__0"}}"#;
+        assert_eq!(request_class(read), Some(RouteClass::Content));
+
+        let list = br#"{"jsonrpc":"2.0","id":5,"method":"resources/list"}"#;
+        assert_eq!(request_class(list), Some(RouteClass::Content));
+
+        let templates = br#"{"jsonrpc":"2.0","id":6,"method":"resources/templates/list"}"#;
+        assert_eq!(request_class(templates), None);
+    }
+}
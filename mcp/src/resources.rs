@@ -0,0 +1,291 @@
+//! Papers as MCP resources, so clients can attach a paper, its table of contents or one of its
+//! sections as context without calling tools:
+//!
+//! - `tdp://{paper_lyt}`: the full paper as markdown
+//! - `tdp://{paper_lyt}/toc`: the table of contents
+//! - `tdp://{paper_lyt}/section/{content_seq}`: a section and its subsections
+//!
+//! Only whole papers are listed. The listing has one page per league and year.
+
+use api::error::{ApiError, ErrorCode};
+use api::{get_section, get_table_of_contents, get_tdp_contents};
+use data_structures::file::TDPName;
+use event_processing::EventSource;
+use rmcp::ErrorData as McpError;
+use rmcp::model::*;
+
+use crate::server::render_section_as_markdown;
+use crate::state::AppState;
+
+const SCHEME: &str = "tdp://";
+const MIME_TYPE: &str = "text/markdown";
+
+/// A parsed `tdp://` URI
+#[derive(Debug)]
+enum TdpUri {
+    Paper(TDPName),
+    Toc(TDPName),
+    Section(TDPName, u32),
+}
+
+impl TdpUri {
+    fn parse(uri: &str) -> Result<Self, McpError> {
+        let invalid = || McpError::invalid_params(format!("Unknown resource URI '{}'", uri), None);
+        let path = uri.strip_prefix(SCHEME).ok_or_else(invalid)?;
+        let mut parts = path.split('/');
+        let paper = parts.next().ok_or_else(invalid)?;
+        // No extension may be stripped off, unlike in file names
+        if paper.contains('.') {
+            return Err(invalid());
+        }
+        let paper = TDPName::try_from(paper).map_err(|e| {
+            McpError::invalid_params(format!("Invalid paper in '{}': {}", uri, e), None)
+        })?;
+
+        match (parts.next(), parts.next(), parts.next()) {
+            (None, _, _) => Ok(TdpUri::Paper(paper)),
+            (Some("toc"), None, _) => Ok(TdpUri::Toc(paper)),
+            (Some("section"), Some(seq), None) => {
+                let seq = seq.parse().map_err(|_| invalid())?;
+                Ok(TdpUri::Section(paper, seq))
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+fn paper_uri(paper: &TDPName) -> String {
+    format!("{}{}", SCHEME, paper.get_paper_lyt())
+}
+
+fn paper_title(paper: &TDPName) -> String {
+    format!(
+        "{} — {} {}",
+        paper.team_name.name_pretty,
+        paper.league.name_pretty(),
+        paper.year
+    )
+}
+
+pub fn resource_templates() -> Vec<ResourceTemplate> {
+    let template = |uri_template: &str, name: &str, description: &str| {
+        RawResourceTemplate {
+            uri_template: uri_template.to_string(),
+            name: name.to_string(),
+            title: None,
+            description: Some(description.to_string()),
+            mime_type: Some(MIME_TYPE.to_string()),
+            icons: None,
+        }
+        .no_annotation()
+    };
+    vec![
+        template(
+            "tdp://{paper_lyt}",
+            "paper",
+            "Full markdown of a Team Description Paper, e.g. tdp://soccer_smallsize__2024__RoboTeam_Twente",
+        ),
+        template(
+            "tdp://{paper_lyt}/toc",
+            "table_of_contents",
+            "Table of contents of a paper, with the content_seq of every paragraph, table and image",
+        ),
+        template(
+            "tdp://{paper_lyt}/section/{content_seq}",
+            "section",
+            "A section of a paper with all its subsections, by content_seq from the table of contents",
+        ),
+    ]
+}
+
+/// The page of papers of one league and year. `cursor` names the page as `{league}__{year}`,
+/// the first page when absent.
+pub fn list_page(
+    mut papers: Vec<TDPName>,
+    cursor: Option<&str>,
+) -> Result<ListResourcesResult, McpError> {
+    papers.sort_by(|a, b| {
+        (a.league.name(), a.year, &a.team_name.name).cmp(&(
+            b.league.name(),
+            b.year,
+            &b.team_name.name,
+        ))
+    });
+    let page_of = |paper: &TDPName| format!("{}__{}", paper.league.name(), paper.year);
+
+    let page = match cursor {
+        Some(cursor) => cursor.to_string(),
+        None => match papers.first() {
+            Some(paper) => page_of(paper),
+            None => return Ok(ListResourcesResult::default()),
+        },
+    };
+    let start = papers
+        .iter()
+        .position(|paper| page_of(paper) == page)
+        .ok_or_else(|| McpError::invalid_params(format!("Invalid cursor '{}'", page), None))?;
+    let end = papers[start..]
+        .iter()
+        .position(|paper| page_of(paper) != page)
+        .map_or(papers.len(), |offset| start + offset);
+
+    let resources = papers[start..end]
+        .iter()
+        .map(|paper| {
+            RawResource {
+                title: Some(paper_title(paper)),
+                mime_type: Some(MIME_TYPE.to_string()),
+                ..RawResource::new(paper_uri(paper), paper.get_paper_lyt())
+            }
+            .no_annotation()
+        })
+        .collect();
+
+    Ok(ListResourcesResult {
+        meta: None,
+        next_cursor: papers.get(end).map(page_of),
+        resources,
+    })
+}
+
+pub async fn read(state: &AppState, uri: &str) -> Result<ReadResourceResult, McpError> {
+    let text = match TdpUri::parse(uri)? {
+        TdpUri::Paper(paper) => get_tdp_contents::get_tdp_contents(
+            state.metadata_client.clone(),
+            get_tdp_contents::GetTdpContentsArgs {
+                league: paper.league.name_pretty().to_string(),
+                year: paper.year,
+                team: paper.team_name.name_pretty.clone(),
+            },
+            &state.dispatcher,
+            EventSource::Mcp,
+        )
+        .await
+        .map_err(resource_error)?,
+        TdpUri::Toc(paper) => get_table_of_contents::get_table_of_contents(
+            state.metadata_client.clone(),
+            get_table_of_contents::GetTableOfContentsArgs {
+                paper: paper.get_paper_lyt(),
+            },
+            &state.dispatcher,
+            EventSource::Mcp,
+        )
+        .await
+        .map_err(resource_error)?,
+        TdpUri::Section(paper, content_seq) => {
+            let section = get_section::get_section(
+                state.metadata_client.clone(),
+                get_section::GetSectionArgs {
+                    paper: paper.get_paper_lyt(),
+                    content_seq,
+                    ..Default::default()
+                },
+                &state.dispatcher,
+                EventSource::Mcp,
+            )
+            .await
+            .map_err(resource_error)?;
+            render_section_as_markdown(&section, state.website_url.as_deref())
+        }
+    };
+
+    Ok(ReadResourceResult {
+        contents: vec![ResourceContents::TextResourceContents {
+            uri: uri.to_string(),
+            mime_type: Some(MIME_TYPE.to_string()),
+            text,
+            meta: None,
+        }],
+    })
+}
+
+fn resource_error(e: ApiError) -> McpError {
+    match e.code() {
+        ErrorCode::PaperNotFound | ErrorCode::NotFound => {
+            McpError::resource_not_found(e.to_string(), Some(e.to_json()))
+        }
+        ErrorCode::InvalidArgument | ErrorCode::InvalidFilter => {
+            McpError::invalid_params(e.to_string(), Some(e.to_json()))
+        }
+        _ => McpError::internal_error(e.to_string(), Some(e.to_json())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data_structures::file::{League, TeamName};
+
+    fn paper(league: League, year: u32, team: &str) -> TDPName {
+        TDPName::new(league, year, TeamName::from_pretty(team))
+    }
+
+    #[test]
+    fn test_parse_uri() {
+        let twente = paper(League::SoccerSmallSize, 2024, "RoboTeam Twente");
+        let parse = |uri: &str| TdpUri::parse(uri).map(|uri| format!("{:?}", uri));
+
+        assert_eq!(
+            parse("tdp://soccer_smallsize__2024__RoboTeam_Twente").unwrap(),
+            format!("{:?}", TdpUri::Paper(twente.clone()))
+        );
+        assert_eq!(
+            parse("tdp://soccer_smallsize__2024__RoboTeam_Twente/toc").unwrap(),
+            format!("{:?}", TdpUri::Toc(twente.clone()))
+        );
+        assert_eq!(
+            parse("tdp://soccer_smallsize__2024__RoboTeam_Twente/section/12").unwrap(),
+            format!("{:?}", TdpUri::Section(twente, 12))
+        );
+
+        assert!(parse("file://soccer_smallsize__2024__RoboTeam_Twente").is_err());
+        assert!(parse("tdp://soccer_smallsize__2024").is_err());
+        assert!(parse("tdp://soccer_smallsize__2024__RoboTeam_Twente.pdf").is_err());
+        assert!(parse("tdp://soccer_smallsize__2024__RoboTeam_Twente/section/x").is_err());
+        assert!(parse("tdp://soccer_smallsize__2024__RoboTeam_Twente/toc/1").is_err());
+    }
+
+    #[test]
+    fn test_list_pages_by_league_and_year() {
+        let papers = vec![
+            paper(League::SoccerSmallSize, 2024, "TIGERs Mannheim"),
+            paper(League::SoccerSmallSize, 2023, "RoboTeam Twente"),
+            paper(League::SoccerSmallSize, 2024, "RoboTeam Twente"),
+            paper(League::RescueRobot, 2024, "Hector"),
+        ];
+        let uris = |result: &ListResourcesResult| {
+            result
+                .resources
+                .iter()
+                .map(|r| r.raw.uri.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let first = list_page(papers.clone(), None).unwrap();
+        assert_eq!(uris(&first), ["tdp://rescue_robot__2024__Hector"]);
+        assert_eq!(first.next_cursor.as_deref(), Some("soccer_smallsize__2023"));
+
+        let second = list_page(papers.clone(), first.next_cursor.as_deref()).unwrap();
+        assert_eq!(
+            uris(&second),
+            ["tdp://soccer_smallsize__2023__RoboTeam_Twente"]
+        );
+
+        let third = list_page(papers.clone(), second.next_cursor.as_deref()).unwrap();
+        assert_eq!(
+            uris(&third),
+            [
+                "tdp://soccer_smallsize__2024__RoboTeam_Twente",
+                "tdp://soccer_smallsize__2024__TIGERs_Mannheim"
+            ]
+        );
+        assert_eq!(third.next_cursor, None);
+        assert_eq!(
+            third.resources[0].raw.title.as_deref(),
+            Some("RoboTeam Twente — Soccer SmallSize 2024")
+        );
+
+        assert!(list_page(papers, Some("soccer_smallsize__1999")).is_err());
+        assert!(list_page(Vec::new(), None).unwrap().resources.is_empty());
+    }
+}
@@ -1,16 +1,28 @@
+use crate::resources;
 use crate::state::AppState;
-use api::{get_abstract, get_league_info, get_paper_info, get_references, get_section, get_table_of_contents, get_tdp_contents, get_team_info, list_leagues, list_papers, list_teams, list_years, paper_filter, search, suggestion};
-use data_structures::content::ContentType;
+use api::{get_abstract, get_league_info, get_paper_info, get_references, get_section, get_table_of_contents, get_tdp_contents, get_team_info, grep, list_leagues, list_papers, list_teams, list_years, paper_filter, search, suggestion};
+use data_structures::content::{ContentType, absolute_url, figure_url, thumbnail_url};
 use data_structures::intermediate::{BreadcrumbEntry, SectionResult};
 use rmcp::handler::server::router::tool::ToolRouter;
 use rmcp::handler::server::wrapper::Parameters;
 use rmcp::model::*;
-use rmcp::{ErrorData as McpError, ServerHandler, tool, tool_handler, tool_router};
+use api::error::{ApiError, ErrorCode};
+use api::rate_limit::ClientId;
+use axum::http::request::Parts;
+use event_processing::EventContext;
+use rmcp::handler::server::tool::ToolCallContext;
+use rmcp::service::RequestContext;
+use rmcp::{ErrorData as McpError, RoleServer, ServerHandler, tool, tool_router};
 use serde::Serialize;
+use tracing::Instrument;
 
 #[derive(Serialize)]
 struct CompactSearchResult {
     query: String,
+    result_set_id: String,
+    /// Only keyword matching was used, because the embedding provider is unavailable
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    degraded: bool,
     results: Vec<CompactChunk>,
     suggestions: Vec<String>,
 }
@@ -24,6 +36,11 @@ struct CompactChunk {
     score: f32,
     text: String,
     section_path: Vec<BreadcrumbEntry>,
+    /// Absolute, so only present when `website_url` is configured
+    #[serde(skip_serializing_if = "Option::is_none")]
+    figure_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thumbnail_url: Option<String>,
 }
 
 
@@ -43,7 +60,7 @@ impl AppServer {
     }
 
     #[tool(
-        description = "Search across 2000+ RoboCup Team Description Papers (TDPs). Returns relevant text chunks with source paper metadata, content_seq, and section_path breadcrumbs for navigation. Use the content_seq with get_section to read full sections. Use keyword queries like 'trajectory planning' or 'omnidirectional drive'. Filter by league (e.g. 'Soccer SmallSize'), year, or team name to narrow results. Use search_type 'hybrid' (default) for general queries, 'sparse' for exact technical terms, 'dense' for conceptual/semantic similarity."
+        description = "Search across 2000+ RoboCup Team Description Papers (TDPs). Returns relevant text chunks with source paper metadata, content_seq, and section_path breadcrumbs for navigation. Use the content_seq with get_section to read full sections, passing along the result_set_id. Use keyword queries like 'trajectory planning' or 'omnidirectional drive'. Filter by league (e.g. 'Soccer SmallSize'), year, or team name to narrow results. Use search_type 'hybrid' (default) for general queries, 'sparse' for exact technical terms, 'dense' for conceptual/semantic similarity."
     )]
     pub async fn search(
         &self,
@@ -51,8 +68,12 @@ impl AppServer {
     ) -> Result<CallToolResult, McpError> {
         match search::search(&self.state.searcher, args, &self.state.dispatcher, event_processing::EventSource::Mcp).await {
             Ok(result) => {
+                let website_url = self.state.website_url.as_deref();
+                let resolve = |url: Option<String>| Some(absolute_url(website_url?, &url?));
                 let compact = CompactSearchResult {
                     query: result.query,
+                    result_set_id: result.result_set_id,
+                    degraded: result.degraded,
                     results: result.chunks.into_iter().map(|c| CompactChunk {
                         paper_lyt: c.paper_lyt,
                         content_seq: c.content_seq,
@@ -61,6 +82,8 @@ impl AppServer {
                         score: c.score,
                         text: c.text,
                         section_path: c.breadcrumbs,
+                        figure_url: resolve(c.figure_url),
+                        thumbnail_url: resolve(c.thumbnail_url),
                     }).collect(),
                     suggestions: result.suggestions.teams,
                 };
@@ -69,7 +92,23 @@ impl AppServer {
                     Err(e) => Err(McpError::internal_error(e.to_string(), None)),
                 }
             },
-            Err(e) => Err(McpError::internal_error(e.to_string(), None)),
+            Err(e) => tool_error(e),
+        }
+    }
+
+    #[tool(
+        description = "Find exact occurrences of a string or regular expression in the raw text of all TDPs, e.g. part numbers ('STM32F7', 'nRF24L01+') or names ('ER-Force') that semantic search mangles. Returns paper_lyt, content_seq, character offsets and a short context snippet per match. Set regex=true for patterns like 'STM32F[47]\\d*'. Use get_section with the returned content_seq to read the surrounding section."
+    )]
+    pub async fn grep(
+        &self,
+        Parameters(args): Parameters<grep::GrepArgs>,
+    ) -> Result<CallToolResult, McpError> {
+        match grep::grep(self.state.metadata_client.clone(), args, &self.state.dispatcher, event_processing::EventSource::Mcp).await {
+            Ok(matches) => match serde_json::to_string_pretty(&matches) {
+                Ok(response) => Ok(CallToolResult::success(vec![Content::text(response)])),
+                Err(e) => Err(McpError::internal_error(e.to_string(), None)),
+            },
+            Err(e) => tool_error(e),
         }
     }
 
@@ -88,7 +127,7 @@ impl AppServer {
                     Err(e) => Err(McpError::internal_error(e.to_string(), None)),
                 }
             }
-            Err(e) => Err(McpError::internal_error(e.to_string(), None)),
+            Err(e) => tool_error(e),
         }
     }
 
@@ -104,7 +143,7 @@ impl AppServer {
                     Err(e) => Err(McpError::internal_error(e.to_string(), None)),
                 }
             }
-            Err(e) => Err(McpError::internal_error(e.to_string(), None)),
+            Err(e) => tool_error(e),
         }
     }
 
@@ -120,7 +159,7 @@ impl AppServer {
                 Ok(response) => Ok(CallToolResult::success(vec![Content::text(response)])),
                 Err(e) => Err(McpError::internal_error(e.to_string(), None)),
             },
-            Err(e) => Err(McpError::internal_error(e.to_string(), None)),
+            Err(e) => tool_error(e),
         }
     }
 
@@ -139,7 +178,7 @@ impl AppServer {
                     Err(e) => Err(McpError::internal_error(e.to_string(), None)),
                 }
             },
-            Err(e) => Err(McpError::internal_error(e.to_string(), None)),
+            Err(e) => tool_error(e),
         }
     }
 
@@ -152,7 +191,7 @@ impl AppServer {
     ) -> Result<CallToolResult, McpError> {
         match get_tdp_contents::get_tdp_contents(self.state.metadata_client.clone(), args, &self.state.dispatcher, event_processing::EventSource::Mcp).await {
             Ok(markdown) => Ok(CallToolResult::success(vec![Content::text(markdown)])),
-            Err(e) => Err(McpError::internal_error(e.to_string(), None)),
+            Err(e) => tool_error(e),
         }
     }
 
@@ -165,7 +204,7 @@ impl AppServer {
     ) -> Result<CallToolResult, McpError> {
         match get_table_of_contents::get_table_of_contents(self.state.metadata_client.clone(), args, &self.state.dispatcher, event_processing::EventSource::Mcp).await {
             Ok(result) => Ok(CallToolResult::success(vec![Content::text(result)])),
-            Err(e) => Err(McpError::internal_error(e.to_string(), None)),
+            Err(e) => tool_error(e),
         }
     }
 
@@ -178,10 +217,10 @@ impl AppServer {
     ) -> Result<CallToolResult, McpError> {
         match get_section::get_section(self.state.metadata_client.clone(), args, &self.state.dispatcher, event_processing::EventSource::Mcp).await {
             Ok(result) => {
-                let markdown = render_section_as_markdown(&result);
+                let markdown = render_section_as_markdown(&result, self.state.website_url.as_deref());
                 Ok(CallToolResult::success(vec![Content::text(markdown)]))
             },
-            Err(e) => Err(McpError::internal_error(e.to_string(), None)),
+            Err(e) => tool_error(e),
         }
     }
 
@@ -195,7 +234,7 @@ impl AppServer {
     ) -> Result<CallToolResult, McpError> {
         match get_abstract::get_abstract(self.state.metadata_client.clone(), args, &self.state.dispatcher, event_processing::EventSource::Mcp).await {
             Ok(result) => Ok(CallToolResult::success(vec![Content::text(result)])),
-            Err(e) => Err(McpError::internal_error(e.to_string(), None)),
+            Err(e) => tool_error(e),
         }
     }
 
@@ -219,7 +258,7 @@ impl AppServer {
                 };
                 Ok(CallToolResult::success(vec![Content::text(text)]))
             },
-            Err(e) => Err(McpError::internal_error(e.to_string(), None)),
+            Err(e) => tool_error(e),
         }
     }
 
@@ -254,7 +293,7 @@ impl AppServer {
 
                 Ok(CallToolResult::success(vec![Content::text(lines.join("\n"))]))
             },
-            Err(e) => Err(McpError::internal_error(e.to_string(), None)),
+            Err(e) => tool_error(e),
         }
     }
 
@@ -285,7 +324,7 @@ impl AppServer {
                     Ok(CallToolResult::success(vec![Content::text(text)]))
                 }
             },
-            Err(e) => Err(McpError::internal_error(e.to_string(), None)),
+            Err(e) => tool_error(e),
         }
     }
 
@@ -316,7 +355,7 @@ impl AppServer {
                     Ok(CallToolResult::success(vec![Content::text(text)]))
                 }
             },
-            Err(e) => Err(McpError::internal_error(e.to_string(), None)),
+            Err(e) => tool_error(e),
         }
     }
 
@@ -335,12 +374,41 @@ impl AppServer {
         .await
         {
             Ok(response) => Ok(CallToolResult::success(vec![Content::text(response)])),
-            Err(e) => Err(McpError::internal_error(e.to_string(), None)),
+            Err(e) => tool_error(e),
         }
     }
 }
 
-fn render_section_as_markdown(result: &SectionResult) -> String {
+/// Report a failed call as a tool error the model can read and act on, with the error code and
+/// offending argument as structured content. Only internal errors are protocol errors.
+fn tool_error(e: ApiError) -> Result<CallToolResult, McpError> {
+    if e.code() == ErrorCode::Internal {
+        return Err(McpError::internal_error(e.to_string(), Some(e.to_json())));
+    }
+    let mut result = CallToolResult::error(vec![Content::text(e.to_string())]);
+    result.structured_content = Some(e.to_json());
+    Ok(result)
+}
+
+/// Events of a request carry the caller's API key and IP
+fn event_context(context: &RequestContext<RoleServer>) -> EventContext {
+    let parts = context.extensions.get::<Parts>();
+    let api_key_id = parts
+        .and_then(|parts| parts.extensions.get::<ClientId>())
+        .and_then(|client| match client {
+            ClientId::ApiKey(id) => Some(id.clone()),
+            _ => None,
+        });
+    EventContext {
+        api_key_id,
+        client_ip: parts
+            .and_then(|parts| crate::middleware::parts_ip(&parts.headers, &parts.extensions)),
+        ..Default::default()
+    }
+}
+
+/// Figures get absolute URLs under `website_url` when it is configured
+pub(crate) fn render_section_as_markdown(result: &SectionResult, website_url: Option<&str>) -> String {
     let mut out = String::new();
 
     // Breadcrumb trail
@@ -374,7 +442,15 @@ fn render_section_as_markdown(result: &SectionResult) -> String {
             ContentType::Image => {
                 out.push_str(&format!("**{}**\n", item.title));
                 if let Some(path) = &item.image_path {
-                    out.push_str(&format!("Image: {}\n\n", path));
+                    out.push_str(&format!("Image: {}\n", path));
+                    if let Some(website_url) = website_url {
+                        out.push_str(&format!(
+                            "Figure: {}\nThumbnail: {}\n",
+                            absolute_url(website_url, &figure_url(&result.paper_lyt, path)),
+                            absolute_url(website_url, &thumbnail_url(&result.paper_lyt, path))
+                        ));
+                    }
+                    out.push('\n');
                 } else {
                     out.push('\n');
                 }
@@ -385,8 +461,98 @@ fn render_section_as_markdown(result: &SectionResult) -> String {
     out.trim_end().to_string()
 }
 
-#[tool_handler]
 impl ServerHandler for AppServer {
+    /// Same as the `#[tool_handler]` expansion, but runs the tool inside the event context of
+    /// the HTTP request.
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParams,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let request_span = context
+            .extensions
+            .get::<Parts>()
+            .and_then(|parts| parts.extensions.get::<tracing::Span>())
+            .cloned()
+            .unwrap_or_else(tracing::Span::current);
+
+        let tool = request.name.to_string();
+        let span = tracing::info_span!(
+            parent: &request_span,
+            "tool_call",
+            otel.name = %format!("tools/call {}", tool),
+            mcp.tool = %tool,
+        );
+        let start = std::time::Instant::now();
+        let event_context = event_context(&context);
+        let tcc = ToolCallContext::new(self, request, context);
+        let result = event_context
+            .scope(self.tool_router.call(tcc))
+            .instrument(span)
+            .await;
+
+        let outcome = match &result {
+            Ok(result) if result.is_error != Some(true) => "ok",
+            _ => "error",
+        };
+        metrics::counter!("tdp_mcp_tool_calls_total", "tool" => tool.clone(), "outcome" => outcome)
+            .increment(1);
+        metrics::histogram!("tdp_mcp_tool_duration_seconds", "tool" => tool)
+            .record(start.elapsed().as_secs_f64());
+        result
+    }
+
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, McpError> {
+        Ok(ListToolsResult {
+            tools: self.tool_router.list_all(),
+            meta: None,
+            next_cursor: None,
+        })
+    }
+
+    fn get_tool(&self, name: &str) -> Option<Tool> {
+        self.tool_router.get(name).cloned()
+    }
+
+    async fn list_resources(
+        &self,
+        request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, McpError> {
+        let papers = self
+            .state
+            .metadata_client
+            .load_tdps()
+            .await
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+        let cursor = request.as_ref().and_then(|r| r.cursor.as_deref());
+        resources::list_page(papers, cursor)
+    }
+
+    async fn list_resource_templates(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourceTemplatesResult, McpError> {
+        Ok(ListResourceTemplatesResult::with_all_items(
+            resources::resource_templates(),
+        ))
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParams,
+        context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, McpError> {
+        event_context(&context)
+            .scope(resources::read(&self.state, &request.uri))
+            .await
+    }
+
     fn get_info(&self) -> ServerInfo {
         let paper_link_rule = match &self.state.website_url {
             Some(url) => {
@@ -410,7 +576,7 @@ A **paper_lyt** (League-Year-Team) is the unique paper identifier used across al
 1. Start broad: search without league filters to find relevant work across all leagues
 2. Narrow down: use league, year, or team filters to focus results
 3. When search results reference specific teams, call get_team_info for those teams — users asking about designs, implementations, hardware, or software almost always benefit from direct links to GitHub repos and team websites. Don't wait for the user to ask — include team URLs proactively.
-4. Read full papers: use get_tdp_contents for papers with promising search chunks
+4. Read full papers: use get_tdp_contents for papers with promising search chunks. The same papers are resources: `tdp://{{paper_lyt}}`, `tdp://{{paper_lyt}}/toc` and `tdp://{{paper_lyt}}/section/{{content_seq}}`
 5. Cross-reference: if a topic appears in one league, check if other leagues address it differently
 
 ## Response rules
@@ -428,7 +594,10 @@ A **paper_lyt** (League-Year-Team) is the unique paper identifier used across al
 
         ServerInfo {
             protocol_version: ProtocolVersion::V_2024_11_05,
-            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            capabilities: ServerCapabilities::builder()
+                .enable_tools()
+                .enable_resources()
+                .build(),
             server_info: Implementation::from_build_env(),
             instructions: Some(instructions),
         }
@@ -1,3 +1,5 @@
+use api::rate_limit::RateLimiter;
+use data_access::api_keys::ApiKeyClient;
 use data_access::metadata::MetadataClient;
 use data_access::registry::RegistryClient;
 use data_processing::search::Searcher;
@@ -11,6 +13,8 @@ pub struct AppState {
     pub dispatcher: Arc<EventDispatcher>,
     pub registry: Option<Arc<dyn RegistryClient + Send + Sync>>,
     pub website_url: Option<String>,
+    pub rate_limiter: Option<Arc<RateLimiter>>,
+    pub api_keys: Option<Arc<dyn ApiKeyClient + Send + Sync>>,
 }
 
 impl AppState {
@@ -20,6 +24,8 @@ impl AppState {
         dispatcher: Arc<EventDispatcher>,
         registry: Option<Arc<dyn RegistryClient + Send + Sync>>,
         website_url: Option<String>,
+        rate_limiter: Option<Arc<RateLimiter>>,
+        api_keys: Option<Arc<dyn ApiKeyClient + Send + Sync>>,
     ) -> Self {
         Self {
             metadata_client,
@@ -27,6 +33,8 @@ impl AppState {
             dispatcher,
             registry,
             website_url,
+            rate_limiter,
+            api_keys,
         }
     }
 }
@@ -1,17 +1,24 @@
 # https://qdrant.tech/documentation/concepts/collections/
 
-# Check if collection 'chunk' exists
-EXISTS_OUTPUT=$(curl -X GET http://localhost:6333/collections/chunk/exists --no-progress-meter)
+# Resolve the 'chunk' alias to the live versioned collection, falling back to the legacy 'chunk' collection
+ALIASES_OUTPUT=$(curl -X GET http://localhost:6333/aliases --no-progress-meter)
+COLLECTION=$(echo $ALIASES_OUTPUT | jq -r '.result.aliases[] | select(.alias_name == "chunk") | .collection_name')
+if [ -z "$COLLECTION" ]; then
+    COLLECTION=chunk
+fi
+
+# Check if the collection exists
+EXISTS_OUTPUT=$(curl -X GET http://localhost:6333/collections/$COLLECTION/exists --no-progress-meter)
 EXISTS=$(echo $EXISTS_OUTPUT | jq -r '.result.exists')
 if [ "$EXISTS" != "true" ]; then
-    echo "Collection 'chunk' does not exist. Please create it before running this script."
+    echo "Collection '$COLLECTION' does not exist. Please create it before running this script."
     exit 1
 fi
 
 # Get collection stats
-STATS_OUTPUT=$(curl -X GET http://localhost:6333/collections/chunk/ --no-progress-meter)
+STATS_OUTPUT=$(curl -X GET http://localhost:6333/collections/$COLLECTION/ --no-progress-meter)
 
 NCHUNKS=$(echo $STATS_OUTPUT | jq -r '.result.points_count')
 VECSIZE=$(echo $STATS_OUTPUT | jq -r '.result.config.params.vectors.dense.size')
 
-echo "Collection 'chunk' has $NCHUNKS chunks with vector size $VECSIZE."
\ No newline at end of file
+echo "Collection '$COLLECTION' has $NCHUNKS chunks with vector size $VECSIZE."
\ No newline at end of file
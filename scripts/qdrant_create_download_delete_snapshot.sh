@@ -7,27 +7,35 @@
 # Optional output filename parameter
 OUTPUT_FILE="$1"
 
-# Check if collection 'chunk' exists
-EXISTS_OUTPUT=$(curl -X GET http://localhost:6333/collections/chunk/exists --no-progress-meter)
+# Resolve the 'chunk' alias to the live versioned collection (e.g. 'chunk_v3').
+# Without an alias, fall back to the legacy unversioned 'chunk' collection.
+ALIASES_OUTPUT=$(curl -X GET http://localhost:6333/aliases --no-progress-meter)
+COLLECTION=$(echo $ALIASES_OUTPUT | jq -r '.result.aliases[] | select(.alias_name == "chunk") | .collection_name')
+if [ -z "$COLLECTION" ]; then
+    COLLECTION=chunk
+fi
+
+# Check if the collection exists
+EXISTS_OUTPUT=$(curl -X GET http://localhost:6333/collections/$COLLECTION/exists --no-progress-meter)
 EXISTS=$(echo $EXISTS_OUTPUT | jq -r '.result.exists')
 if [ "$EXISTS" != "true" ]; then
-    echo "Collection 'chunk' does not exist. Please create it before running this script."
+    echo "Collection '$COLLECTION' does not exist. Please create it before running this script."
     exit 1
 fi
 
 # Get collection stats
-STATS_OUTPUT=$(curl -X GET http://localhost:6333/collections/chunk/ --no-progress-meter)
+STATS_OUTPUT=$(curl -X GET http://localhost:6333/collections/$COLLECTION/ --no-progress-meter)
 echo $STATS_OUTPUT
 
 NCHUNKS=$(echo $STATS_OUTPUT | jq -r '.result.points_count')
 VECSIZE=$(echo $STATS_OUTPUT | jq -r '.result.config.params.vectors.dense.size')
 
-echo "Collection 'chunk' has $NCHUNKS chunks with vector size $VECSIZE."
+echo "Collection '$COLLECTION' has $NCHUNKS chunks with vector size $VECSIZE."
 
 # Create snapshot
-echo "Creating snapshot for collection 'chunk'..."
+echo "Creating snapshot for collection '$COLLECTION'..."
 
-CREATE_OUTPUT=$(curl -X POST http://localhost:6333/collections/chunk/snapshots --no-progress-meter)
+CREATE_OUTPUT=$(curl -X POST http://localhost:6333/collections/$COLLECTION/snapshots --no-progress-meter)
 FILENAME=$(echo $CREATE_OUTPUT | jq -r '.result.name')
 
 # Determine output filename
@@ -38,11 +46,14 @@ fi
 # Download snapshot
 echo "Downloading snapshot '$FILENAME' to '$OUTPUT_FILE'..."
 
-CMD_DOWNLOAD=$(curl -X GET http://localhost:6333/collections/chunk/snapshots/${FILENAME} --output "$OUTPUT_FILE" --no-progress-meter)
+CMD_DOWNLOAD=$(curl -X GET http://localhost:6333/collections/$COLLECTION/snapshots/${FILENAME} --output "$OUTPUT_FILE" --no-progress-meter)
 
 echo "Snapshot saved to: $OUTPUT_FILE"
 
+# Record the collection name so the snapshot is restored under the same version
+echo "$COLLECTION" > "$OUTPUT_FILE.collection"
+
 # Delete snapshot
 echo "Deleting snapshot '$FILENAME' from Qdrant..."
 
-CMD_DELETE=$(curl -X DELETE http://localhost:6333/collections/chunk/snapshots/${FILENAME} --no-progress-meter)
\ No newline at end of file
+CMD_DELETE=$(curl -X DELETE http://localhost:6333/collections/$COLLECTION/snapshots/${FILENAME} --no-progress-meter)
\ No newline at end of file
@@ -7,7 +7,7 @@ echo "Step 1: Stopping Docker Compose services..."
 docker compose down
 
 echo "Step 2: Removing old data..."
-rm -f data/metadata.db qdrant.snapshot
+rm -f data/metadata.db qdrant.snapshot qdrant.snapshot.collection
 
 echo "Step 3: Starting standalone Qdrant..."
 make qdrant-restart
@@ -0,0 +1,142 @@
+use std::net::SocketAddr;
+
+use configuration::{AppConfig, Frontend};
+use tokio::task::JoinSet;
+
+const USAGE: &str =
+    "Usage: serve [--config <path>] [--frontends web,mcp] [--web-addr <addr>] [--mcp-addr <addr>]";
+
+/// Command line flags. Anything not given falls back to the `[server]` config section.
+#[derive(Debug, Default, PartialEq)]
+struct Args {
+    config: Option<String>,
+    frontends: Option<Vec<Frontend>>,
+    web_addr: Option<String>,
+    mcp_addr: Option<String>,
+}
+
+impl Args {
+    fn parse(args: impl IntoIterator<Item = String>) -> anyhow::Result<Self> {
+        let mut parsed = Args::default();
+        let mut args = args.into_iter();
+        while let Some(flag) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| anyhow::anyhow!("Missing value for {}. {}", flag, USAGE))
+            };
+            match flag.as_str() {
+                "--config" => parsed.config = Some(value()?),
+                "--web-addr" => parsed.web_addr = Some(value()?),
+                "--mcp-addr" => parsed.mcp_addr = Some(value()?),
+                "--frontends" => {
+                    let frontends = value()?
+                        .split(',')
+                        .map(|f| Frontend::try_from(f.trim()))
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(|e| anyhow::anyhow!("{}. {}", e, USAGE))?;
+                    parsed.frontends = Some(frontends);
+                }
+                "--help" | "-h" => anyhow::bail!("{}", USAGE),
+                other => anyhow::bail!("Unknown argument '{}'. {}", other, USAGE),
+            }
+        }
+        Ok(parsed)
+    }
+}
+
+async fn bind(addr: &str) -> anyhow::Result<tokio::net::TcpListener> {
+    let addr: SocketAddr = addr
+        .parse()
+        .map_err(|e| anyhow::anyhow!("Invalid bind address '{}': {}", addr, e))?;
+    Ok(tokio::net::TcpListener::bind(addr).await?)
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let args = Args::parse(std::env::args().skip(1))?;
+
+    println!("🚀 Server initializing...");
+
+    let config_path = args.config.as_deref().unwrap_or("config.toml");
+    let config = AppConfig::load_from_file(config_path)
+        .map_err(|e| anyhow::anyhow!("Failed to load config from {}: {}", config_path, e))?;
+    // Flushes the exported spans when `main` returns
+    let _telemetry = configuration::telemetry::init(config.tracing.as_ref())?;
+    let server_config = config.server.clone().unwrap_or_default();
+    let frontends = args.frontends.unwrap_or_else(|| server_config.frontends());
+    if frontends.is_empty() {
+        anyhow::bail!("No frontends to serve. {}", USAGE);
+    }
+
+    println!("Config loaded.");
+
+    // Built once, so the frontends share clients, the IDF and the searcher's caches
+    let services = configuration::bootstrap::bootstrap(&config).await?;
+
+    let mut servers = JoinSet::new();
+    if frontends.contains(&Frontend::Web) {
+        let addr = args.web_addr.as_deref().unwrap_or(server_config.web_addr());
+        let listener = bind(addr).await?;
+        let router = web::router(&services, &config);
+        println!("🌐 Web Server running on http://{}", addr);
+        servers.spawn(async move {
+            axum::serve(
+                listener,
+                router.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .await
+        });
+    }
+    if frontends.contains(&Frontend::Mcp) {
+        let addr = args.mcp_addr.as_deref().unwrap_or(server_config.mcp_addr());
+        let listener = bind(addr).await?;
+        let router = mcp::router(&services, &config);
+        println!("🔎 MCP Server (open) running on http://{}/mcp", addr);
+        servers.spawn(async move {
+            axum::serve(
+                listener,
+                router.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .await
+        });
+    }
+
+    tokio::select! {
+        Some(result) = servers.join_next() => result??,
+        _ = tokio::signal::ctrl_c() => {},
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> anyhow::Result<Args> {
+        Args::parse(args.iter().map(|a| a.to_string()))
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(parse(&[]).unwrap(), Args::default());
+
+        let args = parse(&[
+            "--config",
+            "prod.toml",
+            "--frontends",
+            "web, mcp",
+            "--web-addr",
+            "127.0.0.1:8080",
+        ])
+        .unwrap();
+        assert_eq!(args.config.as_deref(), Some("prod.toml"));
+        assert_eq!(args.frontends, Some(vec![Frontend::Web, Frontend::Mcp]));
+        assert_eq!(args.web_addr.as_deref(), Some("127.0.0.1:8080"));
+        assert_eq!(args.mcp_addr, None);
+
+        assert!(parse(&["--frontends", "grpc"]).is_err());
+        assert!(parse(&["--config"]).is_err());
+        assert!(parse(&["--port", "1"]).is_err());
+    }
+}
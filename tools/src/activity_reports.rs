@@ -0,0 +1,522 @@
+//! Search analytics over the activity log: zero and low-result queries, reformulation chains,
+//! filter usage and per-query click-through rate. Every report is a [`Table`] that prints as
+//! aligned columns or as CSV.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use event_processing::listeners::sqlite::ActivityEvent;
+use serde::Deserialize;
+
+use crate::click_labels::{follow_up_grade, normalize_query};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Table {
+    pub headers: Vec<&'static str>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// Render the first `limit` rows as aligned columns
+    pub fn render(&self, limit: usize) -> String {
+        let rows = &self.rows[..self.rows.len().min(limit)];
+        let mut widths: Vec<usize> = self.headers.iter().map(|h| h.chars().count()).collect();
+        for row in rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let line = |cells: Vec<&str>| {
+            cells
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:<width$}", cell, width = *width))
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        };
+
+        let mut out = line(self.headers.clone());
+        out.push('\n');
+        for row in rows {
+            out.push_str(&line(row.iter().map(String::as_str).collect()));
+            out.push('\n');
+        }
+        out
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut out = self.headers.join(",");
+        out.push('\n');
+        for row in &self.rows {
+            let cells: Vec<String> = row.iter().map(|c| csv_escape(c)).collect();
+            out.push_str(&cells.join(","));
+            out.push('\n');
+        }
+        out
+    }
+}
+
+fn csv_escape(cell: &str) -> String {
+    if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+#[derive(Deserialize)]
+struct SearchPayload {
+    query: String,
+    result_count: usize,
+    #[serde(default)]
+    league_filter: Option<String>,
+    #[serde(default)]
+    team_filter: Option<String>,
+    #[serde(default)]
+    result_set_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct FollowUpPayload {
+    #[serde(default)]
+    result_set_id: Option<String>,
+}
+
+/// A logged search, with its query normalized for grouping
+#[derive(Clone, Debug)]
+pub struct SearchRecord {
+    pub timestamp: DateTime<Utc>,
+    pub source: String,
+    /// Who searched: the API key, else the client IP. Unknown for events logged before
+    /// clients were recorded.
+    pub client: Option<String>,
+    pub query: String,
+    pub result_count: usize,
+    pub league_filter: Option<String>,
+    pub team_filter: Option<String>,
+    pub result_set_id: Option<String>,
+}
+
+/// Parse the search events, oldest first. Events with unreadable payloads or timestamps are
+/// skipped.
+pub fn search_records(events: &[ActivityEvent]) -> Vec<SearchRecord> {
+    let mut records: Vec<SearchRecord> = events
+        .iter()
+        .filter(|e| e.event_type == "search")
+        .filter_map(|e| {
+            let payload: SearchPayload = serde_json::from_str(e.payload.as_deref()?).ok()?;
+            let timestamp = DateTime::parse_from_rfc3339(&e.timestamp).ok()?;
+            Some(SearchRecord {
+                timestamp: timestamp.with_timezone(&Utc),
+                source: e.source.clone(),
+                client: e
+                    .api_key_id
+                    .as_ref()
+                    .map(|id| format!("key:{id}"))
+                    .or_else(|| e.client_ip.as_ref().map(|ip| format!("ip:{ip}"))),
+                query: normalize_query(&payload.query),
+                result_count: payload.result_count,
+                league_filter: payload.league_filter,
+                team_filter: payload.team_filter,
+                result_set_id: payload.result_set_id,
+            })
+        })
+        .collect();
+    records.sort_by_key(|r| r.timestamp);
+    records
+}
+
+/// Queries whose searches returned at most `max_results` results on average, most frequent
+/// first. With `max_results = 0` these are the zero-result queries.
+pub fn low_result_queries(records: &[SearchRecord], max_results: usize) -> Table {
+    let mut by_query: BTreeMap<&str, (usize, usize, usize)> = BTreeMap::new();
+    for r in records {
+        let (searches, total, zero) = by_query.entry(&r.query).or_default();
+        *searches += 1;
+        *total += r.result_count;
+        if r.result_count == 0 {
+            *zero += 1;
+        }
+    }
+
+    let mut rows: Vec<(&str, usize, f64, usize)> = by_query
+        .into_iter()
+        .map(|(q, (searches, total, zero))| (q, searches, total as f64 / searches as f64, zero))
+        .filter(|(_, _, mean, _)| *mean <= max_results as f64)
+        .collect();
+    rows.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+    Table {
+        headers: vec!["query", "searches", "avg_results", "zero_result_searches"],
+        rows: rows
+            .into_iter()
+            .map(|(q, searches, mean, zero)| {
+                vec![
+                    q.to_string(),
+                    searches.to_string(),
+                    format!("{:.1}", mean),
+                    zero.to_string(),
+                ]
+            })
+            .collect(),
+    }
+}
+
+/// Two queries look like a rewrite of each other when they share a term or are spelled
+/// almost the same ("bang bang" and "bang-bang").
+fn is_rewrite(previous: &str, next: &str) -> bool {
+    let terms = |q: &str| -> HashSet<String> {
+        q.split(|c: char| !c.is_alphanumeric())
+            .filter(|t| !t.is_empty())
+            .map(str::to_string)
+            .collect()
+    };
+    !terms(previous).is_disjoint(&terms(next))
+        || strsim::normalized_levenshtein(previous, next) >= 0.7
+}
+
+/// Chains of searches from the same client and source where each query rewrites the previous
+/// one within `window_secs` seconds, grouped by the sequence of queries, most frequent first.
+/// Repeating the same query, e.g. when paging, continues a chain without adding a step.
+/// Searches of unknown clients cannot be told apart and are left out.
+pub fn reformulation_chains(records: &[SearchRecord], window_secs: i64) -> Table {
+    let mut chains: HashMap<Vec<&str>, (usize, Vec<usize>)> = HashMap::new();
+    let mut open: HashMap<(&str, &str), (Vec<&SearchRecord>, DateTime<Utc>)> = HashMap::new();
+
+    for r in records {
+        let Some(client) = r.client.as_deref() else {
+            continue;
+        };
+        let key = (r.source.as_str(), client);
+        let continues = open.get(&key).is_some_and(|(chain, last)| {
+            let previous = chain.last().expect("chains are never empty");
+            (r.timestamp - *last).num_seconds() <= window_secs
+                && (previous.query == r.query || is_rewrite(&previous.query, &r.query))
+        });
+
+        if continues {
+            let (chain, last) = open.get_mut(&key).expect("checked above");
+            if chain.last().is_some_and(|p| p.query != r.query) {
+                chain.push(r);
+            }
+            *last = r.timestamp;
+        } else if let Some((chain, _)) = open.insert(key, (vec![r], r.timestamp)) {
+            close_chain(chain, &mut chains);
+        }
+    }
+    for (chain, _) in open.into_values() {
+        close_chain(chain, &mut chains);
+    }
+
+    let mut rows: Vec<_> = chains.into_iter().collect();
+    rows.sort_by(|a, b| b.1.0.cmp(&a.1.0).then_with(|| a.0.cmp(&b.0)));
+
+    Table {
+        headers: vec!["chain", "steps", "occurrences", "result_counts"],
+        rows: rows
+            .into_iter()
+            .map(|(queries, (count, results))| {
+                vec![
+                    queries.join(" -> "),
+                    queries.len().to_string(),
+                    count.to_string(),
+                    results
+                        .iter()
+                        .map(usize::to_string)
+                        .collect::<Vec<_>>()
+                        .join(" -> "),
+                ]
+            })
+            .collect(),
+    }
+}
+
+/// Record a finished chain, keeping the result counts of its first occurrence
+fn close_chain<'a>(
+    chain: Vec<&'a SearchRecord>,
+    chains: &mut HashMap<Vec<&'a str>, (usize, Vec<usize>)>,
+) {
+    if chain.len() < 2 {
+        return;
+    }
+    let key: Vec<&str> = chain.iter().map(|r| r.query.as_str()).collect();
+    let (count, results) = chains.entry(key).or_default();
+    *count += 1;
+    if results.is_empty() {
+        *results = chain.iter().map(|r| r.result_count).collect();
+    }
+}
+
+/// How often each league and team is used as a search filter, most used first
+pub fn filter_usage(records: &[SearchRecord]) -> Table {
+    let mut counts: BTreeMap<(&'static str, String), usize> = BTreeMap::new();
+    for r in records {
+        for (kind, filter) in [("league", &r.league_filter), ("team", &r.team_filter)] {
+            let values = filter.iter().flat_map(|f| f.split(','));
+            for value in values.map(str::trim).filter(|v| !v.is_empty()) {
+                *counts.entry((kind, value.to_string())).or_default() += 1;
+            }
+        }
+    }
+
+    let mut rows: Vec<_> = counts.into_iter().collect();
+    rows.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    Table {
+        headers: vec!["filter", "value", "searches"],
+        rows: rows
+            .into_iter()
+            .map(|((kind, value), count)| vec![kind.to_string(), value, count.to_string()])
+            .collect(),
+    }
+}
+
+/// Click-through rate per query: the share of its searches that led to at least one paper
+/// open, PDF open or section read. Only searches logged with a result-set ID count, and only
+/// queries with at least `min_searches` of those are listed, lowest click-through rate first.
+pub fn query_click_through(
+    records: &[SearchRecord],
+    events: &[ActivityEvent],
+    min_searches: usize,
+) -> Table {
+    let clicked: HashSet<String> = events
+        .iter()
+        .filter(|e| follow_up_grade(&e.event_type).is_some())
+        .filter_map(|e| {
+            serde_json::from_str::<FollowUpPayload>(e.payload.as_deref()?)
+                .ok()?
+                .result_set_id
+        })
+        .collect();
+
+    let mut by_query: BTreeMap<&str, (usize, usize)> = BTreeMap::new();
+    for r in records {
+        let Some(ref id) = r.result_set_id else {
+            continue;
+        };
+        let (searches, clicks) = by_query.entry(&r.query).or_default();
+        *searches += 1;
+        if clicked.contains(id) {
+            *clicks += 1;
+        }
+    }
+
+    let mut rows: Vec<(&str, usize, usize, f64)> = by_query
+        .into_iter()
+        .filter(|(_, (searches, _))| *searches >= min_searches)
+        .map(|(q, (searches, clicks))| (q, searches, clicks, clicks as f64 / searches as f64))
+        .collect();
+    rows.sort_by(|a, b| {
+        a.3.total_cmp(&b.3)
+            .then_with(|| b.1.cmp(&a.1))
+            .then_with(|| a.0.cmp(b.0))
+    });
+
+    Table {
+        headers: vec!["query", "searches", "clicked", "ctr"],
+        rows: rows
+            .into_iter()
+            .map(|(q, searches, clicks, ctr)| {
+                vec![
+                    q.to_string(),
+                    searches.to_string(),
+                    clicks.to_string(),
+                    format!("{:.2}", ctr),
+                ]
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn event(
+        timestamp: &str,
+        source: &str,
+        event_type: &str,
+        payload: serde_json::Value,
+    ) -> ActivityEvent {
+        ActivityEvent {
+            id: 0,
+            timestamp: timestamp.to_string(),
+            source: source.to_string(),
+            event_type: event_type.to_string(),
+            payload: Some(payload.to_string()),
+            api_key_id: None,
+            client_class: None,
+            client_ip: Some("203.0.113.7".to_string()),
+        }
+    }
+
+    fn search(timestamp: &str, source: &str, query: &str, result_count: usize) -> ActivityEvent {
+        search_by(timestamp, source, "203.0.113.7", query, result_count)
+    }
+
+    fn search_by(
+        timestamp: &str,
+        source: &str,
+        ip: &str,
+        query: &str,
+        result_count: usize,
+    ) -> ActivityEvent {
+        let mut event = event(
+            timestamp,
+            source,
+            "search",
+            json!({
+                "type": "search",
+                "query": query,
+                "search_type": "hybrid",
+                "result_count": result_count,
+                "league_filter": "Soccer SmallSize, Soccer MidSize",
+                "year_filter": null,
+                "team_filter": null,
+                "content_type_filter": null,
+                "result_set_id": format!("{}-{}", source, timestamp),
+                "results": [],
+            }),
+        );
+        event.client_ip = Some(ip.to_string());
+        event
+    }
+
+    fn sample_events() -> Vec<ActivityEvent> {
+        // Newest first, as returned by `query_events`
+        vec![
+            search("2025-06-01T12:30:00.000Z", "web", "kicker", 5),
+            search("2025-06-01T12:00:40.000Z", "mcp", "ball detection", 9),
+            search("2025-06-01T12:00:25.000Z", "web", "bang-bang control", 4),
+            search("2025-06-01T12:00:20.000Z", "web", "bang-bang", 0),
+            search("2025-06-01T12:00:05.000Z", "web", "Bang bang", 0),
+            search("2025-06-01T12:00:00.000Z", "web", "bang bang", 0),
+            event(
+                "2025-06-01T12:00:30.000Z",
+                "web",
+                "paper_open",
+                json!({"type": "paper_open", "paper_id": "a", "referrer": null, "result_set_id": "web-2025-06-01T12:00:25.000Z"}),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_low_result_queries() {
+        let records = search_records(&sample_events());
+        assert_eq!(records.len(), 6);
+        assert_eq!(records[0].query, "bang bang");
+
+        let table = low_result_queries(&records, 0);
+        assert_eq!(
+            table.rows,
+            vec![
+                vec!["bang bang", "2", "0.0", "2"],
+                vec!["bang-bang", "1", "0.0", "1"],
+            ]
+        );
+        assert_eq!(low_result_queries(&records, 5).rows.len(), 4);
+    }
+
+    #[test]
+    fn test_reformulation_chains() {
+        let records = search_records(&sample_events());
+        let table = reformulation_chains(&records, 60);
+        assert_eq!(
+            table.rows,
+            vec![vec![
+                "bang bang -> bang-bang -> bang-bang control",
+                "3",
+                "1",
+                "0 -> 0 -> 4"
+            ]]
+        );
+
+        // With a two second window every search starts a chain of its own
+        assert!(reformulation_chains(&records, 2).rows.is_empty());
+    }
+
+    #[test]
+    fn test_reformulation_chains_per_client() {
+        // Two users searching at the same time do not rewrite each other's queries
+        let events = vec![
+            search_by(
+                "2025-06-01T12:00:10.000Z",
+                "web",
+                "198.51.100.2",
+                "kicker design",
+                3,
+            ),
+            search_by(
+                "2025-06-01T12:00:05.000Z",
+                "web",
+                "198.51.100.1",
+                "kicker",
+                0,
+            ),
+            search_by(
+                "2025-06-01T12:00:00.000Z",
+                "web",
+                "198.51.100.2",
+                "kicker",
+                0,
+            ),
+        ];
+        let table = reformulation_chains(&search_records(&events), 60);
+        assert_eq!(
+            table.rows,
+            vec![vec!["kicker -> kicker design", "2", "1", "0 -> 3"]]
+        );
+
+        // Searches of unknown clients are not chained
+        let mut events = events;
+        for event in &mut events {
+            event.client_ip = None;
+        }
+        assert!(
+            reformulation_chains(&search_records(&events), 60)
+                .rows
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_filter_usage_and_click_through() {
+        let events = sample_events();
+        let records = search_records(&events);
+
+        let filters = filter_usage(&records);
+        assert_eq!(filters.rows[0], vec!["league", "Soccer MidSize", "6"]);
+        assert_eq!(filters.rows.len(), 2);
+
+        let ctr = query_click_through(&records, &events, 1);
+        assert_eq!(
+            ctr.rows.last().unwrap(),
+            &vec!["bang-bang control", "1", "1", "1.00"]
+        );
+        assert_eq!(ctr.rows[0], vec!["bang bang", "2", "0", "0.00"]);
+        assert_eq!(query_click_through(&records, &events, 2).rows.len(), 1);
+    }
+
+    #[test]
+    fn test_table_render_and_csv() {
+        let table = Table {
+            headers: vec!["query", "searches"],
+            rows: vec![
+                vec!["say \"hi\", robot".to_string(), "10".to_string()],
+                vec!["kicker".to_string(), "2".to_string()],
+            ],
+        };
+        assert_eq!(
+            table.to_csv(),
+            "query,searches\n\"say \"\"hi\"\", robot\",10\nkicker,2\n"
+        );
+        assert_eq!(
+            table.render(1),
+            "query            searches\nsay \"hi\", robot  10\n"
+        );
+    }
+}
@@ -1,6 +1,11 @@
 use std::collections::HashMap;
 
 use event_processing::listeners::sqlite::SqliteListener;
+use tools::activity_reports::{
+    Table, filter_usage, low_result_queries, query_click_through, reformulation_chains,
+    search_records,
+};
+use tools::click_labels::mine_labels;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -25,6 +30,13 @@ async fn main() -> anyhow::Result<()> {
         "summary" => summary(&listener, args.get(2..).unwrap_or_default()).await?,
         "recent" => recent(&listener, args.get(2..).unwrap_or_default()).await?,
         "agents" => agents(&listener, args.get(2..).unwrap_or_default()).await?,
+        "labels" => labels(&listener, args.get(2..).unwrap_or_default()).await?,
+        "rollups" => rollups(&listener, args.get(2..).unwrap_or_default()).await?,
+        "prune" => prune(&listener).await?,
+        "purge-ip" => purge_ip(&listener, args.get(2..).unwrap_or_default()).await?,
+        "zero" | "reformulations" | "filters" | "ctr" => {
+            report(&listener, subcommand, args.get(2..).unwrap_or_default()).await?
+        }
         _ => {
             eprintln!("Usage: activity <command>");
             eprintln!();
@@ -32,6 +44,17 @@ async fn main() -> anyhow::Result<()> {
             eprintln!("  summary [--since DATE]   Event counts by type and source");
             eprintln!("  recent  [--limit N]      Most recent events");
             eprintln!("  agents  [--since DATE]   User-agent breakdown (scraper detection)");
+            eprintln!("  labels  [--since DATE] [--out FILE] [--version N]");
+            eprintln!("                           Click-through relevance labels as a golden set");
+            eprintln!("  zero    [--max-results N]    Queries with zero (or at most N) results");
+            eprintln!("  reformulations [--window SECS]  Queries rewritten shortly after each other");
+            eprintln!("  filters                  Most-filtered leagues and teams");
+            eprintln!("  ctr     [--min-searches N]   Click-through rate per query");
+            eprintln!("  rollups [--since DATE]   Daily counts kept after retention");
+            eprintln!("  prune                    Roll up and delete events past retention_days");
+            eprintln!("  purge-ip <IP>            Delete all events recorded for an IP address");
+            eprintln!();
+            eprintln!("Reports take [--since DATE] [--limit N] [--csv]; --csv prints all rows as CSV.");
         }
     }
 
@@ -173,8 +196,12 @@ async fn agents(
     // Group by user_agent
     let mut by_agent: HashMap<String, usize> = HashMap::new();
     let mut by_ip: HashMap<String, usize> = HashMap::new();
+    let mut by_class: HashMap<String, usize> = HashMap::new();
 
     for e in &events {
+        let class = e.client_class.as_deref().unwrap_or("(unclassified)");
+        *by_class.entry(class.to_string()).or_default() += 1;
+
         if let Some(ref payload) = e.payload {
             if let Ok(v) = serde_json::from_str::<serde_json::Value>(payload) {
                 let ua = v
@@ -193,6 +220,15 @@ async fn agents(
 
     println!("=== User-Agent Breakdown ({} requests) ===\n", events.len());
 
+    let mut classes_sorted: Vec<_> = by_class.into_iter().collect();
+    classes_sorted.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    println!("By client class:");
+    for (class, count) in &classes_sorted {
+        println!("  {:>6}  {}", count, class);
+    }
+
+    println!();
+
     let mut agents_sorted: Vec<_> = by_agent.into_iter().collect();
     agents_sorted.sort_by(|a, b| b.1.cmp(&a.1));
     println!("By user-agent:");
@@ -211,3 +247,169 @@ async fn agents(
 
     Ok(())
 }
+
+async fn labels(
+    listener: &SqliteListener,
+    args: &[String],
+) -> anyhow::Result<()> {
+    let since = parse_flag(args, "--since");
+    let version: u32 = match parse_flag(args, "--version") {
+        Some(v) => v
+            .parse()
+            .map_err(|_| anyhow::anyhow!("Invalid --version '{}'", v))?,
+        None => 1,
+    };
+
+    let events = listener
+        .query_events(None, None, since.clone(), None)
+        .await
+        .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+    let (golden, stats) = mine_labels(&events, version);
+
+    println!("=== Click-through Labels ===");
+    if let Some(ref s) = since {
+        println!("Since: {}", s);
+    }
+    println!("Searches:              {}", stats.searches);
+    println!("  with result set ID:  {}", stats.linked_searches);
+    println!("  with follow-ups:     {}", stats.clicked_searches);
+    println!("Click-through rate:    {:.1}%", stats.click_through_rate() * 100.0);
+    println!("Follow-ups:            {}", stats.follow_ups);
+    println!("  without search:      {}", stats.orphan_follow_ups);
+    println!(
+        "Labelled queries:      {} ({} judgments)",
+        golden.queries.len(),
+        golden.queries.iter().map(|q| q.judgments.len()).sum::<usize>()
+    );
+
+    if let Some(out) = parse_flag(args, "--out") {
+        std::fs::write(&out, serde_json::to_string_pretty(&golden)?)?;
+        println!("\nWrote golden set v{} to {}", version, out);
+    }
+
+    Ok(())
+}
+
+async fn report(
+    listener: &SqliteListener,
+    name: &str,
+    args: &[String],
+) -> anyhow::Result<()> {
+    let since = parse_flag(args, "--since");
+    let number = |flag: &str, default: usize| -> anyhow::Result<usize> {
+        match parse_flag(args, flag) {
+            Some(v) => v
+                .parse()
+                .map_err(|_| anyhow::anyhow!("Invalid {} '{}'", flag, v)),
+            None => Ok(default),
+        }
+    };
+    let limit = number("--limit", 25)?;
+
+    let events = listener
+        .query_events(None, None, since.clone(), None)
+        .await
+        .map_err(|e| anyhow::anyhow!("{}", e))?;
+    let records = search_records(&events);
+
+    let (title, table): (&str, Table) = match name {
+        "zero" => {
+            let max_results = number("--max-results", 0)?;
+            ("Zero/Low-result Queries", low_result_queries(&records, max_results))
+        }
+        "reformulations" => {
+            let window = number("--window", 60)? as i64;
+            ("Reformulation Chains", reformulation_chains(&records, window))
+        }
+        "filters" => ("Filter Usage", filter_usage(&records)),
+        "ctr" => {
+            let min_searches = number("--min-searches", 1)?;
+            (
+                "Click-through Rate per Query",
+                query_click_through(&records, &events, min_searches),
+            )
+        }
+        _ => unreachable!("report names are matched in main"),
+    };
+
+    if args.iter().any(|a| a == "--csv") {
+        print!("{}", table.to_csv());
+        return Ok(());
+    }
+
+    println!("=== {} ===", title);
+    if let Some(ref s) = since {
+        println!("Since: {}", s);
+    }
+    println!("Searches: {}, rows: {}\n", records.len(), table.rows.len());
+    if table.rows.is_empty() {
+        println!("Nothing to report.");
+        return Ok(());
+    }
+    print!("{}", table.render(limit));
+    if table.rows.len() > limit {
+        println!("... {} more (use --limit or --csv)", table.rows.len() - limit);
+    }
+
+    Ok(())
+}
+
+async fn rollups(
+    listener: &SqliteListener,
+    args: &[String],
+) -> anyhow::Result<()> {
+    let since = parse_flag(args, "--since");
+
+    let rollups = listener
+        .query_rollups(since)
+        .await
+        .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+    if rollups.is_empty() {
+        println!("No rollups found.");
+        return Ok(());
+    }
+
+    for r in &rollups {
+        println!(
+            "{} [{:<3}] {:<20} {:>6}  {}",
+            r.day, r.source, r.event_type, r.count, r.query
+        );
+    }
+
+    Ok(())
+}
+
+async fn prune(listener: &SqliteListener) -> anyhow::Result<()> {
+    let summary = listener
+        .apply_retention()
+        .await
+        .map_err(|e| anyhow::anyhow!("{}", e))?
+        .ok_or_else(|| anyhow::anyhow!("No retention_days configured for the activity log"))?;
+
+    println!(
+        "Rolled up and deleted {} events before {}",
+        summary.events_deleted, summary.cutoff
+    );
+
+    Ok(())
+}
+
+async fn purge_ip(
+    listener: &SqliteListener,
+    args: &[String],
+) -> anyhow::Result<()> {
+    let ip = args
+        .first()
+        .ok_or_else(|| anyhow::anyhow!("Usage: activity purge-ip <IP>"))?;
+
+    let deleted = listener
+        .purge_ip(ip.clone())
+        .await
+        .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+    println!("Deleted {} events for {}", deleted, ip);
+
+    Ok(())
+}
@@ -0,0 +1,129 @@
+use std::collections::BTreeMap;
+
+use data_access::api_keys::ApiKeyClient;
+use tools::get_arg;
+
+const USAGE: &str = "Usage: api_keys <command>
+
+Commands:
+  issue --name NAME [--contact EMAIL] [--quota N]   Issue a key; the token is shown once
+  revoke <KEY_ID>                                  Revoke a key
+  list                                             All keys with their status and quota
+  usage [--since YYYY-MM-DD]                       Requests per key, day and route class";
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+
+    let config_path = "config.toml";
+    let config = configuration::AppConfig::load_from_file(config_path)
+        .map_err(|e| anyhow::anyhow!("Failed to load config from {}: {}", config_path, e))?;
+
+    let api_keys = configuration::helpers::build_api_key_client(&config)?.ok_or_else(|| {
+        anyhow::anyhow!("API keys not configured. Add [data_access.api_keys.sqlite] to config.toml")
+    })?;
+
+    match args.get(1).map(String::as_str) {
+        Some("issue") => issue(api_keys.as_ref(), &args).await,
+        Some("revoke") => {
+            let key_id = args.get(2).ok_or_else(|| anyhow::anyhow!("{}", USAGE))?;
+            api_keys.revoke_key(key_id).await?;
+            println!("Revoked {}", key_id);
+            Ok(())
+        }
+        Some("list") => list(api_keys.as_ref()).await,
+        Some("usage") => usage(api_keys.as_ref(), &args).await,
+        _ => anyhow::bail!("{}", USAGE),
+    }
+}
+
+async fn issue(api_keys: &(dyn ApiKeyClient + Send + Sync), args: &[String]) -> anyhow::Result<()> {
+    let name = get_arg(args, "--name").ok_or_else(|| anyhow::anyhow!("{}", USAGE))?;
+    let contact = get_arg(args, "--contact");
+    let quota: Option<u64> = match get_arg(args, "--quota") {
+        Some(q) => Some(
+            q.parse()
+                .map_err(|_| anyhow::anyhow!("Invalid --quota '{}'", q))?,
+        ),
+        None => None,
+    };
+
+    let issued = api_keys.issue_key(&name, contact.as_deref(), quota).await?;
+
+    println!("Issued key {} for {}", issued.key.id, issued.key.name);
+    if let Some(quota) = issued.key.daily_quota {
+        println!("Daily quota: {} requests", quota);
+    }
+    println!("\nToken (shown only once):\n  {}", issued.token);
+    println!("\nSend it as `Authorization: Bearer <token>`.");
+    Ok(())
+}
+
+async fn list(api_keys: &(dyn ApiKeyClient + Send + Sync)) -> anyhow::Result<()> {
+    let keys = api_keys.list_keys().await?;
+    if keys.is_empty() {
+        println!("No API keys issued.");
+        return Ok(());
+    }
+
+    println!(
+        "{:<18} {:<24} {:<28} {:>8}  {:<10} created",
+        "id", "name", "contact", "quota", "status"
+    );
+    for key in &keys {
+        println!(
+            "{:<18} {:<24} {:<28} {:>8}  {:<10} {}",
+            key.id,
+            key.name,
+            key.contact.as_deref().unwrap_or("-"),
+            key.daily_quota.map_or("-".to_string(), |q| q.to_string()),
+            if key.is_active() { "active" } else { "revoked" },
+            key.created_at
+        );
+    }
+    Ok(())
+}
+
+async fn usage(api_keys: &(dyn ApiKeyClient + Send + Sync), args: &[String]) -> anyhow::Result<()> {
+    let since = get_arg(args, "--since");
+    let usage = api_keys.usage(since.as_deref()).await?;
+    if usage.is_empty() {
+        println!("No API key usage recorded.");
+        return Ok(());
+    }
+
+    let names: BTreeMap<String, String> = api_keys
+        .list_keys()
+        .await?
+        .into_iter()
+        .map(|k| (k.id, k.name))
+        .collect();
+
+    println!(
+        "{:<12} {:<18} {:<24} {:<10} {:>8}",
+        "day", "key", "name", "class", "requests"
+    );
+    let mut totals: BTreeMap<&str, u64> = BTreeMap::new();
+    for u in &usage {
+        println!(
+            "{:<12} {:<18} {:<24} {:<10} {:>8}",
+            u.day,
+            u.key_id,
+            names.get(&u.key_id).map_or("?", String::as_str),
+            u.route_class,
+            u.count
+        );
+        *totals.entry(u.key_id.as_str()).or_default() += u.count;
+    }
+
+    println!("\nTotal per key:");
+    for (key_id, total) in &totals {
+        println!(
+            "  {:<18} {:<24} {:>8}",
+            key_id,
+            names.get(*key_id).map_or("?", String::as_str),
+            total
+        );
+    }
+    Ok(())
+}
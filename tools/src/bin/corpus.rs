@@ -0,0 +1,130 @@
+use data_access::vector::{QdrantClient, VectorClient};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use tools::corpus::{Corpus, load_paper, read_archive, write_archive};
+use tracing::info;
+
+const USAGE: &str = "Usage: corpus [export <file> [--version <version>]|import <file>]";
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    tracing_subscriber::fmt::init();
+    let args: Vec<String> = std::env::args().collect();
+    let (Some(command), Some(path)) = (args.get(1), args.get(2)) else {
+        anyhow::bail!("{}", USAGE);
+    };
+    let version = tools::get_arg(&args, "--version")
+        .map(|v| {
+            v.parse::<u32>()
+                .map_err(|_| anyhow::anyhow!("Invalid version '{}'. {}", v, USAGE))
+        })
+        .transpose()?;
+
+    let config_path = "config.toml";
+    let config = configuration::AppConfig::load_from_file(config_path)
+        .map_err(|e| anyhow::anyhow!("Failed to load config from {}: {}", config_path, e))?;
+
+    let qdrant_config = config
+        .data_access
+        .vector
+        .qdrant
+        .clone()
+        .ok_or_else(|| anyhow::anyhow!("No Qdrant configuration found in config.toml"))?;
+    let embedding_size = qdrant_config.embedding_size;
+    let live_client = QdrantClient::new(qdrant_config).await?;
+    let metadata_client = configuration::helpers::load_any_metadata_client(&config)?;
+
+    match command.as_str() {
+        "export" => {
+            let vector_client = match version {
+                Some(version) => live_client.open_version(version).await?,
+                None => live_client,
+            };
+            info!(
+                "Exporting {} (version {})",
+                vector_client.collection_name(),
+                vector_client.version()
+            );
+
+            let mut papers = Vec::new();
+            for name in metadata_client.load_tdps().await? {
+                papers.push(load_paper(&*metadata_client, name).await?);
+            }
+            let idf = metadata_client.load_idf(vector_client.version()).await?;
+            if idf.is_empty() {
+                anyhow::bail!("No IDF stored for version {}", vector_client.version());
+            }
+            let chunks = vector_client.get_all_chunks().await?;
+
+            let corpus = Corpus {
+                papers,
+                idf,
+                chunks,
+            };
+            let manifest = corpus.manifest(vector_client.version(), embedding_size);
+            write_archive(BufWriter::new(File::create(path)?), &manifest, &corpus)?;
+            println!(
+                "Exported {} papers, {} IDF words and {} chunks to {}",
+                manifest.papers, manifest.idf_words, manifest.chunks, path
+            );
+        }
+        "import" if version.is_none() => {
+            let (manifest, corpus) = read_archive(BufReader::new(File::open(path)?))?;
+            if manifest.embedding_size != embedding_size {
+                anyhow::bail!(
+                    "Archive has {}-dimensional vectors but Qdrant is configured for {}",
+                    manifest.embedding_size,
+                    embedding_size
+                );
+            }
+            info!(
+                "Importing {} papers, {} IDF words and {} chunks (exported from version {})",
+                manifest.papers, manifest.idf_words, manifest.chunks, manifest.source_index_version
+            );
+
+            for paper in corpus.papers {
+                metadata_client.store_paper(paper).await?;
+            }
+
+            // A fresh install starts with an empty live collection, which can be filled directly
+            let vector_client = if live_client.version() != QdrantClient::LEGACY_VERSION
+                && live_client.count().await? == 0
+            {
+                live_client
+            } else {
+                live_client.create_next_version().await?
+            };
+            info!(
+                "Indexing into {} (version {})",
+                vector_client.collection_name(),
+                vector_client.version()
+            );
+
+            metadata_client
+                .store_idf(vector_client.version(), corpus.idf)
+                .await?;
+
+            let n_chunks = corpus
+                .chunks
+                .iter()
+                .map(|c| c.to_uuid())
+                .collect::<HashSet<_>>()
+                .len() as u64;
+            for chunk in corpus.chunks {
+                vector_client.store_chunk(chunk).await?;
+            }
+
+            vector_client.validate(Some(n_chunks)).await?;
+            vector_client.promote().await?;
+            println!(
+                "Version {} ({} points) is live. Restart running servers to pick it up.",
+                vector_client.version(),
+                n_chunks
+            );
+        }
+        _ => anyhow::bail!("{}", USAGE),
+    }
+
+    Ok(())
+}
@@ -115,7 +115,7 @@ fn parsing(pdf_root: &str, md_root: &str) -> anyhow::Result<()> {
 
 /// Compare markdowns on disk vs what's indexed in the metadata DB.
 async fn indexing(md_root: &str, config: &configuration::AppConfig) -> anyhow::Result<()> {
-    let metadata_client = configuration::helpers::load_any_metadata_client(config);
+    let metadata_client = configuration::helpers::load_any_metadata_client(config)?;
 
     let indexed_tdps = metadata_client.load_tdps().await
         .map_err(|e| anyhow::anyhow!("Failed to load TDPs from metadata DB: {}", e))?;
@@ -244,8 +244,8 @@ fn heatmap(pdf_root: &str, md_root: &str) -> anyhow::Result<()> {
 
 /// Find teams that have papers but no metadata in the team registry.
 async fn teams(config: &configuration::AppConfig) -> anyhow::Result<()> {
-    let metadata_client = configuration::helpers::load_any_metadata_client(config);
-    let registry = match configuration::helpers::build_registry_client(config) {
+    let metadata_client = configuration::helpers::load_any_metadata_client(config)?;
+    let registry = match configuration::helpers::build_registry_client(config)? {
         Some(r) => r,
         None => {
             println!("=== Team Registry Coverage ===");
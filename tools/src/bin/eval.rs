@@ -0,0 +1,196 @@
+use data_access::vector::VectorClient;
+use data_processing::search::Searcher;
+use data_structures::embed_type::EmbedType;
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+use tools::eval::{
+    GOLDEN_FORMAT_VERSION, GoldenSet, Metrics, ModeReport, RunReport, compare, evaluate,
+};
+use tools::get_arg;
+
+const USAGE: &str = "Usage: eval run <golden.json> [--k 10] [--out run.json]\n       \
+eval compare <baseline.json> <candidate.json> [--threshold 0.02]";
+
+const MODES: [(&str, EmbedType); 3] = [
+    ("dense", EmbedType::DENSE),
+    ("sparse", EmbedType::SPARSE),
+    ("hybrid", EmbedType::HYBRID),
+];
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+    match (args.get(1).map(String::as_str), args.get(2)) {
+        (Some("run"), Some(golden_path)) => run(golden_path, &args).await,
+        (Some("compare"), Some(baseline_path)) => {
+            let candidate_path = args.get(3).ok_or_else(|| anyhow::anyhow!("{}", USAGE))?;
+            compare_runs(baseline_path, candidate_path, &args)
+        }
+        _ => anyhow::bail!("{}", USAGE),
+    }
+}
+
+async fn run(golden_path: &str, args: &[String]) -> anyhow::Result<()> {
+    let k: usize = match get_arg(args, "--k") {
+        Some(k) => k
+            .parse()
+            .map_err(|_| anyhow::anyhow!("Invalid --k '{}'", k))?,
+        None => 10,
+    };
+
+    let golden: GoldenSet = serde_json::from_str(&std::fs::read_to_string(golden_path)?)?;
+    if golden.format_version != GOLDEN_FORMAT_VERSION {
+        anyhow::bail!(
+            "Unsupported golden set format version {} (expected {})",
+            golden.format_version,
+            GOLDEN_FORMAT_VERSION
+        );
+    }
+
+    let config = configuration::AppConfig::load_from_file("config.toml")
+        .map_err(|e| anyhow::anyhow!("Failed to load config from config.toml: {}", e))?;
+    let embed_client = configuration::helpers::load_any_embed_client(&config)?;
+    let vector_client = configuration::helpers::load_any_vector_client(&config).await?;
+    let metadata_client = configuration::helpers::load_any_metadata_client(&config)?;
+
+    let index_version = vector_client.index_version();
+    let idf_map = Arc::new(metadata_client.load_idf(index_version).await?);
+
+    let tdps = metadata_client.load_tdps().await?;
+    let teams: Vec<String> = tdps
+        .iter()
+        .map(|tdp| tdp.team_name.name_pretty.clone())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    let leagues: Vec<String> = tdps
+        .iter()
+        .map(|tdp| tdp.league.name_pretty().to_string())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+
+    let searcher = Searcher::new(
+        embed_client,
+        vector_client,
+        metadata_client,
+        idf_map,
+        teams,
+        leagues,
+        config.data_processing.highlight_idf_threshold(),
+    );
+
+    println!(
+        "Evaluating {} queries (golden set v{}) against index version {} at k={}\n",
+        golden.queries.len(),
+        golden.version,
+        index_version,
+        k
+    );
+
+    let mut modes = BTreeMap::new();
+    for (mode, search_type) in MODES {
+        let mut queries = BTreeMap::new();
+        for query in &golden.queries {
+            let result = searcher
+                .search(
+                    query.query.clone(),
+                    Some(k as u64),
+                    query.filter.clone(),
+                    search_type,
+                )
+                .await?;
+            let ranked: Vec<(String, u32)> = result
+                .chunks
+                .into_iter()
+                .map(|c| (c.paper_lyt, c.content_seq))
+                .collect();
+            queries.insert(query.id.clone(), evaluate(&query.judgments, &ranked, k));
+        }
+        modes.insert(mode.to_string(), ModeReport::new(queries));
+    }
+
+    println!("{:<8} {:>8} {:>8} {:>8}", "mode", "nDCG", "MRR", "recall");
+    for (mode, report) in &modes {
+        print_metrics(mode, &report.mean);
+    }
+
+    let report = RunReport {
+        golden_version: golden.version,
+        index_version,
+        k,
+        created_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default(),
+        modes,
+    };
+
+    if let Some(out) = get_arg(args, "--out") {
+        std::fs::write(&out, serde_json::to_string_pretty(&report)?)?;
+        println!("\nWrote run to {}", out);
+    }
+
+    Ok(())
+}
+
+fn compare_runs(baseline_path: &str, candidate_path: &str, args: &[String]) -> anyhow::Result<()> {
+    let threshold: f64 = match get_arg(args, "--threshold") {
+        Some(t) => t
+            .parse()
+            .map_err(|_| anyhow::anyhow!("Invalid --threshold '{}'", t))?,
+        None => 0.02,
+    };
+
+    let baseline: RunReport = serde_json::from_str(&std::fs::read_to_string(baseline_path)?)?;
+    let candidate: RunReport = serde_json::from_str(&std::fs::read_to_string(candidate_path)?)?;
+
+    println!(
+        "Baseline: index version {}, candidate: index version {} (golden set v{}, k={})\n",
+        baseline.index_version, candidate.index_version, baseline.golden_version, baseline.k
+    );
+    println!(
+        "{:<8} {:>8} {:>8} {:>8}   (candidate - baseline)",
+        "mode", "nDCG", "MRR", "recall"
+    );
+    for (mode, before) in &baseline.modes {
+        if let Some(after) = candidate.modes.get(mode) {
+            let [n, m, r] = after.mean.values();
+            let [bn, bm, br] = before.mean.values();
+            print_metrics(
+                mode,
+                &Metrics {
+                    ndcg: n - bn,
+                    mrr: m - bm,
+                    recall: r - br,
+                },
+            );
+        }
+    }
+
+    let regressions = compare(&baseline, &candidate, threshold).map_err(anyhow::Error::msg)?;
+    if regressions.is_empty() {
+        println!("\nNo regressions larger than {}", threshold);
+        return Ok(());
+    }
+
+    println!("\nRegressions larger than {}:", threshold);
+    for r in &regressions {
+        println!(
+            "  {:<8} {:<24} {:<8} {:.4} -> {:.4}",
+            r.mode,
+            r.query_id.as_deref().unwrap_or("(mean)"),
+            r.metric,
+            r.baseline,
+            r.candidate
+        );
+    }
+    anyhow::bail!("{} regressions found", regressions.len())
+}
+
+fn print_metrics(mode: &str, metrics: &Metrics) {
+    println!(
+        "{:<8} {:>8.4} {:>8.4} {:>8.4}",
+        mode, metrics.ndcg, metrics.mrr, metrics.recall
+    );
+}
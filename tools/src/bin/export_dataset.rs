@@ -0,0 +1,70 @@
+use api::export_dataset::{
+    DATASET_SCHEMA_VERSION, DatasetFormat, ExportDatasetArgs, export_dataset,
+};
+use event_processing::EventSource;
+use event_processing::dispatcher::EventDispatcher;
+use std::fs::File;
+use std::io::BufWriter;
+use tools::get_arg;
+
+const USAGE: &str = "Usage: export_dataset <file.jsonl|file.parquet> [--format jsonl|parquet] \
+[--league L] [--year Y] [--team T] [--paper P] [--type text,table,image] [--dense] [--sparse]";
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+    let Some(path) = args.get(1).filter(|a| !a.starts_with("--")) else {
+        anyhow::bail!("{}", USAGE);
+    };
+
+    let format = match get_arg(&args, "--format").as_deref() {
+        Some("jsonl") => DatasetFormat::Jsonl,
+        Some("parquet") => DatasetFormat::Parquet,
+        Some(other) => anyhow::bail!("Unknown format '{}'. {}", other, USAGE),
+        None if path.ends_with(".parquet") => DatasetFormat::Parquet,
+        None => DatasetFormat::Jsonl,
+    };
+
+    let config_path = "config.toml";
+    let config = configuration::AppConfig::load_from_file(config_path)
+        .map_err(|e| anyhow::anyhow!("Failed to load config from {}: {}", config_path, e))?;
+
+    let vector_client = configuration::helpers::load_any_vector_client(&config).await?;
+    let metadata_client = configuration::helpers::load_any_metadata_client(&config)?;
+    // Offline exports are not user activity, so no listener records them
+    let dispatcher = EventDispatcher::new();
+
+    let export_args = ExportDatasetArgs {
+        format: Some(format),
+        league_filter: get_arg(&args, "--league"),
+        year_filter: get_arg(&args, "--year"),
+        team_filter: get_arg(&args, "--team"),
+        paper_lyt_filter: get_arg(&args, "--paper"),
+        content_type_filter: get_arg(&args, "--type"),
+        include_dense: Some(args.iter().any(|a| a == "--dense")),
+        include_sparse: Some(args.iter().any(|a| a == "--sparse")),
+    };
+
+    let writer = BufWriter::new(File::create(path)?);
+    let summary = export_dataset(
+        metadata_client,
+        vector_client,
+        export_args,
+        writer,
+        &dispatcher,
+        EventSource::Web,
+    )
+    .await?;
+
+    println!(
+        "Wrote {} chunks from {} papers to {} ({}, schema v{}, index version {})",
+        summary.rows,
+        summary.papers,
+        path,
+        format.as_str(),
+        DATASET_SCHEMA_VERSION,
+        summary.index_version
+    );
+
+    Ok(())
+}
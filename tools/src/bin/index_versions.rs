@@ -0,0 +1,70 @@
+use data_access::vector::QdrantClient;
+
+const USAGE: &str = "Usage: index_versions [list|promote <version>|delete <version>]";
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+    let command = args.get(1).map(String::as_str).unwrap_or("list");
+    let version = args
+        .get(2)
+        .map(|v| {
+            v.parse::<u32>()
+                .map_err(|_| anyhow::anyhow!("Invalid version '{}'. {}", v, USAGE))
+        })
+        .transpose()?;
+
+    let config_path = "config.toml";
+    let config = configuration::AppConfig::load_from_file(config_path)
+        .map_err(|e| anyhow::anyhow!("Failed to load config from {}: {}", config_path, e))?;
+
+    let qdrant_config = config
+        .data_access
+        .vector
+        .qdrant
+        .clone()
+        .ok_or_else(|| anyhow::anyhow!("No Qdrant configuration found in config.toml"))?;
+    let client = QdrantClient::new(qdrant_config).await?;
+    let metadata_client = configuration::helpers::load_any_metadata_client(&config)?;
+
+    match (command, version) {
+        ("list", None) => {
+            let live = client.live_version().await?;
+            if live.is_none() {
+                println!(
+                    "No alias yet, serving legacy collection (version {})",
+                    QdrantClient::LEGACY_VERSION
+                );
+            }
+            for version in client.list_versions().await? {
+                let points = client.open_version(version).await?.count().await?;
+                let idf_words = metadata_client.load_idf(version).await?.len();
+                let marker = if live == Some(version) { "*" } else { " " };
+                println!(
+                    "{} v{:<4} {:>8} points  {:>8} IDF words",
+                    marker, version, points, idf_words
+                );
+            }
+        }
+        ("promote", Some(version)) => {
+            let target = client.open_version(version).await?;
+            let points = target.validate(None).await?;
+            if metadata_client.load_idf(version).await?.is_empty() {
+                anyhow::bail!("No IDF stored for version {}", version);
+            }
+            target.promote().await?;
+            println!(
+                "Version {} ({} points) is live. Restart running servers to pick it up.",
+                version, points
+            );
+        }
+        ("delete", Some(version)) => {
+            client.delete_version(version).await?;
+            metadata_client.delete_idf(version).await?;
+            println!("Deleted version {}", version);
+        }
+        _ => anyhow::bail!("{}", USAGE),
+    }
+
+    Ok(())
+}
@@ -1,8 +1,10 @@
+use data_access::vector::{QdrantClient, VectorClient};
 use data_processing::{
     content_chunker::tdp_to_chunks, embed::embed_chunks, markdown_parser::load_all_markdown_tdps,
     text::create_idf,
 };
 use data_structures::{IDF, embed_type::EmbedType, filter::Filter};
+use std::collections::HashSet;
 use tracing::info;
 
 #[tokio::main]
@@ -10,9 +12,15 @@ pub async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let _stdout_subscriber = tracing_subscriber::fmt::init();
     let config = configuration::AppConfig::load_from_file("config.toml").unwrap();
 
-    let embed_client = configuration::helpers::load_any_embed_client(&config);
-    let vector_client = configuration::helpers::load_any_vector_client(&config).await?;
-    let metadata_client = configuration::helpers::load_any_metadata_client(&config);
+    let embed_client = configuration::helpers::load_any_embed_client(&config)?;
+    let qdrant_config = config
+        .data_access
+        .vector
+        .qdrant
+        .clone()
+        .ok_or("No Qdrant configuration found in config.toml")?;
+    let live_client = QdrantClient::new(qdrant_config).await?;
+    let metadata_client = configuration::helpers::load_any_metadata_client(&config)?;
 
     let filter = Filter::default();
 
@@ -32,13 +40,30 @@ pub async fn main() -> Result<(), Box<dyn std::error::Error>> {
         metadata_client.store_paper(tdp).await?;
     }
 
-    /* Step 4 : Create and store IDF */
+    /* Step 4 : Create the collection for the new index version */
+    // A fresh install starts with an empty live collection, which can be filled directly
+    let vector_client = if live_client.version() != QdrantClient::LEGACY_VERSION
+        && live_client.count().await? == 0
+    {
+        live_client
+    } else {
+        live_client.create_next_version().await?
+    };
+    info!(
+        "Indexing into {} (version {})",
+        vector_client.collection_name(),
+        vector_client.version()
+    );
+
+    /* Step 5 : Create and store IDF */
     info!("Creating IDF");
     let texts: Vec<&str> = chunks.iter().map(|c| c.text.as_str()).collect();
     let idf_map = create_idf(&texts, &[1, 5, 10]);
-    metadata_client.store_idf(idf_map.clone()).await?;
+    metadata_client
+        .store_idf(vector_client.version(), idf_map.clone())
+        .await?;
 
-    /* Step 5 : Create embeddings */
+    /* Step 6 : Create embeddings */
     info!("Creating embeddings");
     embed_chunks(
         &mut chunks,
@@ -48,12 +73,23 @@ pub async fn main() -> Result<(), Box<dyn std::error::Error>> {
     )
     .await?;
 
-    /* Step 6 : Store chunks */
+    /* Step 7 : Store chunks */
     info!("Storing chunks");
+    // Points are keyed by chunk UUID, so duplicates collapse into a single point
+    let n_chunks = chunks.iter().map(|c| c.to_uuid()).collect::<HashSet<_>>().len() as u64;
     for chunk in chunks {
         vector_client.store_chunk(chunk).await?;
     }
 
+    /* Step 8 : Validate and promote */
+    info!("Validating {}", vector_client.collection_name());
+    vector_client.validate(Some(n_chunks)).await?;
+    vector_client.promote().await?;
+    info!(
+        "Version {} is live. Restart running servers to pick it up.",
+        vector_client.version()
+    );
+
     Ok(())
 }
 
@@ -0,0 +1,96 @@
+use data_access::api_keys::SqliteApiKeyClient;
+use data_access::metadata::SqliteClient;
+use data_access::migration::{Migration, MigrationStatus};
+use data_access::registry::SqliteRegistryClient;
+use event_processing::listeners::sqlite::SqliteListener;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let command = std::env::args().nth(1).unwrap_or_else(|| "status".to_string());
+
+    let config_path = "config.toml";
+    let config = configuration::AppConfig::load_from_file(config_path)
+        .map_err(|e| anyhow::anyhow!("Failed to load config from {}: {}", config_path, e))?;
+
+    let metadata_cfg = config.data_access.metadata.sqlite.as_ref();
+    let registry_cfg = config
+        .data_access
+        .registry
+        .as_ref()
+        .and_then(|registry| registry.sqlite.as_ref());
+    let api_keys_cfg = config
+        .data_access
+        .api_keys
+        .as_ref()
+        .and_then(|api_keys| api_keys.sqlite.as_ref());
+    let activity_cfg = config
+        .event_processing
+        .as_ref()
+        .and_then(|event_processing| event_processing.activity.as_ref())
+        .and_then(|activity| activity.sqlite.as_ref());
+
+    match command.as_str() {
+        "status" => {
+            if let Some(cfg) = metadata_cfg {
+                print_status(&SqliteClient::migration_status(cfg)?);
+            }
+            if let Some(cfg) = registry_cfg {
+                print_status(&SqliteRegistryClient::migration_status(cfg)?);
+            }
+            if let Some(cfg) = api_keys_cfg {
+                print_status(&SqliteApiKeyClient::migration_status(cfg)?);
+            }
+            if let Some(cfg) = activity_cfg {
+                print_status(&SqliteListener::migration_status(cfg)?);
+            }
+        }
+        "apply" => {
+            if let Some(cfg) = metadata_cfg {
+                let status = SqliteClient::migration_status(cfg)?;
+                print_applied(&status, &SqliteClient::migrate(cfg)?);
+            }
+            if let Some(cfg) = registry_cfg {
+                let status = SqliteRegistryClient::migration_status(cfg)?;
+                print_applied(&status, &SqliteRegistryClient::migrate(cfg)?);
+            }
+            if let Some(cfg) = api_keys_cfg {
+                let status = SqliteApiKeyClient::migration_status(cfg)?;
+                print_applied(&status, &SqliteApiKeyClient::migrate(cfg)?);
+            }
+            if let Some(cfg) = activity_cfg {
+                let status = SqliteListener::migration_status(cfg)?;
+                print_applied(&status, &SqliteListener::migrate(cfg)?);
+            }
+        }
+        other => {
+            anyhow::bail!("Unknown command '{}'. Usage: migrate [status|apply]", other);
+        }
+    }
+
+    Ok(())
+}
+
+fn print_status(status: &MigrationStatus) {
+    println!(
+        "{}: version {} of {}",
+        status.database, status.current, status.latest
+    );
+    if status.current > status.latest {
+        println!("  database is newer than this build");
+    }
+    for migration in &status.pending {
+        println!("  pending {:>3}  {}", migration.version, migration.description);
+    }
+}
+
+fn print_applied(before: &MigrationStatus, applied: &[&Migration]) {
+    if applied.is_empty() {
+        println!("{}: up to date (version {})", before.database, before.current);
+        return;
+    }
+
+    println!("{}:", before.database);
+    for migration in applied {
+        println!("  applied {:>3}  {}", migration.version, migration.description);
+    }
+}
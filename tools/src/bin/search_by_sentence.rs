@@ -1,4 +1,5 @@
 use api::search::{search, SearchArgs};
+use data_access::vector::VectorClient;
 use event_processing::EventSource;
 use data_processing::search::Searcher;
 use data_structures::embed_type::EmbedType;
@@ -73,12 +74,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let config = configuration::AppConfig::load_from_file("config.toml").unwrap();
 
-    let embed_client = configuration::helpers::load_any_embed_client(&config);
+    let embed_client = configuration::helpers::load_any_embed_client(&config)?;
     let vector_client = configuration::helpers::load_any_vector_client(&config).await?;
-    let metadata_client = configuration::helpers::load_any_metadata_client(&config);
+    let metadata_client = configuration::helpers::load_any_metadata_client(&config)?;
     let dispatcher = configuration::helpers::build_event_dispatcher(&config);
 
-    let idf_map = Arc::new(metadata_client.load_idf().await?);
+    let idf_map = Arc::new(metadata_client.load_idf(vector_client.index_version()).await?);
 
     let tdps = metadata_client.load_tdps().await?;
     let mut teams = tdps
@@ -114,7 +115,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         team_filter: None,
         paper_lyt_filter: None,
         content_type_filter: content_type_filter,
-        search_type: search_mode,
+        search_type: Some(search_mode),
     };
 
     let results = search(&searcher, search_args, &dispatcher, EventSource::Web)
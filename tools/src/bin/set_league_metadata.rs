@@ -19,7 +19,7 @@ async fn main() -> anyhow::Result<()> {
     let config = configuration::AppConfig::load_from_file(config_path)
         .map_err(|e| anyhow::anyhow!("Failed to load config from {}: {}", config_path, e))?;
 
-    let registry = configuration::helpers::build_registry_client(&config)
+    let registry = configuration::helpers::build_registry_client(&config)?
         .ok_or_else(|| anyhow::anyhow!(
             "Registry not configured. Add [data_access.registry.sqlite] to config.toml"
         ))?;
@@ -15,7 +15,7 @@ async fn main() -> anyhow::Result<()> {
     let config = configuration::AppConfig::load_from_file(config_path)
         .map_err(|e| anyhow::anyhow!("Failed to load config from {}: {}", config_path, e))?;
 
-    let metadata_client = configuration::helpers::load_any_metadata_client(&config);
+    let metadata_client = configuration::helpers::load_any_metadata_client(&config)?;
     let known_teams = metadata_client
         .load_teams()
         .await
@@ -23,7 +23,7 @@ async fn main() -> anyhow::Result<()> {
 
     let team = validate_team_name(&team_input, &known_teams);
 
-    let registry = configuration::helpers::build_registry_client(&config)
+    let registry = configuration::helpers::build_registry_client(&config)?
         .ok_or_else(|| anyhow::anyhow!(
             "Registry not configured. Add [data_access.registry.sqlite] to config.toml"
         ))?;
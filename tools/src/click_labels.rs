@@ -0,0 +1,324 @@
+//! Implicit relevance judgments mined from the activity log. Every search logs a result-set ID,
+//! and paper opens, PDF opens and section reads that originate from it reference that ID. Those
+//! follow-ups are turned into graded click-through labels in the golden set format.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use data_structures::filter::Filter;
+use event_processing::listeners::sqlite::ActivityEvent;
+use serde::Deserialize;
+
+use crate::eval::{GOLDEN_FORMAT_VERSION, GoldenQuery, GoldenSet, Judgment};
+
+/// Opening the paper page says little more than that the snippet looked promising
+pub const PAPER_OPEN_GRADE: u32 = 1;
+/// Opening the PDF means the reader went past the rendered paper
+pub const PDF_OPEN_GRADE: u32 = 2;
+/// Reading a specific section, as agents do after a search, pins relevance to that content item
+pub const SECTION_GRADE: u32 = 2;
+
+#[derive(Deserialize)]
+struct SearchPayload {
+    query: String,
+    #[serde(default)]
+    result_set_id: Option<String>,
+    #[serde(default)]
+    league_filter: Option<String>,
+    #[serde(default)]
+    year_filter: Option<String>,
+    #[serde(default)]
+    team_filter: Option<String>,
+    #[serde(default)]
+    content_type_filter: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct FollowUpPayload {
+    #[serde(alias = "paper_id")]
+    paper: String,
+    #[serde(default)]
+    content_seq: Option<u32>,
+    #[serde(default)]
+    result_set_id: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ClickStats {
+    pub searches: usize,
+    /// Searches logged with a result-set ID
+    pub linked_searches: usize,
+    /// Linked searches with at least one follow-up
+    pub clicked_searches: usize,
+    pub follow_ups: usize,
+    /// Follow-ups whose result-set ID matches no search in the log window
+    pub orphan_follow_ups: usize,
+}
+
+impl ClickStats {
+    pub fn click_through_rate(&self) -> f64 {
+        if self.linked_searches == 0 {
+            return 0.0;
+        }
+        self.clicked_searches as f64 / self.linked_searches as f64
+    }
+}
+
+/// Searches with the same normalized query and the same filters share one golden query
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct QueryKey {
+    query: String,
+    league_filter: Option<String>,
+    year_filter: Option<String>,
+    team_filter: Option<String>,
+    content_type_filter: Option<String>,
+}
+
+impl QueryKey {
+    fn filter(&self) -> Option<Filter> {
+        let filters = [
+            &self.league_filter,
+            &self.year_filter,
+            &self.team_filter,
+            &self.content_type_filter,
+        ];
+        if filters.iter().all(|f| f.is_none()) {
+            return None;
+        }
+        api::search::parse_filter(
+            self.league_filter.as_deref(),
+            self.year_filter.as_deref(),
+            self.team_filter.as_deref(),
+            None,
+            self.content_type_filter.as_deref(),
+        )
+        .ok()
+    }
+}
+
+pub(crate) fn normalize_query(query: &str) -> String {
+    query
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+pub(crate) fn follow_up_grade(event_type: &str) -> Option<u32> {
+    match event_type {
+        "paper_open" => Some(PAPER_OPEN_GRADE),
+        "pdf_open" => Some(PDF_OPEN_GRADE),
+        "get_section" => Some(SECTION_GRADE),
+        _ => None,
+    }
+}
+
+/// Turn activity log events into a golden set of click-through labels. Each follow-up is
+/// credited to the search it references, paper opens as paper-level judgments and section reads
+/// as content-level ones. Repeated clicks on the same target keep the highest grade.
+pub fn mine_labels(events: &[ActivityEvent], version: u32) -> (GoldenSet, ClickStats) {
+    let mut stats = ClickStats::default();
+    let mut searches: HashMap<String, QueryKey> = HashMap::new();
+
+    for event in events.iter().filter(|e| e.event_type == "search") {
+        stats.searches += 1;
+        let Some(payload) = event
+            .payload
+            .as_deref()
+            .and_then(|p| serde_json::from_str::<SearchPayload>(p).ok())
+        else {
+            continue;
+        };
+        let Some(result_set_id) = payload.result_set_id else {
+            continue;
+        };
+        stats.linked_searches += 1;
+        searches.insert(
+            result_set_id,
+            QueryKey {
+                query: normalize_query(&payload.query),
+                league_filter: payload.league_filter,
+                year_filter: payload.year_filter,
+                team_filter: payload.team_filter,
+                content_type_filter: payload.content_type_filter,
+            },
+        );
+    }
+
+    let mut grades: BTreeMap<QueryKey, BTreeMap<(String, Option<u32>), u32>> = BTreeMap::new();
+    let mut clicked: HashSet<String> = HashSet::new();
+
+    for event in events {
+        let Some(grade) = follow_up_grade(&event.event_type) else {
+            continue;
+        };
+        let Some(payload) = event
+            .payload
+            .as_deref()
+            .and_then(|p| serde_json::from_str::<FollowUpPayload>(p).ok())
+        else {
+            continue;
+        };
+        let Some(result_set_id) = payload.result_set_id else {
+            continue;
+        };
+        stats.follow_ups += 1;
+
+        let Some(key) = searches.get(&result_set_id) else {
+            stats.orphan_follow_ups += 1;
+            continue;
+        };
+        clicked.insert(result_set_id);
+
+        let content_seq = match event.event_type.as_str() {
+            "get_section" => payload.content_seq,
+            _ => None,
+        };
+        let entry = grades
+            .entry(key.clone())
+            .or_default()
+            .entry((payload.paper, content_seq))
+            .or_default();
+        *entry = (*entry).max(grade);
+    }
+    stats.clicked_searches = clicked.len();
+
+    let queries = grades
+        .into_iter()
+        .enumerate()
+        .map(|(i, (key, judgments))| GoldenQuery {
+            id: format!("click-{:04}", i + 1),
+            filter: key.filter(),
+            query: key.query,
+            judgments: judgments
+                .into_iter()
+                .map(|((paper_lyt, content_seq), grade)| Judgment {
+                    paper_lyt,
+                    content_seq,
+                    grade,
+                })
+                .collect(),
+        })
+        .collect();
+
+    let golden = GoldenSet {
+        format_version: GOLDEN_FORMAT_VERSION,
+        version,
+        queries,
+    };
+    (golden, stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn event(event_type: &str, payload: serde_json::Value) -> ActivityEvent {
+        ActivityEvent {
+            id: 0,
+            timestamp: "2025-06-01T12:00:00".to_string(),
+            source: "web".to_string(),
+            event_type: event_type.to_string(),
+            payload: Some(payload.to_string()),
+            api_key_id: None,
+            client_class: None,
+            client_ip: None,
+        }
+    }
+
+    fn search(query: &str, result_set_id: Option<&str>) -> ActivityEvent {
+        event(
+            "search",
+            json!({
+                "type": "search",
+                "query": query,
+                "search_type": "hybrid",
+                "result_count": 2,
+                "league_filter": null,
+                "year_filter": "2024",
+                "team_filter": null,
+                "content_type_filter": null,
+                "result_set_id": result_set_id,
+                "results": [],
+            }),
+        )
+    }
+
+    #[test]
+    fn test_mine_labels_grades_and_merges() {
+        let events = vec![
+            search("Ball  Detection", Some("rs1")),
+            search("ball detection", Some("rs2")),
+            search("kicker", Some("rs3")),
+            search("legacy", None),
+            event(
+                "paper_open",
+                json!({"type": "paper_open", "paper_id": "a", "referrer": null, "result_set_id": "rs1"}),
+            ),
+            event(
+                "pdf_open",
+                json!({"type": "pdf_open", "paper_id": "a", "result_set_id": "rs2"}),
+            ),
+            event(
+                "get_section",
+                json!({"type": "get_section", "paper": "b", "content_seq": 4, "include_children": true, "items_returned": 3, "result_set_id": "rs1"}),
+            ),
+            event(
+                "paper_open",
+                json!({"type": "paper_open", "paper_id": "c", "referrer": null, "result_set_id": "gone"}),
+            ),
+            event(
+                "paper_open",
+                json!({"type": "paper_open", "paper_id": "d", "referrer": null, "result_set_id": null}),
+            ),
+        ];
+
+        let (golden, stats) = mine_labels(&events, 3);
+
+        assert_eq!(
+            stats,
+            ClickStats {
+                searches: 4,
+                linked_searches: 3,
+                clicked_searches: 2,
+                follow_ups: 4,
+                orphan_follow_ups: 1,
+            }
+        );
+        assert!((stats.click_through_rate() - 2.0 / 3.0).abs() < 1e-9);
+
+        assert_eq!(golden.version, 3);
+        assert_eq!(golden.queries.len(), 1);
+        let query = &golden.queries[0];
+        assert_eq!(query.query, "ball detection");
+        assert!(query.filter.is_some());
+
+        let judgments: Vec<_> = query
+            .judgments
+            .iter()
+            .map(|j| (j.paper_lyt.as_str(), j.content_seq, j.grade))
+            .collect();
+        assert_eq!(
+            judgments,
+            vec![("a", None, PDF_OPEN_GRADE), ("b", Some(4), SECTION_GRADE)]
+        );
+    }
+
+    #[test]
+    fn test_mine_labels_output_is_a_valid_golden_set() {
+        let events = vec![
+            search("kicker", Some("rs1")),
+            event(
+                "pdf_open",
+                json!({"type": "pdf_open", "paper_id": "a", "result_set_id": "rs1"}),
+            ),
+        ];
+        let (golden, _) = mine_labels(&events, 1);
+
+        let parsed: GoldenSet =
+            serde_json::from_str(&serde_json::to_string(&golden).unwrap()).unwrap();
+        assert_eq!(parsed.format_version, GOLDEN_FORMAT_VERSION);
+        assert_eq!(parsed.queries[0].id, "click-0001");
+        assert_eq!(parsed.queries[0].judgments[0].grade, PDF_OPEN_GRADE);
+    }
+}
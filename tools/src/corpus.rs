@@ -0,0 +1,345 @@
+//! Portable corpus archive: a gzip-compressed JSONL file whose first line is a [`Manifest`],
+//! followed by one [`Record`] per line. It carries everything needed to rebuild SQLite and
+//! Qdrant on another machine without re-parsing or re-embedding.
+
+use std::io::{BufRead, BufReader, Read, Write};
+
+use data_access::metadata::{MetadataClient, MetadataClientError};
+use data_structures::IDF;
+use data_structures::content::{FrontMatter, MarkdownTDP};
+use data_structures::file::TDPName;
+use data_structures::intermediate::Chunk;
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+
+/// Bumped whenever the record layout changes incompatibly.
+pub const FORMAT_VERSION: u32 = 1;
+
+#[derive(thiserror::Error, Debug)]
+pub enum CorpusError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Invalid archive: {0}")]
+    Invalid(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub format_version: u32,
+    /// Unix timestamp in seconds
+    pub created_at: u64,
+    /// Index version the archive was exported from
+    pub source_index_version: u32,
+    /// Dimension of the dense vectors
+    pub embedding_size: u64,
+    pub papers: usize,
+    pub idf_words: usize,
+    pub chunks: usize,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+enum RecordRef<'a> {
+    Paper(&'a MarkdownTDP),
+    Idf { word: &'a str, index: u32, idf: f32 },
+    Chunk(&'a Chunk),
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Record {
+    Paper(Box<MarkdownTDP>),
+    Idf { word: String, index: u32, idf: f32 },
+    Chunk(Box<Chunk>),
+}
+
+pub struct Corpus {
+    pub papers: Vec<MarkdownTDP>,
+    pub idf: IDF,
+    pub chunks: Vec<Chunk>,
+}
+
+impl Corpus {
+    pub fn manifest(&self, source_index_version: u32, embedding_size: u64) -> Manifest {
+        let created_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+
+        Manifest {
+            format_version: FORMAT_VERSION,
+            created_at,
+            source_index_version,
+            embedding_size,
+            papers: self.papers.len(),
+            idf_words: self.idf.len(),
+            chunks: self.chunks.len(),
+        }
+    }
+}
+
+/// Write the manifest and all records. Records are sorted so the same corpus always produces
+/// the same archive body.
+pub fn write_archive<W: Write>(
+    writer: W,
+    manifest: &Manifest,
+    corpus: &Corpus,
+) -> Result<(), CorpusError> {
+    let mut encoder = GzEncoder::new(writer, Compression::default());
+    write_line(&mut encoder, manifest)?;
+
+    let mut papers: Vec<_> = corpus.papers.iter().collect();
+    papers.sort_by_key(|p| p.name.get_paper_lyt());
+    for paper in papers {
+        write_line(&mut encoder, &RecordRef::Paper(paper))?;
+    }
+
+    let mut idf: Vec<_> = corpus.idf.iter().collect();
+    idf.sort_by_key(|(_, (index, _))| *index);
+    for (word, (index, idf)) in idf {
+        write_line(
+            &mut encoder,
+            &RecordRef::Idf {
+                word,
+                index: *index,
+                idf: *idf,
+            },
+        )?;
+    }
+
+    let mut chunks: Vec<_> = corpus.chunks.iter().collect();
+    chunks.sort_by(|a, b| {
+        (&a.paper_lyt, a.content_seq, a.chunk_seq).cmp(&(&b.paper_lyt, b.content_seq, b.chunk_seq))
+    });
+    for chunk in chunks {
+        write_line(&mut encoder, &RecordRef::Chunk(chunk))?;
+    }
+
+    encoder.finish()?;
+    Ok(())
+}
+
+fn write_line<W: Write, T: Serialize>(writer: &mut W, value: &T) -> Result<(), CorpusError> {
+    serde_json::to_writer(&mut *writer, value)?;
+    writer.write_all(b"\n")?;
+    Ok(())
+}
+
+/// Read and validate an archive. Fails if the format version is unknown, the record counts
+/// do not match the manifest, or a dense vector has the wrong dimension.
+pub fn read_archive<R: Read>(reader: R) -> Result<(Manifest, Corpus), CorpusError> {
+    let mut lines = BufReader::new(GzDecoder::new(reader)).lines();
+
+    let manifest_line = lines
+        .next()
+        .ok_or_else(|| CorpusError::Invalid("archive is empty".to_string()))??;
+    let manifest: Manifest = serde_json::from_str(&manifest_line)?;
+    if manifest.format_version != FORMAT_VERSION {
+        return Err(CorpusError::Invalid(format!(
+            "unsupported format version {} (expected {})",
+            manifest.format_version, FORMAT_VERSION
+        )));
+    }
+
+    let mut corpus = Corpus {
+        papers: Vec::with_capacity(manifest.papers),
+        idf: IDF::new(),
+        chunks: Vec::with_capacity(manifest.chunks),
+    };
+
+    for (i, line) in lines.enumerate() {
+        let line = line?;
+        if line.is_empty() {
+            continue;
+        }
+        let record: Record = serde_json::from_str(&line)
+            .map_err(|e| CorpusError::Invalid(format!("line {}: {}", i + 2, e)))?;
+        match record {
+            Record::Paper(paper) => corpus.papers.push(*paper),
+            Record::Idf { word, index, idf } => {
+                corpus.idf.insert(word, (index, idf));
+            }
+            Record::Chunk(chunk) => {
+                if chunk.dense_embedding.len() as u64 != manifest.embedding_size {
+                    return Err(CorpusError::Invalid(format!(
+                        "chunk {} has a dense vector of size {} (expected {})",
+                        chunk.to_uuid(),
+                        chunk.dense_embedding.len(),
+                        manifest.embedding_size
+                    )));
+                }
+                corpus.chunks.push(*chunk);
+            }
+        }
+    }
+
+    let counts = [
+        ("papers", manifest.papers, corpus.papers.len()),
+        ("IDF words", manifest.idf_words, corpus.idf.len()),
+        ("chunks", manifest.chunks, corpus.chunks.len()),
+    ];
+    for (what, expected, found) in counts {
+        if expected != found {
+            return Err(CorpusError::Invalid(format!(
+                "manifest lists {} {} but the archive contains {}",
+                expected, what, found
+            )));
+        }
+    }
+
+    Ok((manifest, corpus))
+}
+
+/// Reassemble a paper as it was passed to [`MetadataClient::store_paper`].
+pub async fn load_paper(
+    metadata_client: &dyn MetadataClient,
+    name: TDPName,
+) -> Result<MarkdownTDP, MetadataClientError> {
+    let paper_lyt = name.get_paper_lyt();
+
+    let info = metadata_client.load_paper_info(paper_lyt.clone()).await?;
+    let abstract_text = match metadata_client.load_paper_abstract(paper_lyt.clone()).await {
+        Ok(abstract_text) => Some(abstract_text),
+        Err(MetadataClientError::NotFound(_)) => None,
+        Err(e) => return Err(e),
+    };
+    let content_items = metadata_client
+        .load_content_items_range(paper_lyt.clone(), 0, u32::MAX)
+        .await?;
+    let references = metadata_client.load_references(paper_lyt).await?;
+    let raw_markdown = metadata_client.get_tdp_markdown(name.clone()).await?;
+
+    Ok(MarkdownTDP {
+        name,
+        front_matter: FrontMatter {
+            title: info.title,
+            authors: info.authors,
+            institutions: info.institutions,
+            urls: info.urls,
+            abstract_text,
+        },
+        content_items,
+        references,
+        raw_markdown,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data_structures::content::{Author, ContentItem, ContentType};
+    use std::collections::HashMap;
+
+    fn sample_corpus() -> Corpus {
+        let name = TDPName::try_from("soccer_smallsize__2024__RoboTeam_Twente").unwrap();
+        let paper = MarkdownTDP {
+            name: name.clone(),
+            front_matter: FrontMatter {
+                title: "RoboTeam Twente 2024".to_string(),
+                authors: vec![Author {
+                    name: "Jane Doe".to_string(),
+                    affiliation: Some("University of Twente".to_string()),
+                }],
+                institutions: vec!["University of Twente".to_string()],
+                urls: vec![],
+                abstract_text: Some("We describe our robots.".to_string()),
+            },
+            content_items: vec![ContentItem {
+                content_seq: 0,
+                content_type: ContentType::Text,
+                depth: 1,
+                title: "Introduction".to_string(),
+                body: "Our kicker uses a solenoid.".to_string(),
+                image_path: None,
+            }],
+            references: vec!["[1] Some paper".to_string()],
+            raw_markdown: "# Introduction\nOur kicker uses a solenoid.".to_string(),
+        };
+        let chunk = Chunk {
+            dense_embedding: vec![0.1, -0.25, 1.0 / 3.0],
+            sparse_embedding: HashMap::from([(0, 1.5), (7, 0.2)]),
+            paper_lyt: name.get_paper_lyt(),
+            league: name.league,
+            year: name.year,
+            team: name.team_name.clone(),
+            content_seq: 0,
+            chunk_seq: 0,
+            content_type: ContentType::Text,
+            title: "Introduction".to_string(),
+            image_path: None,
+            text: "Our kicker uses a solenoid.".to_string(),
+        };
+
+        Corpus {
+            papers: vec![paper],
+            idf: IDF::from(HashMap::from([
+                ("kicker".to_string(), (0, 1.5)),
+                ("solenoid".to_string(), (7, 2.0)),
+            ])),
+            chunks: vec![chunk],
+        }
+    }
+
+    #[test]
+    fn test_archive_roundtrip() {
+        let corpus = sample_corpus();
+        let manifest = corpus.manifest(3, 3);
+
+        let mut buffer = Vec::new();
+        write_archive(&mut buffer, &manifest, &corpus).unwrap();
+        let (read_manifest, read_corpus) = read_archive(buffer.as_slice()).unwrap();
+
+        assert_eq!(read_manifest, manifest);
+        assert_eq!(read_corpus.idf, corpus.idf);
+
+        let paper = &read_corpus.papers[0];
+        assert_eq!(
+            paper.name.get_paper_lyt(),
+            "soccer_smallsize__2024__RoboTeam_Twente"
+        );
+        assert_eq!(paper.front_matter.authors[0].name, "Jane Doe");
+        assert_eq!(paper.content_items[0].body, "Our kicker uses a solenoid.");
+        assert_eq!(paper.references, vec!["[1] Some paper".to_string()]);
+
+        // Vectors must survive bit-for-bit, otherwise the import would silently degrade search
+        let chunk = &read_corpus.chunks[0];
+        assert_eq!(chunk.dense_embedding, corpus.chunks[0].dense_embedding);
+        assert_eq!(chunk.sparse_embedding, corpus.chunks[0].sparse_embedding);
+        assert_eq!(chunk.to_uuid(), corpus.chunks[0].to_uuid());
+    }
+
+    #[test]
+    fn test_archive_rejects_mismatches() {
+        let corpus = sample_corpus();
+
+        let mut manifest = corpus.manifest(1, 3);
+        manifest.chunks = 2;
+        let mut buffer = Vec::new();
+        write_archive(&mut buffer, &manifest, &corpus).unwrap();
+        assert!(matches!(
+            read_archive(buffer.as_slice()),
+            Err(CorpusError::Invalid(_))
+        ));
+
+        let manifest = corpus.manifest(1, 1024);
+        let mut buffer = Vec::new();
+        write_archive(&mut buffer, &manifest, &corpus).unwrap();
+        assert!(matches!(
+            read_archive(buffer.as_slice()),
+            Err(CorpusError::Invalid(_))
+        ));
+
+        let mut manifest = corpus.manifest(1, 3);
+        manifest.format_version = FORMAT_VERSION + 1;
+        let mut buffer = Vec::new();
+        write_archive(&mut buffer, &manifest, &corpus).unwrap();
+        assert!(matches!(
+            read_archive(buffer.as_slice()),
+            Err(CorpusError::Invalid(_))
+        ));
+    }
+}
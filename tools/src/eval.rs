@@ -0,0 +1,343 @@
+//! Retrieval evaluation: golden sets of graded judgments, IR metrics and run comparison.
+
+use std::collections::BTreeMap;
+
+use data_structures::filter::Filter;
+use serde::{Deserialize, Serialize};
+
+/// Bumped whenever the golden set file layout changes incompatibly.
+pub const GOLDEN_FORMAT_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GoldenSet {
+    pub format_version: u32,
+    /// Version of the judgments themselves. Bump it whenever queries or grades change, so
+    /// runs against different judgments are never compared.
+    pub version: u32,
+    pub queries: Vec<GoldenQuery>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GoldenQuery {
+    pub id: String,
+    pub query: String,
+    #[serde(default)]
+    pub filter: Option<Filter>,
+    pub judgments: Vec<Judgment>,
+}
+
+/// A graded relevance judgment. Without `content_seq`, any chunk of the paper matches.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Judgment {
+    pub paper_lyt: String,
+    #[serde(default)]
+    pub content_seq: Option<u32>,
+    /// 0 = not relevant, 1 = somewhat relevant, 2 = relevant, 3 = highly relevant
+    pub grade: u32,
+}
+
+impl Judgment {
+    fn matches(&self, paper_lyt: &str, content_seq: u32) -> bool {
+        self.paper_lyt == paper_lyt && self.content_seq.is_none_or(|seq| seq == content_seq)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Metrics {
+    pub ndcg: f64,
+    pub mrr: f64,
+    pub recall: f64,
+}
+
+impl Metrics {
+    pub const NAMES: [&'static str; 3] = ["nDCG", "MRR", "recall"];
+
+    pub fn values(&self) -> [f64; 3] {
+        [self.ndcg, self.mrr, self.recall]
+    }
+
+    pub fn mean(metrics: &[Metrics]) -> Metrics {
+        if metrics.is_empty() {
+            return Metrics::default();
+        }
+        let n = metrics.len() as f64;
+        Metrics {
+            ndcg: metrics.iter().map(|m| m.ndcg).sum::<f64>() / n,
+            mrr: metrics.iter().map(|m| m.mrr).sum::<f64>() / n,
+            recall: metrics.iter().map(|m| m.recall).sum::<f64>() / n,
+        }
+    }
+}
+
+/// Score a ranked list of `(paper_lyt, content_seq)` results against the judgments, looking at
+/// the first `k` results. Each judgment is credited at most once, at its highest rank, so
+/// several chunks of the same content item do not inflate the score.
+pub fn evaluate(judgments: &[Judgment], results: &[(String, u32)], k: usize) -> Metrics {
+    let mut credited = vec![false; judgments.len()];
+    let mut dcg = 0.0;
+    let mut first_relevant_rank = None;
+
+    for (rank, (paper_lyt, content_seq)) in results.iter().take(k).enumerate() {
+        // Prefer the highest graded uncredited judgment this result satisfies
+        let best = judgments
+            .iter()
+            .enumerate()
+            .filter(|(i, j)| !credited[*i] && j.grade > 0 && j.matches(paper_lyt, *content_seq))
+            .max_by_key(|(_, j)| j.grade);
+
+        if let Some((i, judgment)) = best {
+            credited[i] = true;
+            dcg += gain(judgment.grade) / (rank as f64 + 2.0).log2();
+            first_relevant_rank.get_or_insert(rank + 1);
+        }
+    }
+
+    let mut ideal_grades: Vec<u32> = judgments
+        .iter()
+        .map(|j| j.grade)
+        .filter(|g| *g > 0)
+        .collect();
+    ideal_grades.sort_unstable_by(|a, b| b.cmp(a));
+    let idcg: f64 = ideal_grades
+        .iter()
+        .take(k)
+        .enumerate()
+        .map(|(rank, grade)| gain(*grade) / (rank as f64 + 2.0).log2())
+        .sum();
+
+    let n_relevant = ideal_grades.len();
+    let n_found = credited.iter().filter(|c| **c).count();
+
+    Metrics {
+        ndcg: if idcg > 0.0 { dcg / idcg } else { 0.0 },
+        mrr: first_relevant_rank.map_or(0.0, |rank| 1.0 / rank as f64),
+        recall: if n_relevant > 0 {
+            n_found as f64 / n_relevant as f64
+        } else {
+            0.0
+        },
+    }
+}
+
+fn gain(grade: u32) -> f64 {
+    2f64.powi(grade as i32) - 1.0
+}
+
+/// Results of one evaluation run, keyed by search mode ("dense", "sparse", "hybrid").
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RunReport {
+    pub golden_version: u32,
+    pub index_version: u32,
+    pub k: usize,
+    /// Unix timestamp in seconds
+    pub created_at: u64,
+    pub modes: BTreeMap<String, ModeReport>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ModeReport {
+    pub mean: Metrics,
+    pub queries: BTreeMap<String, Metrics>,
+}
+
+impl ModeReport {
+    pub fn new(queries: BTreeMap<String, Metrics>) -> Self {
+        let values: Vec<Metrics> = queries.values().copied().collect();
+        Self {
+            mean: Metrics::mean(&values),
+            queries,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Regression {
+    pub mode: String,
+    /// `None` for the mean over all queries
+    pub query_id: Option<String>,
+    pub metric: &'static str,
+    pub baseline: f64,
+    pub candidate: f64,
+}
+
+/// Find every metric that dropped by more than `threshold` from `baseline` to `candidate`,
+/// both for the per-mode means and for individual queries.
+pub fn compare(
+    baseline: &RunReport,
+    candidate: &RunReport,
+    threshold: f64,
+) -> Result<Vec<Regression>, String> {
+    if baseline.golden_version != candidate.golden_version {
+        return Err(format!(
+            "runs use different golden set versions ({} vs {})",
+            baseline.golden_version, candidate.golden_version
+        ));
+    }
+    if baseline.k != candidate.k {
+        return Err(format!(
+            "runs use different k ({} vs {})",
+            baseline.k, candidate.k
+        ));
+    }
+
+    let mut regressions = Vec::new();
+    let mut check = |mode: &str, query_id: Option<&String>, before: &Metrics, after: &Metrics| {
+        for ((metric, b), a) in Metrics::NAMES
+            .iter()
+            .zip(before.values())
+            .zip(after.values())
+        {
+            if b - a > threshold {
+                regressions.push(Regression {
+                    mode: mode.to_string(),
+                    query_id: query_id.cloned(),
+                    metric,
+                    baseline: b,
+                    candidate: a,
+                });
+            }
+        }
+    };
+
+    for (mode, before) in &baseline.modes {
+        let Some(after) = candidate.modes.get(mode) else {
+            continue;
+        };
+        check(mode, None, &before.mean, &after.mean);
+        for (query_id, before_query) in &before.queries {
+            if let Some(after_query) = after.queries.get(query_id) {
+                check(mode, Some(query_id), before_query, after_query);
+            }
+        }
+    }
+
+    Ok(regressions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn judgment(paper_lyt: &str, content_seq: Option<u32>, grade: u32) -> Judgment {
+        Judgment {
+            paper_lyt: paper_lyt.to_string(),
+            content_seq,
+            grade,
+        }
+    }
+
+    fn results(items: &[(&str, u32)]) -> Vec<(String, u32)> {
+        items.iter().map(|(p, s)| (p.to_string(), *s)).collect()
+    }
+
+    #[test]
+    fn test_evaluate_perfect_ranking() {
+        let judgments = vec![judgment("a", Some(1), 3), judgment("b", Some(2), 1)];
+        let m = evaluate(&judgments, &results(&[("a", 1), ("b", 2)]), 10);
+        assert!((m.ndcg - 1.0).abs() < 1e-9);
+        assert_eq!(m.mrr, 1.0);
+        assert_eq!(m.recall, 1.0);
+    }
+
+    #[test]
+    fn test_evaluate_graded_and_truncated() {
+        let judgments = vec![judgment("a", Some(1), 3), judgment("b", Some(2), 1)];
+
+        // Swapped order: DCG = 1/log2(2) + 7/log2(3), IDCG = 7/log2(2) + 1/log2(3)
+        let m = evaluate(&judgments, &results(&[("b", 2), ("a", 1)]), 10);
+        let expected = (1.0 + 7.0 / 3f64.log2()) / (7.0 + 1.0 / 3f64.log2());
+        assert!((m.ndcg - expected).abs() < 1e-9);
+        assert_eq!(m.mrr, 1.0);
+
+        // Only the first result counts at k = 1
+        let m = evaluate(&judgments, &results(&[("x", 0), ("a", 1)]), 1);
+        assert_eq!(m.ndcg, 0.0);
+        assert_eq!(m.mrr, 0.0);
+        assert_eq!(m.recall, 0.0);
+
+        let m = evaluate(&judgments, &results(&[("x", 0), ("a", 1)]), 10);
+        assert_eq!(m.mrr, 0.5);
+        assert_eq!(m.recall, 0.5);
+    }
+
+    #[test]
+    fn test_evaluate_paper_level_and_duplicates() {
+        // A paper-level judgment matches any chunk of the paper, but only once
+        let judgments = vec![judgment("a", None, 2)];
+        let m = evaluate(&judgments, &results(&[("a", 4), ("a", 7)]), 10);
+        assert!((m.ndcg - 1.0).abs() < 1e-9);
+        assert_eq!(m.recall, 1.0);
+
+        // No relevant judgments at all
+        let m = evaluate(&[judgment("a", None, 0)], &results(&[("a", 1)]), 10);
+        assert_eq!(m, Metrics::default());
+    }
+
+    #[test]
+    fn test_example_golden_set_parses() {
+        let golden: GoldenSet =
+            serde_json::from_str(include_str!("../../eval/golden.example.json")).unwrap();
+        assert_eq!(golden.format_version, GOLDEN_FORMAT_VERSION);
+        assert!(golden.queries.iter().all(|q| !q.judgments.is_empty()));
+        assert!(golden.queries[0].filter.is_some());
+    }
+
+    fn report(golden_version: u32, hybrid_q1: Metrics) -> RunReport {
+        RunReport {
+            golden_version,
+            index_version: 1,
+            k: 10,
+            created_at: 0,
+            modes: BTreeMap::from([(
+                "hybrid".to_string(),
+                ModeReport::new(BTreeMap::from([
+                    ("q1".to_string(), hybrid_q1),
+                    (
+                        "q2".to_string(),
+                        Metrics {
+                            ndcg: 0.5,
+                            mrr: 0.5,
+                            recall: 0.5,
+                        },
+                    ),
+                ])),
+            )]),
+        }
+    }
+
+    #[test]
+    fn test_compare_flags_regressions() {
+        let good = Metrics {
+            ndcg: 0.9,
+            mrr: 1.0,
+            recall: 1.0,
+        };
+        let worse = Metrics {
+            ndcg: 0.6,
+            mrr: 1.0,
+            recall: 0.99,
+        };
+
+        let regressions = compare(&report(1, good), &report(1, worse), 0.02).unwrap();
+        assert_eq!(regressions.len(), 2);
+        assert!(regressions.contains(&Regression {
+            mode: "hybrid".to_string(),
+            query_id: Some("q1".to_string()),
+            metric: "nDCG",
+            baseline: 0.9,
+            candidate: 0.6,
+        }));
+        assert!(
+            regressions
+                .iter()
+                .any(|r| r.query_id.is_none() && r.metric == "nDCG")
+        );
+
+        assert!(
+            compare(&report(1, worse), &report(1, good), 0.02)
+                .unwrap()
+                .is_empty()
+        );
+        assert!(compare(&report(1, good), &report(2, good), 0.02).is_err());
+    }
+}
@@ -1,3 +1,8 @@
+pub mod activity_reports;
+pub mod click_labels;
+pub mod corpus;
+pub mod eval;
+
 use data_access::registry::RegistryEntry;
 use data_structures::file::TeamName;
 
@@ -1,3 +1,4 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -10,3 +11,9 @@ impl<T> ApiResponse<T> {
         Self { data }
     }
 }
+
+/// Query string of follow-up requests that originate from a search result page.
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct ResultSetQuery {
+    pub result_set_id: Option<String>,
+}
@@ -1,3 +1,4 @@
+use api::error::ErrorCode;
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use axum::Json;
@@ -6,46 +7,68 @@ use serde_json::json;
 #[derive(Debug)]
 pub struct ApiError {
     pub status: StatusCode,
+    pub code: &'static str,
     pub message: String,
+    /// Argument the error is about, for invalid arguments and filters
+    pub field: Option<String>,
 }
 
 impl ApiError {
+    /// An error with the default code for its status
     pub fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        let code = match status {
+            StatusCode::BAD_REQUEST => ErrorCode::InvalidArgument.as_str(),
+            StatusCode::UNAUTHORIZED => ErrorCode::Unauthorized.as_str(),
+            StatusCode::FORBIDDEN => ErrorCode::Forbidden.as_str(),
+            StatusCode::NOT_FOUND => ErrorCode::NotFound.as_str(),
+            StatusCode::TOO_MANY_REQUESTS => ErrorCode::RateLimited.as_str(),
+            StatusCode::SERVICE_UNAVAILABLE => ErrorCode::UpstreamUnavailable.as_str(),
+            _ => ErrorCode::Internal.as_str(),
+        };
         Self {
             status,
+            code,
             message: message.into(),
+            field: None,
         }
     }
 
-    pub fn internal_server_error(message: impl Into<String>) -> Self {
-        Self::new(StatusCode::INTERNAL_SERVER_ERROR, message)
-    }
-
-    pub fn bad_request(message: impl Into<String>) -> Self {
-        Self::new(StatusCode::BAD_REQUEST, message)
-    }
-
     pub fn not_found(message: impl Into<String>) -> Self {
         Self::new(StatusCode::NOT_FOUND, message)
     }
-
-    pub fn forbidden(message: impl Into<String>) -> Self {
-        Self::new(StatusCode::FORBIDDEN, message)
-    }
 }
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
-        let body = Json(json!({
+        let mut body = json!({
             "error": self.message,
-        }));
+            "code": self.code,
+        });
+        if let Some(field) = self.field {
+            body["field"] = json!(field);
+        }
 
-        (self.status, body).into_response()
+        (self.status, Json(body)).into_response()
     }
 }
 
 impl From<api::error::ApiError> for ApiError {
     fn from(err: api::error::ApiError) -> Self {
-        Self::internal_server_error(err.to_string())
+        let code = err.code();
+        let status = match code {
+            ErrorCode::InvalidArgument | ErrorCode::InvalidFilter => StatusCode::BAD_REQUEST,
+            ErrorCode::PaperNotFound | ErrorCode::NotFound => StatusCode::NOT_FOUND,
+            ErrorCode::Unauthorized => StatusCode::UNAUTHORIZED,
+            ErrorCode::Forbidden => StatusCode::FORBIDDEN,
+            ErrorCode::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+            ErrorCode::UpstreamUnavailable => StatusCode::SERVICE_UNAVAILABLE,
+            ErrorCode::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        Self {
+            status,
+            code: code.as_str(),
+            message: err.to_string(),
+            field: err.field().map(str::to_string),
+        }
     }
 }
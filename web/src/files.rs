@@ -0,0 +1,263 @@
+//! Streaming file responses for the PDF and TDP routes, with byte ranges so PDF viewers can
+//! load page by page, and ETag/Last-Modified validators for conditional GET.
+
+use std::io::SeekFrom;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use axum::body::Body;
+use axum::http::{HeaderMap, HeaderValue, StatusCode, header};
+use axum::response::Response;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tokio_util::io::ReaderStream;
+
+#[derive(Debug, PartialEq, Eq)]
+enum ByteRange {
+    /// No usable range; serve the whole file
+    Full,
+    /// First and last byte, inclusive
+    Partial(u64, u64),
+    Unsatisfiable,
+}
+
+/// Parse a `Range` header for a file of `len` bytes. Only single ranges are supported; for
+/// anything else the whole file is served, which RFC 9110 allows.
+fn parse_range(range: &str, len: u64) -> ByteRange {
+    let Some(spec) = range.trim().strip_prefix("bytes=") else {
+        return ByteRange::Full;
+    };
+    if spec.contains(',') {
+        return ByteRange::Full;
+    }
+    let Some((start, end)) = spec.trim().split_once('-') else {
+        return ByteRange::Full;
+    };
+
+    match (start.parse::<u64>(), end.parse::<u64>()) {
+        // bytes=-500: the last 500 bytes
+        (Err(_), Ok(suffix)) if start.is_empty() => {
+            if suffix == 0 || len == 0 {
+                ByteRange::Unsatisfiable
+            } else {
+                ByteRange::Partial(len.saturating_sub(suffix), len - 1)
+            }
+        }
+        // bytes=500-: from byte 500 to the end
+        (Ok(start), Err(_)) if end.is_empty() => {
+            if start >= len {
+                ByteRange::Unsatisfiable
+            } else {
+                ByteRange::Partial(start, len - 1)
+            }
+        }
+        (Ok(start), Ok(end)) if start <= end => {
+            if start >= len {
+                ByteRange::Unsatisfiable
+            } else {
+                ByteRange::Partial(start, end.min(len - 1))
+            }
+        }
+        _ => ByteRange::Full,
+    }
+}
+
+/// Whether an `If-None-Match` list contains `etag`, using the weak comparison
+fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    let etag = etag.trim_start_matches("W/");
+    if_none_match
+        .split(',')
+        .map(|t| t.trim().trim_start_matches("W/"))
+        .any(|t| t == "*" || t == etag)
+}
+
+fn content_type(path: &Path) -> HeaderValue {
+    let mime = mime_guess::from_path(path).first_or_octet_stream();
+    let value = if mime.type_() == mime_guess::mime::TEXT {
+        format!("{}; charset=utf-8", mime.essence_str())
+    } else {
+        mime.essence_str().to_string()
+    };
+    HeaderValue::from_str(&value)
+        .unwrap_or_else(|_| HeaderValue::from_static("application/octet-stream"))
+}
+
+fn header_str(headers: &HeaderMap, name: header::HeaderName) -> Option<&str> {
+    headers.get(name).and_then(|v| v.to_str().ok())
+}
+
+/// Stream the file at `path`, which the caller has already checked lies under its root.
+/// Answers 304 when the client's copy is current, and 206 or 416 for `Range` requests.
+pub async fn serve_file(
+    path: &Path,
+    headers: &HeaderMap,
+    cache_control: &str,
+) -> Result<Response<Body>, StatusCode> {
+    let mut file = tokio::fs::File::open(path)
+        .await
+        .map_err(|_| StatusCode::NOT_FOUND)?;
+    let metadata = file.metadata().await.map_err(|_| StatusCode::NOT_FOUND)?;
+    if !metadata.is_file() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    let len = metadata.len();
+    let modified = metadata.modified().unwrap_or(UNIX_EPOCH);
+    let modified_nanos = modified
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+
+    let etag = format!("\"{:x}-{:x}\"", len, modified_nanos);
+    let last_modified = httpdate::fmt_http_date(modified);
+
+    let mut builder = Response::builder()
+        .header(header::ETAG, &etag)
+        .header(header::LAST_MODIFIED, &last_modified)
+        .header(header::ACCEPT_RANGES, "bytes")
+        .header(header::CACHE_CONTROL, cache_control);
+
+    // If-None-Match takes precedence over If-Modified-Since
+    let not_modified = match header_str(headers, header::IF_NONE_MATCH) {
+        Some(if_none_match) => etag_matches(if_none_match, &etag),
+        None => header_str(headers, header::IF_MODIFIED_SINCE)
+            .and_then(|since| httpdate::parse_http_date(since).ok())
+            .is_some_and(|since| truncate_to_seconds(modified) <= since),
+    };
+    if not_modified {
+        return builder
+            .status(StatusCode::NOT_MODIFIED)
+            .body(Body::empty())
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    builder = builder.header(header::CONTENT_TYPE, content_type(path));
+
+    // A range only applies if the client's copy is still the one it names in If-Range
+    let range_applies = header_str(headers, header::IF_RANGE)
+        .is_none_or(|if_range| if_range == etag || if_range == last_modified);
+    let range = match header_str(headers, header::RANGE) {
+        Some(range) if range_applies => parse_range(range, len),
+        _ => ByteRange::Full,
+    };
+
+    let response = match range {
+        ByteRange::Full => builder
+            .status(StatusCode::OK)
+            .header(header::CONTENT_LENGTH, len)
+            .body(Body::from_stream(ReaderStream::new(file))),
+        ByteRange::Partial(start, end) => {
+            file.seek(SeekFrom::Start(start))
+                .await
+                .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+            let length = end - start + 1;
+            builder
+                .status(StatusCode::PARTIAL_CONTENT)
+                .header(
+                    header::CONTENT_RANGE,
+                    format!("bytes {}-{}/{}", start, end, len),
+                )
+                .header(header::CONTENT_LENGTH, length)
+                .body(Body::from_stream(ReaderStream::new(file.take(length))))
+        }
+        ByteRange::Unsatisfiable => builder
+            .status(StatusCode::RANGE_NOT_SATISFIABLE)
+            .header(header::CONTENT_RANGE, format!("bytes */{}", len))
+            .body(Body::empty()),
+    };
+    response.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+/// HTTP dates have second precision
+fn truncate_to_seconds(time: SystemTime) -> SystemTime {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    UNIX_EPOCH + std::time::Duration::from_secs(secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range("bytes=0-99", 1000), ByteRange::Partial(0, 99));
+        assert_eq!(
+            parse_range("bytes=900-", 1000),
+            ByteRange::Partial(900, 999)
+        );
+        assert_eq!(
+            parse_range("bytes=-100", 1000),
+            ByteRange::Partial(900, 999)
+        );
+        assert_eq!(parse_range("bytes=-5000", 1000), ByteRange::Partial(0, 999));
+        assert_eq!(
+            parse_range("bytes=500-5000", 1000),
+            ByteRange::Partial(500, 999)
+        );
+        assert_eq!(parse_range("bytes=1000-", 1000), ByteRange::Unsatisfiable);
+        assert_eq!(parse_range("bytes=-0", 1000), ByteRange::Unsatisfiable);
+        assert_eq!(parse_range("bytes=0-9,20-29", 1000), ByteRange::Full);
+        assert_eq!(parse_range("bytes=9-0", 1000), ByteRange::Full);
+        assert_eq!(parse_range("items=0-9", 1000), ByteRange::Full);
+    }
+
+    #[test]
+    fn test_etag_matches() {
+        assert!(etag_matches("\"a-1\"", "\"a-1\""));
+        assert!(etag_matches("\"b-2\", W/\"a-1\"", "\"a-1\""));
+        assert!(etag_matches("*", "\"a-1\""));
+        assert!(!etag_matches("\"a-2\"", "\"a-1\""));
+    }
+
+    #[tokio::test]
+    async fn test_serve_file() {
+        let mut file = tempfile::Builder::new().suffix(".pdf").tempfile().unwrap();
+        file.write_all(b"0123456789").unwrap();
+        let path = file.path();
+
+        let response = serve_file(path, &HeaderMap::new(), "public, max-age=60")
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/pdf");
+        assert_eq!(
+            response.headers()[header::CACHE_CONTROL],
+            "public, max-age=60"
+        );
+        let etag = response.headers()[header::ETAG].clone();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"0123456789");
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::RANGE, HeaderValue::from_static("bytes=2-4"));
+        let response = serve_file(path, &headers, "no-cache").await.unwrap();
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(response.headers()[header::CONTENT_RANGE], "bytes 2-4/10");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"234");
+
+        // A range for a different version of the file gets the whole file
+        headers.insert(header::IF_RANGE, HeaderValue::from_static("\"stale\""));
+        let response = serve_file(path, &headers, "no-cache").await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_NONE_MATCH, etag);
+        let response = serve_file(path, &headers, "no-cache").await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+        let missing = path.with_extension("missing");
+        assert_eq!(
+            serve_file(&missing, &HeaderMap::new(), "no-cache")
+                .await
+                .unwrap_err(),
+            StatusCode::NOT_FOUND
+        );
+    }
+}
@@ -0,0 +1,40 @@
+//! REST API, TDP/PDF file routes and the static frontend, served by `serve`
+
+use std::sync::Arc;
+
+use axum::Router;
+use configuration::AppConfig;
+use configuration::bootstrap::Services;
+
+mod dto;
+mod error;
+mod files;
+mod middleware;
+mod openapi;
+mod routes;
+mod state;
+
+use state::AppState;
+
+/// The web frontend over the shared services
+pub fn router(services: &Services, config: &AppConfig) -> Router {
+    let state = AppState::new(
+        services.metadata_client.clone(),
+        services.searcher.clone(),
+        services.dispatcher.clone(),
+        config.data_processing.tdps_markdown_root.clone(),
+        config.data_processing.tdps_pdf_root.clone(),
+        services.registry.clone(),
+        config
+            .rate_limit
+            .as_ref()
+            .map(|c| Arc::new(api::rate_limit::RateLimiter::new(c))),
+        services.api_keys.clone(),
+        Arc::new(api::crawler::ClientClassifier::new(
+            &config.crawlers.clone().unwrap_or_default(),
+        )),
+        config.files.clone().unwrap_or_default(),
+    );
+
+    routes::create_router(state, services.clone())
+}
@@ -1,11 +1,57 @@
+use api::auth::{AuthError, authenticate, bearer_token, seconds_until_quota_reset};
+use api::crawler::ClientClass;
+use api::rate_limit::{ClientId, RouteClass, client_ip};
 use axum::body::Body;
-use axum::http::{Request, header};
+use axum::extract::{ConnectInfo, MatchedPath};
+use axum::http::{HeaderValue, Method, Request, StatusCode, header};
 use axum::middleware::Next;
-use axum::response::Response;
+use axum::response::{IntoResponse, Response};
+use configuration::CrawlerPolicy;
+use configuration::telemetry;
+use event_processing::EventContext;
+use std::net::SocketAddr;
 use std::time::Instant;
+use tracing::Instrument;
 
+use crate::error::ApiError;
 use crate::state::AppState;
 
+/// The proxy-reported client IP, or the socket address for clients connecting directly
+fn request_ip(request: &Request<Body>) -> Option<String> {
+    let headers = request.headers();
+    client_ip(
+        headers.get("x-forwarded-for").and_then(|v| v.to_str().ok()),
+        headers.get("x-real-ip").and_then(|v| v.to_str().ok()),
+        request
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip()),
+    )
+}
+
+/// Route class a request is rate limited under
+fn route_class(method: &Method, path: &str) -> RouteClass {
+    match path {
+        "/api/search" | "/api/grep" => RouteClass::Search,
+        "/api/suggestion" => RouteClass::Write,
+        _ if path.starts_with("/api/registry/") && method == Method::POST => RouteClass::Write,
+        _ => RouteClass::Content,
+    }
+}
+
+/// Whether a request counts towards the scraper rate. API calls and pages do; TDP files,
+/// figures, PDFs and static assets are loaded along with a page and do not.
+fn counts_toward_scraper_rate(path: &str) -> bool {
+    if ["/tdps/", "/images/", "/pdfs/"]
+        .iter()
+        .any(|prefix| path.starts_with(prefix))
+    {
+        return false;
+    }
+    let last_segment = path.rsplit('/').next().unwrap_or("");
+    path.starts_with("/api") || !last_segment.contains('.')
+}
+
 pub async fn activity_logging(
     axum::extract::State(state): axum::extract::State<AppState>,
     request: Request<Body>,
@@ -19,19 +65,7 @@ pub async fn activity_logging(
         .and_then(|v| v.to_str().ok())
         .unwrap_or("")
         .to_string();
-    let ip = request
-        .headers()
-        .get("x-forwarded-for")
-        .and_then(|v| v.to_str().ok())
-        .and_then(|s| s.split(',').next())
-        .map(|s| s.trim().to_string())
-        .or_else(|| {
-            request
-                .headers()
-                .get("x-real-ip")
-                .and_then(|v| v.to_str().ok())
-                .map(|s| s.to_string())
-        });
+    let ip = request_ip(&request);
 
     let start = Instant::now();
     let response = next.run(request).await;
@@ -53,3 +87,271 @@ pub async fn activity_logging(
 
     response
 }
+
+/// Count requests and record their latency per matched route. Requests no route matched, such
+/// as static files, share one label so arbitrary paths cannot blow up the label set.
+pub async fn track_metrics(request: Request<Body>, next: Next) -> Response {
+    let route = route_label(request.extensions().get::<MatchedPath>());
+    let method = request.method().to_string();
+
+    let start = Instant::now();
+    let response = next.run(request).await;
+    let duration = start.elapsed().as_secs_f64();
+
+    let status = response.status().as_u16().to_string();
+    metrics::counter!(
+        "tdp_http_requests_total",
+        "route" => route.clone(),
+        "method" => method.clone(),
+        "status" => status
+    )
+    .increment(1);
+    metrics::histogram!(
+        "tdp_http_request_duration_seconds",
+        "route" => route,
+        "method" => method
+    )
+    .record(duration);
+
+    response
+}
+
+/// Run the request in a span named after its route and return the trace ID in `x-trace-id`,
+/// so a slow request can be looked up in the collector
+pub async fn trace_request(request: Request<Body>, next: Next) -> Response {
+    let route = route_label(request.extensions().get::<MatchedPath>());
+    let span = telemetry::request_span(request.method(), &route, request.headers());
+
+    let mut response = next.run(request).instrument(span.clone()).await;
+
+    span.record("http.response.status_code", response.status().as_u16());
+    if let Some(trace_id) =
+        telemetry::trace_id(&span).and_then(|trace_id| HeaderValue::from_str(&trace_id).ok())
+    {
+        response.headers_mut().insert("x-trace-id", trace_id);
+    }
+    response
+}
+
+fn route_label(matched: Option<&MatchedPath>) -> String {
+    matched
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| "static".to_string())
+}
+
+/// Authenticate `Authorization: Bearer` API keys. Requests without one stay anonymous. A valid
+/// key is attached to the request, so the rate limiter counts it per key, and to every event
+/// dispatched while handling it.
+pub async fn api_key_auth(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    mut request: Request<Body>,
+    next: Next,
+) -> Response {
+    let Some(ref api_keys) = state.api_keys else {
+        return next.run(request).await;
+    };
+    let authorization = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok());
+    let Some(token) = bearer_token(authorization).map(str::to_string) else {
+        return next.run(request).await;
+    };
+
+    let path = request.uri().path().to_string();
+    let class = route_class(request.method(), &path);
+
+    match authenticate(api_keys.as_ref(), &token, Some(class)).await {
+        Ok(key) => {
+            request
+                .extensions_mut()
+                .insert(ClientId::ApiKey(key.id.clone()));
+            let context = EventContext {
+                api_key_id: Some(key.id),
+                ..EventContext::current()
+            };
+            context.scope(next.run(request)).await
+        }
+        Err(AuthError::InvalidKey) => {
+            let mut response =
+                ApiError::new(StatusCode::UNAUTHORIZED, AuthError::InvalidKey.to_string())
+                    .into_response();
+            response
+                .headers_mut()
+                .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+            response
+        }
+        Err(e @ AuthError::QuotaExceeded { .. }) => {
+            let retry_after_secs = seconds_until_quota_reset();
+            state.dispatcher.dispatch(
+                event_processing::EventSource::Web,
+                event_processing::Event::Throttled(event_processing::ThrottledEvent {
+                    route_class: "quota".to_string(),
+                    path,
+                    ip: request_ip(&request),
+                    retry_after_secs,
+                }),
+            );
+
+            let mut response =
+                ApiError::new(StatusCode::TOO_MANY_REQUESTS, e.to_string()).into_response();
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(retry_after_secs));
+            response
+        }
+        Err(e) => {
+            tracing::error!("API key check failed: {}", e);
+            ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, "Failed to check API key")
+                .into_response()
+        }
+    }
+}
+
+/// Tag the request with its crawler `ClientClass` and record the class on every event
+/// dispatched while handling it. Runs after `api_key_auth`, since key holders are classified
+/// more leniently.
+pub async fn classify_client(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    mut request: Request<Body>,
+    next: Next,
+) -> Response {
+    let user_agent = request
+        .headers()
+        .get(header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    let ip = request_ip(&request);
+    let has_api_key = matches!(
+        request.extensions().get::<ClientId>(),
+        Some(ClientId::ApiKey(_))
+    );
+    let path = request.uri().path();
+    let class = state.client_classifier.classify(
+        user_agent,
+        path,
+        ip.as_deref(),
+        has_api_key,
+        counts_toward_scraper_rate(path),
+    );
+    request.extensions_mut().insert(class);
+
+    let context = EventContext {
+        client_class: Some(class.as_str().to_string()),
+        client_ip: ip,
+        exclude_from_analytics: state.client_classifier.policy(class) == CrawlerPolicy::Exclude,
+        ..EventContext::current()
+    };
+    context.scope(next.run(request)).await
+}
+
+/// Enforce the crawler policy for the request's class. Runs inside `activity_logging`, so
+/// blocked requests still show up in the activity log.
+pub async fn crawler_policy(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let Some(class) = request.extensions().get::<ClientClass>().copied() else {
+        return next.run(request).await;
+    };
+
+    match state.client_classifier.policy(class) {
+        CrawlerPolicy::Block => ApiError::new(
+            StatusCode::FORBIDDEN,
+            format!("Automated access by {} clients is not allowed", class.as_str()),
+        )
+        .into_response(),
+        CrawlerPolicy::Slow => {
+            tokio::time::sleep(state.client_classifier.slow_down()).await;
+            next.run(request).await
+        }
+        CrawlerPolicy::Allow | CrawlerPolicy::Exclude => next.run(request).await,
+    }
+}
+
+pub async fn rate_limit(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let Some(ref limiter) = state.rate_limiter else {
+        return next.run(request).await;
+    };
+
+    let ip = request_ip(&request);
+    let client = match request.extensions().get::<ClientId>() {
+        Some(client) => client.clone(),
+        None => ip.clone().map_or(ClientId::Unknown, ClientId::Ip),
+    };
+    let path = request.uri().path().to_string();
+    let class = route_class(request.method(), &path);
+
+    let Err(retry_after) = limiter.check(class, &client) else {
+        return next.run(request).await;
+    };
+    let retry_after_secs = retry_after.as_secs_f64().ceil() as u64;
+
+    state.dispatcher.dispatch(
+        event_processing::EventSource::Web,
+        event_processing::Event::Throttled(event_processing::ThrottledEvent {
+            route_class: class.as_str().to_string(),
+            path,
+            ip,
+            retry_after_secs,
+        }),
+    );
+
+    let mut response = ApiError::new(
+        StatusCode::TOO_MANY_REQUESTS,
+        format!(
+            "Too many {} requests, retry in {}s",
+            class.as_str(),
+            retry_after_secs
+        ),
+    )
+    .into_response();
+    response
+        .headers_mut()
+        .insert(header::RETRY_AFTER, HeaderValue::from(retry_after_secs));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_route_class() {
+        assert_eq!(route_class(&Method::GET, "/api/search"), RouteClass::Search);
+        assert_eq!(route_class(&Method::GET, "/api/grep"), RouteClass::Search);
+        assert_eq!(
+            route_class(&Method::POST, "/api/registry/team"),
+            RouteClass::Write
+        );
+        assert_eq!(
+            route_class(&Method::POST, "/api/suggestion"),
+            RouteClass::Write
+        );
+        assert_eq!(
+            route_class(&Method::GET, "/api/registry/team/RoboTeam"),
+            RouteClass::Content
+        );
+        assert_eq!(
+            route_class(&Method::GET, "/pdfs/a.pdf"),
+            RouteClass::Content
+        );
+    }
+
+    #[test]
+    fn test_counts_toward_scraper_rate() {
+        assert!(counts_toward_scraper_rate("/api/search"));
+        assert!(counts_toward_scraper_rate("/api/papers/info"));
+        assert!(counts_toward_scraper_rate("/"));
+        assert!(counts_toward_scraper_rate("/paper/some_paper"));
+        assert!(!counts_toward_scraper_rate("/images/soccer/a.webp"));
+        assert!(!counts_toward_scraper_rate("/tdps/soccer/2024/team.md"));
+        assert!(!counts_toward_scraper_rate("/pdfs/soccer/2024/team.pdf"));
+        assert!(!counts_toward_scraper_rate("/assets/index-3f2a.js"));
+    }
+}
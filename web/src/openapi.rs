@@ -0,0 +1,371 @@
+//! OpenAPI 3 document for the web API. Routes are registered through `ApiRouter`, which adds
+//! each handler to the axum router and its schemars types to the document, so the published
+//! spec cannot drift from the routes that are actually served.
+
+use axum::Router;
+use axum::routing::MethodRouter;
+use schemars::generate::{SchemaGenerator, SchemaSettings};
+use schemars::{JsonSchema, Schema};
+use serde_json::{Map, Value, json};
+
+type SchemaFn = fn(&mut SchemaGenerator) -> Schema;
+
+/// Method, summary and the types of the inputs and output of a single route
+pub struct Operation {
+    method: &'static str,
+    path: &'static str,
+    summary: &'static str,
+    path_params: Vec<(&'static str, &'static str, SchemaFn)>,
+    query: Option<SchemaFn>,
+    body: Option<SchemaFn>,
+    response: Option<SchemaFn>,
+}
+
+impl Operation {
+    fn new(method: &'static str, path: &'static str, summary: &'static str) -> Self {
+        Self {
+            method,
+            path,
+            summary,
+            path_params: Vec::new(),
+            query: None,
+            body: None,
+            response: None,
+        }
+    }
+
+    pub fn get(path: &'static str, summary: &'static str) -> Self {
+        Self::new("get", path, summary)
+    }
+
+    pub fn post(path: &'static str, summary: &'static str) -> Self {
+        Self::new("post", path, summary)
+    }
+
+    /// Type and description of a path parameter. Parameters not listed are plain strings.
+    pub fn path_param<T: JsonSchema>(
+        mut self,
+        name: &'static str,
+        description: &'static str,
+    ) -> Self {
+        self.path_params
+            .push((name, description, SchemaGenerator::subschema_for::<T>));
+        self
+    }
+
+    /// Query string struct; each of its fields becomes a query parameter
+    pub fn query<T: JsonSchema>(mut self) -> Self {
+        self.query = Some(SchemaGenerator::subschema_for::<T>);
+        self
+    }
+
+    /// JSON request body
+    pub fn body<T: JsonSchema>(mut self) -> Self {
+        self.body = Some(SchemaGenerator::subschema_for::<T>);
+        self
+    }
+
+    /// Type of `data` in the `ApiResponse` envelope. Routes without one answer 204 No Content.
+    pub fn response<T: JsonSchema>(mut self) -> Self {
+        self.response = Some(SchemaGenerator::subschema_for::<T>);
+        self
+    }
+}
+
+/// A `Router` that documents every route added to it
+pub struct ApiRouter<S> {
+    router: Router<S>,
+    /// Generator for request types, which are read with the deserialize contract
+    input: SchemaGenerator,
+    /// Generator for response types, which are written with the serialize contract
+    output: SchemaGenerator,
+    paths: Map<String, Value>,
+}
+
+impl<S: Clone + Send + Sync + 'static> ApiRouter<S> {
+    pub fn new() -> Self {
+        Self {
+            router: Router::new(),
+            input: SchemaSettings::openapi3()
+                .for_deserialize()
+                .into_generator(),
+            output: SchemaSettings::openapi3().for_serialize().into_generator(),
+            paths: Map::new(),
+        }
+    }
+
+    pub fn route(mut self, operation: Operation, method_router: MethodRouter<S>) -> Self {
+        self.router = self.router.route(operation.path, method_router);
+        self.document(operation);
+        self
+    }
+
+    /// The router and the OpenAPI document describing it
+    pub fn finish(mut self) -> (Router<S>, Value) {
+        let mut schemas = self.input.take_definitions(true);
+        schemas.extend(self.output.take_definitions(true));
+        schemas.insert(
+            "Error".to_string(),
+            json!({
+                "type": "object",
+                "required": ["error", "code"],
+                "properties": {
+                    "error": { "type": "string" },
+                    "code": {
+                        "type": "string",
+                        "description": "Machine-readable error code, e.g. `invalid_filter`, `paper_not_found`, `unauthorized` or `upstream_unavailable`"
+                    },
+                    "field": {
+                        "type": "string",
+                        "description": "Argument the error is about, for `invalid_argument` and `invalid_filter`"
+                    }
+                }
+            }),
+        );
+
+        let document = json!({
+            "openapi": "3.0.3",
+            "info": {
+                "title": "TDP Search API",
+                "version": env!("CARGO_PKG_VERSION"),
+                "description": "Search and browse RoboCup Team Description Papers. Successful responses wrap their payload in `{\"data\": ...}`, errors are `{\"error\": \"...\", \"code\": \"...\"}` with a status code matching the error code."
+            },
+            "paths": self.paths,
+            "components": {
+                "schemas": schemas,
+                "securitySchemes": {
+                    "bearerAuth": {
+                        "type": "http",
+                        "scheme": "bearer",
+                        "description": "Optional API key issued with the api_keys tool. Requests without one are served anonymously."
+                    }
+                }
+            },
+            "security": [{}, { "bearerAuth": [] }]
+        });
+        (self.router, document)
+    }
+
+    fn document(&mut self, operation: Operation) {
+        let mut parameters = Vec::new();
+        for name in path_param_names(operation.path) {
+            let param = operation.path_params.iter().find(|(n, _, _)| *n == name);
+            let mut parameter = json!({
+                "name": name,
+                "in": "path",
+                "required": true,
+                "schema": match param {
+                    Some((_, _, schema_fn)) => transformed(&mut self.input, *schema_fn),
+                    None => json!({ "type": "string" }),
+                }
+            });
+            if let Some((_, description, _)) = param {
+                parameter["description"] = json!(description);
+            }
+            parameters.push(parameter);
+        }
+        if let Some(query) = operation.query {
+            parameters.extend(query_parameters(&mut self.input, query));
+        }
+
+        let mut responses = Map::new();
+        match operation.response {
+            Some(schema_fn) => {
+                let data = transformed(&mut self.output, schema_fn);
+                responses.insert(
+                    "200".to_string(),
+                    json!({
+                        "description": "OK",
+                        "content": { "application/json": { "schema": {
+                            "type": "object",
+                            "required": ["data"],
+                            "properties": { "data": data }
+                        } } }
+                    }),
+                );
+            }
+            None => {
+                responses.insert("204".to_string(), json!({ "description": "No Content" }));
+            }
+        }
+        responses.insert(
+            "default".to_string(),
+            json!({
+                "description": "Error",
+                "content": { "application/json": { "schema": { "$ref": "#/components/schemas/Error" } } }
+            }),
+        );
+
+        let mut op = json!({
+            "summary": operation.summary,
+            "responses": responses,
+        });
+        if !parameters.is_empty() {
+            op["parameters"] = Value::Array(parameters);
+        }
+        if let Some(body) = operation.body {
+            op["requestBody"] = json!({
+                "required": true,
+                "content": { "application/json": { "schema": transformed(&mut self.input, body) } }
+            });
+        }
+
+        let path = self
+            .paths
+            .entry(operation.path.to_string())
+            .or_insert_with(|| json!({}));
+        path[operation.method] = op;
+    }
+}
+
+/// Names of the `{name}` segments of an axum path
+fn path_param_names(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/')
+        .filter_map(|s| s.strip_prefix('{')?.strip_suffix('}'))
+        .filter(|s| !s.starts_with('*'))
+}
+
+/// Schema of `schema_fn` with the generator's OpenAPI transforms applied
+fn transformed(generator: &mut SchemaGenerator, schema_fn: SchemaFn) -> Value {
+    let mut schema = schema_fn(generator);
+    for transform in generator.transforms_mut() {
+        transform.transform(&mut schema);
+    }
+    schema.to_value()
+}
+
+/// One query parameter per property of the query struct. The struct's own definition is
+/// dropped from the components since clients never send it as a whole.
+fn query_parameters(generator: &mut SchemaGenerator, schema_fn: SchemaFn) -> Vec<Value> {
+    let mut schema = transformed(generator, schema_fn);
+    if let Some(name) = schema
+        .get("$ref")
+        .and_then(Value::as_str)
+        .and_then(|r| r.rsplit('/').next())
+        .map(str::to_string)
+        && let Some(definition) = generator.definitions_mut().remove(&name)
+    {
+        let mut definition = Schema::try_from(definition).unwrap_or_default();
+        for transform in generator.transforms_mut() {
+            transform.transform(&mut definition);
+        }
+        schema = definition.to_value();
+    }
+
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|r| r.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+        return Vec::new();
+    };
+
+    properties
+        .iter()
+        .map(|(name, property)| {
+            let mut property = property.clone();
+            let description = property
+                .as_object_mut()
+                .and_then(|p| p.remove("description"));
+            let mut parameter = json!({
+                "name": name,
+                "in": "query",
+                "required": required.contains(&name.as_str()),
+                "schema": property,
+            });
+            if let Some(description) = description {
+                parameter["description"] = description;
+            }
+            parameter
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::{get, post};
+    use serde::{Deserialize, Serialize};
+
+    /// Filter for widgets
+    #[derive(Deserialize, JsonSchema)]
+    #[allow(dead_code)]
+    struct WidgetQuery {
+        /// Widget name
+        name: String,
+        limit: Option<u32>,
+    }
+
+    #[derive(Serialize, JsonSchema)]
+    struct Widget {
+        name: String,
+        parts: Vec<Part>,
+    }
+
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    struct Part {
+        serial: Option<String>,
+    }
+
+    fn document() -> Value {
+        let (_, document) = ApiRouter::<()>::new()
+            .route(
+                Operation::get("/widgets/{name}/{seq}", "Get a widget")
+                    .path_param::<u32>("seq", "Part number")
+                    .query::<WidgetQuery>()
+                    .response::<Vec<Widget>>(),
+                get(|| async {}),
+            )
+            .route(
+                Operation::post("/widgets/{name}/parts", "Add a part").body::<Part>(),
+                post(|| async {}),
+            )
+            .finish();
+        document
+    }
+
+    #[test]
+    fn test_parameters_and_responses() {
+        let document = document();
+        let get = &document["paths"]["/widgets/{name}/{seq}"]["get"];
+        let parameters = get["parameters"].as_array().unwrap();
+        let parameter = |name: &str| parameters.iter().find(|p| p["name"] == name).unwrap();
+
+        assert_eq!(parameter("name")["in"], "path");
+        assert_eq!(parameter("name")["schema"]["type"], "string");
+        assert_eq!(parameter("seq")["schema"]["type"], "integer");
+        assert_eq!(parameter("seq")["description"], "Part number");
+        assert_eq!(parameters.iter().filter(|p| p["name"] == "name").count(), 2);
+        let query_name = parameters
+            .iter()
+            .find(|p| p["name"] == "name" && p["in"] == "query")
+            .unwrap();
+        assert_eq!(query_name["required"], true);
+        assert_eq!(query_name["description"], "Widget name");
+        assert_eq!(parameter("limit")["required"], false);
+
+        let data =
+            &get["responses"]["200"]["content"]["application/json"]["schema"]["properties"]["data"];
+        assert_eq!(data["items"]["$ref"], "#/components/schemas/Widget");
+
+        let post = &document["paths"]["/widgets/{name}/parts"]["post"];
+        assert!(post["responses"]["204"].is_object());
+        assert_eq!(
+            post["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/Part"
+        );
+    }
+
+    #[test]
+    fn test_components() {
+        let document = document();
+        let schemas = document["components"]["schemas"].as_object().unwrap();
+
+        assert!(schemas.contains_key("Widget"));
+        assert!(schemas.contains_key("Part"));
+        assert!(schemas.contains_key("Error"));
+        assert!(!schemas.contains_key("WidgetQuery"));
+        assert_eq!(schemas["Part"]["properties"]["serial"]["nullable"], true);
+    }
+}
@@ -1,109 +1,103 @@
-use axum::Json;
+use std::sync::Arc;
+
+use axum::response::Html;
+use axum::{Extension, Json};
 use serde::Serialize;
+use serde_json::Value;
+
+/// The OpenAPI document built by `ApiRouter`, shared with the handlers below
+pub type OpenApiDocument = Arc<Value>;
 
 #[derive(Serialize)]
 pub struct ApiRoute {
-    pub method: &'static str,
-    pub path: &'static str,
-    pub description: &'static str,
+    pub method: String,
+    pub path: String,
+    pub description: String,
+}
+
+impl ApiRoute {
+    fn new(method: &str, path: &str, description: &str) -> Self {
+        Self {
+            method: method.to_string(),
+            path: path.to_string(),
+            description: description.to_string(),
+        }
+    }
+}
+
+/// One entry per operation in the document, with the query parameters spelled out
+fn documented_routes(document: &Value) -> Vec<ApiRoute> {
+    let Some(paths) = document["paths"].as_object() else {
+        return Vec::new();
+    };
+
+    let mut routes = Vec::new();
+    for (path, operations) in paths {
+        let Some(operations) = operations.as_object() else {
+            continue;
+        };
+        for (method, operation) in operations {
+            let query: Vec<String> = operation["parameters"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter(|p| p["in"] == "query")
+                .filter_map(|p| p["name"].as_str())
+                .map(|name| format!("{}=", name))
+                .collect();
+            let path = if query.is_empty() {
+                path.clone()
+            } else {
+                format!("{}?{}", path, query.join("&"))
+            };
+
+            routes.push(ApiRoute::new(
+                &method.to_uppercase(),
+                &path,
+                operation["summary"].as_str().unwrap_or_default(),
+            ));
+        }
+    }
+    routes
+}
+
+pub async fn api_index_handler(
+    Extension(document): Extension<OpenApiDocument>,
+) -> Json<Vec<ApiRoute>> {
+    let mut routes = vec![
+        ApiRoute::new("GET", "/api", "List all available API routes"),
+        ApiRoute::new(
+            "GET",
+            "/api/openapi.json",
+            "OpenAPI 3 specification of this API",
+        ),
+        ApiRoute::new("GET", "/api/docs", "Interactive API documentation"),
+    ];
+    routes.extend(documented_routes(&document));
+    Json(routes)
+}
+
+pub async fn openapi_handler(Extension(document): Extension<OpenApiDocument>) -> Json<Value> {
+    Json(document.as_ref().clone())
 }
 
-pub async fn api_index_handler() -> Json<Vec<ApiRoute>> {
-    Json(vec![
-        ApiRoute {
-            method: "GET",
-            path: "/api",
-            description: "List all available API routes",
-        },
-        ApiRoute {
-            method: "GET",
-            path: "/api/search?query=<query>&league=&year=&team=&content_type=&search_type=",
-            description: "Search across all papers using hybrid semantic+keyword search",
-        },
-        ApiRoute {
-            method: "GET",
-            path: "/api/papers?league=&year=&team=",
-            description: "List papers, optionally filtered by league, year, or team",
-        },
-        ApiRoute {
-            method: "GET",
-            path: "/api/papers/{paper_lyt}/toc",
-            description: "Get the table of contents for a paper",
-        },
-        ApiRoute {
-            method: "GET",
-            path: "/api/papers/{paper_lyt}/abstract",
-            description: "Get the abstract of a paper",
-        },
-        ApiRoute {
-            method: "GET",
-            path: "/api/papers/{paper_lyt}/references",
-            description: "Get the references/bibliography of a paper",
-        },
-        ApiRoute {
-            method: "GET",
-            path: "/api/papers/{paper_lyt}/info",
-            description: "Get paper metadata: title, authors, institutions, URLs",
-        },
-        ApiRoute {
-            method: "GET",
-            path: "/api/papers/{paper_lyt}/paragraph/{seq}",
-            description: "Get a specific paragraph by content sequence number",
-        },
-        ApiRoute {
-            method: "GET",
-            path: "/api/papers/{paper_lyt}/table/{seq}",
-            description: "Get a specific table by content sequence number",
-        },
-        ApiRoute {
-            method: "GET",
-            path: "/api/papers/{paper_lyt}/image/{seq}",
-            description: "Get a specific image by content sequence number",
-        },
-        ApiRoute {
-            method: "POST",
-            path: "/api/papers/{paper_lyt}/open",
-            description: "Track a paper open event (analytics)",
-        },
-        ApiRoute {
-            method: "POST",
-            path: "/api/papers/{paper_lyt}/pdf-open",
-            description: "Track a PDF open event (analytics)",
-        },
-        ApiRoute {
-            method: "GET",
-            path: "/api/teams",
-            description: "List all teams in the corpus",
-        },
-        ApiRoute {
-            method: "GET",
-            path: "/api/leagues",
-            description: "List all leagues",
-        },
-        ApiRoute {
-            method: "GET",
-            path: "/api/years?league=&team=",
-            description: "List all years, optionally filtered by league or team",
-        },
-        ApiRoute {
-            method: "POST",
-            path: "/api/suggestion",
-            description: "Submit a user suggestion or feedback message",
-        },
-        ApiRoute {
-            method: "GET",
-            path: "/api/registry/team/{name}",
-            description: "Get team metadata: GitHub, website, social links",
-        },
-        ApiRoute {
-            method: "POST",
-            path: "/api/registry/team",
-            description: "Update team metadata (requires team code or master password)",
-        },
-        ApiRoute {
-            method: "GET",
-            path: "/api/registry/league/{name}",
-            description: "Get league metadata: official site, GitHub org, rules, community links",
-        },
-    ])
+pub async fn api_docs_handler() -> Html<&'static str> {
+    Html(
+        r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="utf-8">
+  <title>TDP Search API</title>
+  <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5/swagger-ui.css">
+</head>
+<body>
+  <div id="swagger-ui"></div>
+  <script src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js"></script>
+  <script>
+    window.ui = SwaggerUIBundle({ url: "/api/openapi.json", dom_id: "#swagger-ui" });
+  </script>
+</body>
+</html>
+"##,
+    )
 }
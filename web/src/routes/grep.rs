@@ -0,0 +1,23 @@
+use axum::extract::{Query, State};
+use axum::Json;
+
+use crate::dto::ApiResponse;
+use crate::error::ApiError;
+use crate::state::AppState;
+use data_structures::intermediate::GrepMatch;
+
+pub async fn grep_handler(
+    State(state): State<AppState>,
+    Query(args): Query<api::grep::GrepArgs>,
+) -> Result<Json<ApiResponse<Vec<GrepMatch>>>, ApiError> {
+    let result = api::grep::grep(
+        state.metadata_client.clone(),
+        args,
+        &state.dispatcher,
+        event_processing::EventSource::Web,
+    )
+    .await
+    .map_err(ApiError::from)?;
+
+    Ok(Json(ApiResponse::new(result)))
+}
@@ -0,0 +1,22 @@
+use axum::Json;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use configuration::bootstrap::Services;
+use serde_json::json;
+
+/// Liveness: the process is up and serving requests
+pub async fn healthz_handler() -> impl IntoResponse {
+    Json(json!({ "status": "ok" }))
+}
+
+/// Readiness: 200 when every dependency check passes, 503 with the failing ones otherwise
+pub async fn readyz_handler(State(services): State<Services>) -> impl IntoResponse {
+    let readiness = configuration::health::readiness(&services).await;
+    let status = if readiness.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(readiness))
+}
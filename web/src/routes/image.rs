@@ -16,6 +16,7 @@ pub async fn get_image_handler(
     let result = api::get_image::get_image(
         state.metadata_client.clone(),
         args,
+        None,
         &state.dispatcher,
         event_processing::EventSource::Web,
     )
@@ -0,0 +1,69 @@
+use axum::body::Body;
+use axum::extract::{Path, Query, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::Response;
+use data_processing::thumbnail::{self, ThumbnailFormat};
+use serde::Deserialize;
+
+use super::tdps::resolve_tdps_file;
+use crate::files::serve_file;
+use crate::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct ImageQuery {
+    /// Maximum width in pixels, rounded up to a multiple of 64. Defaults to the thumbnail width.
+    pub w: Option<u32>,
+    /// webp (default), jpeg or png
+    pub format: Option<String>,
+}
+
+/// A figure from `/tdps/{paper_lyt}/{subpath}`, scaled down and re-encoded. Copies are made on
+/// the first request and served from the image cache afterwards. Formats the image decoders
+/// cannot read, such as SVG, are served as they are.
+pub async fn serve_image(
+    State(state): State<AppState>,
+    Path(path): Path<String>,
+    Query(query): Query<ImageQuery>,
+    headers: HeaderMap,
+) -> Result<Response<Body>, StatusCode> {
+    // Only files inside a paper's folder, not the markdown itself
+    if !path.contains('/') {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    let (canonical_root, source) = resolve_tdps_file(&state.tdps_markdown_root, &path)?;
+    if !thumbnail::is_supported(&source) {
+        return serve_file(&source, &headers, state.files.tdps_cache_control()).await;
+    }
+
+    let format = match query.format.as_deref() {
+        Some(format) => ThumbnailFormat::try_from(format).map_err(|_| StatusCode::BAD_REQUEST)?,
+        None => ThumbnailFormat::Webp,
+    };
+    let files = &state.files;
+    let width = thumbnail::snap_width(
+        query.w.unwrap_or(files.thumbnail_width()),
+        files.max_image_width(),
+    );
+    let relative = source
+        .strip_prefix(&canonical_root)
+        .map_err(|_| StatusCode::FORBIDDEN)?;
+    let cached = thumbnail::cache_path(
+        std::path::Path::new(files.image_cache_dir()),
+        relative,
+        width,
+        format,
+    );
+
+    let target = cached.clone();
+    tokio::task::spawn_blocking(move || {
+        thumbnail::ensure_thumbnail(&source, &target, width, format)
+    })
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .map_err(|e| {
+        tracing::warn!("Failed to resize {}: {:#}", path, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    serve_file(&cached, &headers, files.images_cache_control()).await
+}
@@ -0,0 +1,10 @@
+use axum::http::header;
+use axum::response::IntoResponse;
+
+/// Prometheus scrape endpoint
+pub async fn metrics_handler() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        configuration::metrics::render(),
+    )
+}
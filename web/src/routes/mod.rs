@@ -1,8 +1,12 @@
 mod abstract_text;
 mod api_index;
+mod grep;
+mod health;
 mod references;
 mod image;
+mod images;
 mod leagues;
+mod metrics;
 mod paper_info;
 mod papers;
 mod paragraph;
@@ -16,61 +20,123 @@ mod registry;
 mod teams;
 mod years;
 
+use std::sync::Arc;
+
 use axum::middleware;
 use axum::routing::{get, post};
-use axum::Router;
+use axum::{Extension, Router};
+use configuration::bootstrap::Services;
 use tower_http::cors::{Any, CorsLayer};
 use tower_http::services::{ServeDir, ServeFile};
 
+use crate::dto::ResultSetQuery;
+use crate::openapi::{ApiRouter, Operation};
 use crate::state::AppState;
 
-pub fn create_router(state: AppState) -> Router {
+pub fn create_router(state: AppState, services: Services) -> Router {
     let cors = CorsLayer::new()
         .allow_origin(Any)
         .allow_methods(Any)
         .allow_headers(Any);
 
-    // API routes
-    let api_routes = Router::new()
+    // API routes, documented in the OpenAPI document served at /api/openapi.json
+    let (api_routes, document) = documented_api_routes().finish();
+    let api_routes = api_routes
         .route("/api", get(api_index::api_index_handler))
-        .route("/api/search", get(search::search_handler))
-        .route("/api/papers", get(papers::list_papers_handler))
-        .route("/api/papers/{id}/open", post(papers::paper_open_handler))
-        .route("/api/papers/{id}/pdf-open", post(papers::pdf_open_handler))
-        .route("/api/papers/{id}/toc", get(table_of_contents::get_table_of_contents_handler))
-        .route("/api/papers/{id}/paragraph/{seq}", get(paragraph::get_paragraph_handler))
-        .route("/api/papers/{id}/table/{seq}", get(table::get_table_handler))
-        .route("/api/papers/{id}/image/{seq}", get(image::get_image_handler))
-        .route("/api/papers/{id}/abstract", get(abstract_text::get_abstract_handler))
-        .route("/api/papers/{id}/references", get(references::get_references_handler))
-        .route("/api/papers/{id}/info", get(paper_info::get_paper_info_handler))
-        .route("/api/teams", get(teams::list_teams_handler))
-        .route("/api/leagues", get(leagues::list_leagues_handler))
-        .route("/api/years", get(years::list_years_handler))
-        .route("/api/suggestion", post(suggestion::submit_suggestion_handler))
-        .route("/api/registry/team/{name}", get(registry::get_team_info_handler))
-        .route("/api/registry/team", post(registry::update_team_info_handler))
-        .route("/api/registry/league/{name}", get(registry::get_league_info_handler))
+        .route("/api/openapi.json", get(api_index::openapi_handler))
+        .route("/api/docs", get(api_index::api_docs_handler))
+        .layer(Extension(Arc::new(document)))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            crate::middleware::rate_limit,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            crate::middleware::crawler_policy,
+        ))
         .layer(middleware::from_fn_with_state(
             state.clone(),
             crate::middleware::activity_logging,
         ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            crate::middleware::classify_client,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            crate::middleware::api_key_auth,
+        ))
         .with_state(state.clone());
 
-    // TDP file routes (no activity logging, static file serving)
+    // TDP file and resized figure routes (no activity logging, static file serving)
     let tdps_routes = Router::new()
         .route("/tdps/{*path}", get(tdps::serve_tdps_file))
+        .route("/images/{*path}", get(images::serve_image))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            crate::middleware::rate_limit,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            crate::middleware::crawler_policy,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            crate::middleware::classify_client,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            crate::middleware::api_key_auth,
+        ))
         .with_state(state.clone());
 
     // PDF file routes (no activity logging, static file serving)
     let pdfs_routes = Router::new()
         .route("/pdfs/{*path}", get(pdfs::serve_pdf_file))
-        .with_state(state);
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            crate::middleware::rate_limit,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            crate::middleware::crawler_policy,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            crate::middleware::classify_client,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            crate::middleware::api_key_auth,
+        ))
+        .with_state(state.clone());
+
+    // Probes, outside rate limiting and API key checks
+    let health_routes = Router::new()
+        .route("/healthz", get(health::healthz_handler))
+        .route("/readyz", get(health::readyz_handler))
+        .with_state(services);
 
     // Serve static frontend files with SPA fallback
     // If a file exists, serve it; otherwise serve index.html for client-side routing
+    // Classified too, so vulnerability probes for paths like /.env are caught
     let static_files = ServeDir::new("static")
         .not_found_service(ServeFile::new("static/index.html"));
+    let static_routes = Router::new()
+        .fallback_service(static_files)
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            crate::middleware::crawler_policy,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            crate::middleware::classify_client,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            crate::middleware::api_key_auth,
+        ))
+        .with_state(state);
 
     // Combine API routes with static file serving
     // API routes take precedence, then static files
@@ -78,6 +144,167 @@ pub fn create_router(state: AppState) -> Router {
         .merge(api_routes)
         .merge(tdps_routes)
         .merge(pdfs_routes)
-        .fallback_service(static_files)
+        .merge(health_routes)
+        .route("/metrics", get(metrics::metrics_handler))
+        .merge(static_routes)
+        .layer(middleware::from_fn(crate::middleware::track_metrics))
+        .layer(middleware::from_fn(crate::middleware::trace_request))
         .layer(cors)
 }
+
+const PAPER_LYT: &str = "Paper identifier, e.g. 'soccer_smallsize__2024__RoboTeam_Twente'";
+const CONTENT_SEQ: &str = "Content sequence number, as listed in the table of contents";
+
+fn documented_api_routes() -> ApiRouter<AppState> {
+    ApiRouter::new()
+        .route(
+            Operation::get("/api/search", "Search across all papers using hybrid semantic+keyword search")
+                .query::<api::search::SearchArgs>()
+                .response::<data_structures::intermediate::SearchResult>(),
+            get(search::search_handler),
+        )
+        .route(
+            Operation::get("/api/grep", "Find exact text or regex matches (e.g. part numbers) with paper_lyt, content_seq and character offsets")
+                .query::<api::grep::GrepArgs>()
+                .response::<Vec<data_structures::intermediate::GrepMatch>>(),
+            get(grep::grep_handler),
+        )
+        .route(
+            Operation::get("/api/papers", "List all papers")
+                .response::<Vec<data_structures::file::TDPName>>(),
+            get(papers::list_papers_handler),
+        )
+        .route(
+            Operation::post("/api/papers/{paper_lyt}/open", "Track a paper open event (analytics)")
+                .path_param::<String>("paper_lyt", PAPER_LYT)
+                .query::<ResultSetQuery>(),
+            post(papers::paper_open_handler),
+        )
+        .route(
+            Operation::post("/api/papers/{paper_lyt}/pdf-open", "Track a PDF open event (analytics)")
+                .path_param::<String>("paper_lyt", PAPER_LYT)
+                .query::<ResultSetQuery>(),
+            post(papers::pdf_open_handler),
+        )
+        .route(
+            Operation::get("/api/papers/{paper_lyt}/toc", "Get the table of contents for a paper")
+                .path_param::<String>("paper_lyt", PAPER_LYT)
+                .response::<String>(),
+            get(table_of_contents::get_table_of_contents_handler),
+        )
+        .route(
+            Operation::get("/api/papers/{paper_lyt}/paragraph/{seq}", "Get a specific paragraph by content sequence number")
+                .path_param::<String>("paper_lyt", PAPER_LYT)
+                .path_param::<u32>("seq", CONTENT_SEQ)
+                .response::<String>(),
+            get(paragraph::get_paragraph_handler),
+        )
+        .route(
+            Operation::get("/api/papers/{paper_lyt}/table/{seq}", "Get a specific table by content sequence number")
+                .path_param::<String>("paper_lyt", PAPER_LYT)
+                .path_param::<u32>("seq", CONTENT_SEQ)
+                .response::<String>(),
+            get(table::get_table_handler),
+        )
+        .route(
+            Operation::get("/api/papers/{paper_lyt}/image/{seq}", "Get a specific image by content sequence number")
+                .path_param::<String>("paper_lyt", PAPER_LYT)
+                .path_param::<u32>("seq", CONTENT_SEQ)
+                .response::<String>(),
+            get(image::get_image_handler),
+        )
+        .route(
+            Operation::get("/api/papers/{paper_lyt}/abstract", "Get the abstract of a paper")
+                .path_param::<String>("paper_lyt", PAPER_LYT)
+                .response::<String>(),
+            get(abstract_text::get_abstract_handler),
+        )
+        .route(
+            Operation::get("/api/papers/{paper_lyt}/references", "Get the references/bibliography of a paper")
+                .path_param::<String>("paper_lyt", PAPER_LYT)
+                .response::<Vec<String>>(),
+            get(references::get_references_handler),
+        )
+        .route(
+            Operation::get("/api/papers/{paper_lyt}/info", "Get paper metadata: title, authors, institutions, URLs")
+                .path_param::<String>("paper_lyt", PAPER_LYT)
+                .response::<data_structures::content::PaperInfo>(),
+            get(paper_info::get_paper_info_handler),
+        )
+        .route(
+            Operation::get("/api/teams", "List all teams in the corpus")
+                .query::<api::list_teams::ListTeamsArgs>()
+                .response::<Vec<data_structures::file::TeamName>>(),
+            get(teams::list_teams_handler),
+        )
+        .route(
+            Operation::get("/api/leagues", "List all leagues")
+                .response::<Vec<data_structures::file::League>>(),
+            get(leagues::list_leagues_handler),
+        )
+        .route(
+            Operation::get("/api/years", "List all years")
+                .response::<Vec<u32>>(),
+            get(years::list_years_handler),
+        )
+        .route(
+            Operation::post("/api/suggestion", "Submit a user suggestion or feedback message")
+                .body::<api::suggestion::SuggestionArgs>()
+                .response::<String>(),
+            post(suggestion::submit_suggestion_handler),
+        )
+        .route(
+            Operation::get("/api/registry/team/{name}", "Get team metadata: GitHub, website, social links")
+                .path_param::<String>("name", "Team name")
+                .response::<Vec<data_access::registry::RegistryEntry>>(),
+            get(registry::get_team_info_handler),
+        )
+        .route(
+            Operation::post("/api/registry/team", "Update team metadata (requires team code or master password)")
+                .body::<api::update_team_info::UpdateTeamInfoArgs>()
+                .response::<String>(),
+            post(registry::update_team_info_handler),
+        )
+        .route(
+            Operation::get("/api/registry/league/{name}", "Get league metadata: official site, GitHub org, rules, community links")
+                .path_param::<String>("name", "League name")
+                .response::<Vec<data_access::registry::RegistryEntry>>(),
+            get(registry::get_league_info_handler),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    fn collect_refs<'a>(value: &'a Value, refs: &mut Vec<&'a str>) {
+        match value {
+            Value::Object(map) => {
+                if let Some(Value::String(r)) = map.get("$ref") {
+                    refs.push(r);
+                }
+                map.values().for_each(|v| collect_refs(v, refs));
+            }
+            Value::Array(items) => items.iter().for_each(|v| collect_refs(v, refs)),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn every_schema_reference_resolves() {
+        let (_, document) = documented_api_routes().finish();
+        let schemas = document["components"]["schemas"].as_object().unwrap();
+
+        let mut refs = Vec::new();
+        collect_refs(&document, &mut refs);
+        assert!(!refs.is_empty());
+        for r in refs {
+            let name = r.strip_prefix("#/components/schemas/").unwrap();
+            assert!(schemas.contains_key(name), "unresolved reference {}", r);
+        }
+
+        let search = &document["paths"]["/api/search"]["get"]["parameters"];
+        assert!(search.as_array().unwrap().iter().any(|p| p["name"] == "query" && p["required"] == true));
+    }
+}
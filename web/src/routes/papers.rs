@@ -1,8 +1,8 @@
-use axum::extract::{Path, State};
+use axum::extract::{Path, Query, State};
 use axum::http::{HeaderMap, StatusCode};
 use axum::Json;
 
-use crate::dto::ApiResponse;
+use crate::dto::{ApiResponse, ResultSetQuery};
 use crate::error::ApiError;
 use crate::state::AppState;
 
@@ -24,6 +24,7 @@ pub async fn list_papers_handler(
 pub async fn paper_open_handler(
     State(state): State<AppState>,
     Path(paper_lyt): Path<String>,
+    Query(query): Query<ResultSetQuery>,
     headers: HeaderMap,
 ) -> StatusCode {
     let referrer = headers
@@ -36,6 +37,7 @@ pub async fn paper_open_handler(
         event_processing::Event::PaperOpen(event_processing::PaperOpenEvent {
             paper_id: paper_lyt,
             referrer,
+            result_set_id: query.result_set_id,
         }),
     );
 
@@ -45,11 +47,13 @@ pub async fn paper_open_handler(
 pub async fn pdf_open_handler(
     State(state): State<AppState>,
     Path(paper_lyt): Path<String>,
+    Query(query): Query<ResultSetQuery>,
 ) -> StatusCode {
     state.dispatcher.dispatch(
         event_processing::EventSource::Web,
         event_processing::Event::PdfOpen(event_processing::PdfOpenEvent {
             paper_id: paper_lyt,
+            result_set_id: query.result_set_id,
         }),
     );
 
@@ -1,14 +1,16 @@
 use axum::body::Body;
 use axum::extract::{Path, State};
-use axum::http::{HeaderValue, StatusCode, header};
+use axum::http::{HeaderMap, StatusCode};
 use axum::response::Response;
 use data_structures::file::TDPName;
 
+use crate::files::serve_file;
 use crate::state::AppState;
 
 pub async fn serve_pdf_file(
     State(state): State<AppState>,
     Path(path): Path<String>,
+    headers: HeaderMap,
 ) -> Result<Response<Body>, StatusCode> {
     // Strip .pdf extension
     let paper_lyt_str = path
@@ -43,19 +45,5 @@ pub async fn serve_pdf_file(
         return Err(StatusCode::FORBIDDEN);
     }
 
-    // Read and return PDF
-    let contents = tokio::fs::read(&canonical_file)
-        .await
-        .map_err(|_| StatusCode::NOT_FOUND)?;
-
-    let response = Response::builder()
-        .status(StatusCode::OK)
-        .header(
-            header::CONTENT_TYPE,
-            HeaderValue::from_static("application/pdf"),
-        )
-        .body(Body::from(contents))
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-
-    Ok(response)
+    serve_file(&canonical_file, &headers, state.files.pdf_cache_control()).await
 }
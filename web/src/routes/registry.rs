@@ -6,14 +6,6 @@ use crate::error::ApiError;
 use crate::state::AppState;
 use data_access::registry::RegistryEntry;
 
-fn map_api_error(e: api::error::ApiError) -> ApiError {
-    match e {
-        api::error::ApiError::Argument(_, _) => ApiError::bad_request(e.to_string()),
-        api::error::ApiError::Internal(_) => ApiError::internal_server_error(e.to_string()),
-        api::error::ApiError::Forbidden(_) => ApiError::forbidden(e.to_string()),
-    }
-}
-
 pub async fn get_team_info_handler(
     State(state): State<AppState>,
     Path(name): Path<String>,
@@ -30,7 +22,7 @@ pub async fn get_team_info_handler(
         event_processing::EventSource::Web,
     )
     .await
-    .map_err(map_api_error)?;
+    .map_err(ApiError::from)?;
 
     Ok(Json(ApiResponse::new(entries)))
 }
@@ -51,7 +43,7 @@ pub async fn get_league_info_handler(
         event_processing::EventSource::Web,
     )
     .await
-    .map_err(map_api_error)?;
+    .map_err(ApiError::from)?;
 
     Ok(Json(ApiResponse::new(entries)))
 }
@@ -70,7 +62,7 @@ pub async fn update_team_info_handler(
         event_processing::EventSource::Web,
     )
     .await
-    .map_err(map_api_error)?;
+    .map_err(ApiError::from)?;
 
     Ok(Json(ApiResponse::new(result)))
 }
@@ -16,7 +16,7 @@ pub async fn search_handler(
         event_processing::EventSource::Web,
     )
     .await
-    .map_err(|e| ApiError::internal_server_error(e.to_string()))?;
+    .map_err(ApiError::from)?;
 
     Ok(Json(ApiResponse::new(result)))
 }
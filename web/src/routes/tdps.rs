@@ -1,22 +1,38 @@
+use std::path::PathBuf;
+
 use axum::body::Body;
 use axum::extract::{Path, State};
-use axum::http::{HeaderValue, StatusCode, header};
+use axum::http::{HeaderMap, StatusCode};
 use axum::response::Response;
 use data_structures::file::TDPName;
 
+use crate::files::serve_file;
 use crate::state::AppState;
 
 pub async fn serve_tdps_file(
     State(state): State<AppState>,
     Path(path): Path<String>,
+    headers: HeaderMap,
 ) -> Result<Response<Body>, StatusCode> {
+    let (_, canonical_file) = resolve_tdps_file(&state.tdps_markdown_root, &path)?;
+
+    // Markdown and images in the paper folder get their type from the extension
+    serve_file(&canonical_file, &headers, state.files.tdps_cache_control()).await
+}
+
+/// Canonical paths of the TDP root and of the file a `/tdps/*` path points to, which is
+/// checked to lie under the root
+pub(super) fn resolve_tdps_file(
+    tdps_markdown_root: &str,
+    path: &str,
+) -> Result<(PathBuf, PathBuf), StatusCode> {
     // The path is either:
     //   {paper_lyt}.md          — the markdown file itself
     //   {paper_lyt}/{subpath}   — a file inside the paper's image folder
     let (paper_lyt_str, subpath) = if let Some((paper_lyt, sub)) = path.split_once('/') {
         (paper_lyt, Some(sub))
     } else {
-        (path.as_str(), None)
+        (path, None)
     };
 
     // Strip .md extension from paper_lyt if present (for the markdown file case)
@@ -29,7 +45,7 @@ pub async fn serve_tdps_file(
     let tdp_name: TDPName = TDPName::try_from(paper_lyt_for_parse).map_err(|_| StatusCode::BAD_REQUEST)?;
 
     // Build the filesystem path for the file
-    let root = std::path::Path::new(&state.tdps_markdown_root);
+    let root = std::path::Path::new(tdps_markdown_root);
 
     let league = &tdp_name.league;
     let year = tdp_name.year.to_string();
@@ -66,35 +82,5 @@ pub async fn serve_tdps_file(
         return Err(StatusCode::FORBIDDEN);
     }
 
-    // Read file contents
-    let contents = tokio::fs::read(&canonical_file)
-        .await
-        .map_err(|_| StatusCode::NOT_FOUND)?;
-
-    // Determine Content-Type from extension
-    let content_type = match canonical_file
-        .extension()
-        .and_then(|e| e.to_str())
-        .map(|e| e.to_lowercase())
-        .as_deref()
-    {
-        Some("md") => "text/markdown; charset=utf-8",
-        Some("jpeg") | Some("jpg") => "image/jpeg",
-        Some("png") => "image/png",
-        Some("gif") => "image/gif",
-        Some("webp") => "image/webp",
-        Some("svg") => "image/svg+xml",
-        _ => "application/octet-stream",
-    };
-
-    let content_type_value =
-        HeaderValue::from_str(content_type).unwrap_or_else(|_| HeaderValue::from_static("application/octet-stream"));
-
-    let response = Response::builder()
-        .status(StatusCode::OK)
-        .header(header::CONTENT_TYPE, content_type_value)
-        .body(Body::from(contents))
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-
-    Ok(response)
+    Ok((canonical_root, canonical_file))
 }
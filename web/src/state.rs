@@ -1,3 +1,7 @@
+use api::crawler::ClientClassifier;
+use api::rate_limit::RateLimiter;
+use configuration::FilesConfig;
+use data_access::api_keys::ApiKeyClient;
 use data_access::metadata::MetadataClient;
 use data_access::registry::RegistryClient;
 use data_processing::search::Searcher;
@@ -12,9 +16,14 @@ pub struct AppState {
     pub tdps_markdown_root: String,
     pub tdps_pdf_root: String,
     pub registry: Option<Arc<dyn RegistryClient + Send + Sync>>,
+    pub rate_limiter: Option<Arc<RateLimiter>>,
+    pub api_keys: Option<Arc<dyn ApiKeyClient + Send + Sync>>,
+    pub client_classifier: Arc<ClientClassifier>,
+    pub files: FilesConfig,
 }
 
 impl AppState {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         metadata_client: Arc<dyn MetadataClient + Send + Sync>,
         searcher: Arc<Searcher>,
@@ -22,6 +31,10 @@ impl AppState {
         tdps_markdown_root: String,
         tdps_pdf_root: String,
         registry: Option<Arc<dyn RegistryClient + Send + Sync>>,
+        rate_limiter: Option<Arc<RateLimiter>>,
+        api_keys: Option<Arc<dyn ApiKeyClient + Send + Sync>>,
+        client_classifier: Arc<ClientClassifier>,
+        files: FilesConfig,
     ) -> Self {
         Self {
             metadata_client,
@@ -30,6 +43,10 @@ impl AppState {
             tdps_markdown_root,
             tdps_pdf_root,
             registry,
+            rate_limiter,
+            api_keys,
+            client_classifier,
+            files,
         }
     }
 }